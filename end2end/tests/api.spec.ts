@@ -0,0 +1,375 @@
+import { test, expect, APIRequestContext } from "@playwright/test";
+
+// The API tests run against a local worker with a migrated database, see `pnpm dev`.
+const API_URL = process.env.API_URL ?? "http://localhost:8787/api/v1";
+
+/// Creates a game as a new client, so the tests don't share the create-game rate limit.
+async function createGame(request: APIRequestContext, data: object = {}) {
+  return request.post(`${API_URL}/game`, {
+    data,
+    headers: { "CF-Connecting-IP": `test-${Math.random()}` },
+  });
+}
+
+test("two games can be created one after another", async ({ request }) => {
+  const first = await createGame(request);
+  expect(first.status()).toBe(201);
+
+  const second = await createGame(request);
+  expect(second.status()).toBe(201);
+
+  expect((await first.json()).id).not.toBe((await second.json()).id);
+});
+
+/// Joins a game and returns the new player together with their session token.
+async function joinGame(request: APIRequestContext, gameId: string, data: object) {
+  const response = await request.post(`${API_URL}/game/${gameId}/players`, { data });
+  expect(response.status()).toBe(200);
+  return response.json();
+}
+
+test("a new game without players can be joined", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  expect(game.players).toHaveLength(0);
+
+  const alice = await joinGame(request, game.id, { name: "Alice" });
+  const bob = await joinGame(request, game.id, { name: "Bob" });
+
+  const players = await (await request.get(`${API_URL}/game/${game.id}/players`)).json();
+  expect(players.map((player: { id: string }) => player.id)).toEqual([alice.player.id, bob.player.id]);
+});
+
+test("a game is only shown to an authenticated player", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const { session_token } = await joinGame(request, game.id, { name: "Alice" });
+
+  expect((await request.get(`${API_URL}/game/${game.id}`)).status()).toBe(401);
+  expect((await request.get(`${API_URL}/game/${game.id}?player_id=x`)).status()).toBe(401);
+
+  const response = await request.get(`${API_URL}/game/${game.id}`, {
+    headers: { Authorization: `Bearer ${session_token}` },
+  });
+  expect(response.status()).toBe(200);
+});
+
+test("all hands of a game are only shown to administrators", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const { session_token } = await joinGame(request, game.id, { name: "Alice" });
+
+  const response = await request.get(`${API_URL}/game/${game.id}?full=true`, {
+    headers: { Authorization: `Bearer ${session_token}` },
+  });
+  expect(response.status()).toBe(403);
+});
+
+test("only the authenticated host sees all hands in the list of players", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const host = await joinGame(request, game.id, { name: "Alice" });
+  const guest = await joinGame(request, game.id, { name: "Bob" });
+
+  // the id of the host is public, sending it doesn't make a client the host
+  const spoofed = await request.get(`${API_URL}/game/${game.id}/players?full=true&player_id=${host.player.id}`);
+  expect(spoofed.status()).toBe(403);
+
+  const asGuest = await request.get(`${API_URL}/game/${game.id}/players?full=true`, {
+    headers: { Authorization: `Bearer ${guest.session_token}` },
+  });
+  expect(asGuest.status()).toBe(403);
+
+  const asHost = await request.get(`${API_URL}/game/${game.id}/players?full=true`, {
+    headers: { Authorization: `Bearer ${host.session_token}` },
+  });
+  expect(asHost.status()).toBe(200);
+});
+
+test("only the authenticated host can rename a game and the summary shows the title", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const host = await joinGame(request, game.id, { name: "Alice" });
+  const guest = await joinGame(request, game.id, { name: "Bob" });
+
+  const spoofed = await request.patch(`${API_URL}/game/${game.id}`, {
+    data: { player_id: host.player.id, title: "Spoofed" },
+    headers: { Authorization: `Bearer ${guest.session_token}` },
+  });
+  expect(spoofed.status()).toBe(403);
+
+  const renamed = await request.patch(`${API_URL}/game/${game.id}`, {
+    data: { title: "Friday night" },
+    headers: { Authorization: `Bearer ${host.session_token}` },
+  });
+  expect(renamed.status()).toBe(200);
+
+  const summary = await (await request.get(`${API_URL}/game/${game.id}/summary`)).json();
+  expect(summary.title).toBe("Friday night");
+});
+
+test("a game with auto start deals once it is full and all players are ready", async ({ request }) => {
+  const game = await (await createGame(request, { settings: { auto_start_at: 2 } })).json();
+  await joinGame(request, game.id, { name: "Alice", ready: true });
+  const bob = await joinGame(request, game.id, { name: "Bob" });
+
+  const waiting = await (await request.get(`${API_URL}/game/${game.id}/summary`)).json();
+  expect(waiting.state).toBe("WaitingForPlayers");
+
+  const ready = await request.post(`${API_URL}/game/${game.id}/players/${bob.player.id}/ready`, {
+    headers: { Authorization: `Bearer ${bob.session_token}` },
+  });
+  expect(ready.status()).toBe(200);
+
+  const started = await (
+    await request.get(`${API_URL}/game/${game.id}`, { headers: { Authorization: `Bearer ${bob.session_token}` } })
+  ).json();
+  expect(started.state).toBe("InProgress");
+  expect(started.phase).toBe("Playing");
+});
+
+test("only the host can reset a round, which deals the whole deck again", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const host = await joinGame(request, game.id, { name: "Alice" });
+  const guest = await joinGame(request, game.id, { name: "Bob" });
+
+  expect((await request.post(`${API_URL}/game/${game.id}/reset`)).status()).toBe(401);
+  const asGuest = await request.post(`${API_URL}/game/${game.id}/reset`, {
+    headers: { Authorization: `Bearer ${guest.session_token}` },
+  });
+  expect(asGuest.status()).toBe(403);
+
+  const reset = await request.post(`${API_URL}/game/${game.id}/reset`, {
+    headers: { Authorization: `Bearer ${host.session_token}` },
+  });
+  expect(reset.status()).toBe(200);
+  const players = await reset.json();
+  const dealt = players.reduce((sum: number, player: { assigned_cards: unknown[] }) => sum + player.assigned_cards.length, 0);
+  expect(dealt).toBe(18);
+
+  const view = await (
+    await request.get(`${API_URL}/game/${game.id}`, { headers: { Authorization: `Bearer ${host.session_token}` } })
+  ).json();
+  expect(view.phase).toBe("Playing");
+  expect(view.card_to_play).toBe("King");
+  expect(view.which_player_turn).toBe(host.player.id);
+});
+
+test("a retried join with the same idempotency key returns the first player and token", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const headers = { "Idempotency-Key": `join-${Math.random()}-${"x".repeat(32)}` };
+
+  const [first, second] = await Promise.all([
+    request.post(`${API_URL}/game/${game.id}/players`, { data: { name: "Alice" }, headers }),
+    request.post(`${API_URL}/game/${game.id}/players`, { data: { name: "Alice" }, headers }),
+  ]);
+  expect(first.status()).toBe(200);
+  expect(second.status()).toBe(200);
+
+  const firstJoin = await first.json();
+  const secondJoin = await second.json();
+  expect(secondJoin.player.id).toBe(firstJoin.player.id);
+  expect(secondJoin.session_token).toBe(firstJoin.session_token);
+
+  // the token handed out first stays valid
+  const view = await request.get(`${API_URL}/game/${game.id}`, {
+    headers: { Authorization: `Bearer ${firstJoin.session_token}` },
+  });
+  expect(view.status()).toBe(200);
+  expect((await view.json()).players).toHaveLength(1);
+});
+
+test("joins without an idempotency key are never merged", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  await joinGame(request, game.id, { name: "Alice" });
+  await joinGame(request, game.id, { name: "Alice" });
+
+  const players = await (await request.get(`${API_URL}/game/${game.id}/players`)).json();
+  expect(players).toHaveLength(2);
+});
+
+test("a player who chatted in the lobby can leave the game", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const alice = await joinGame(request, game.id, { name: "Alice" });
+  const bob = await joinGame(request, game.id, { name: "Bob" });
+  const headers = { Authorization: `Bearer ${bob.session_token}` };
+
+  const sent = await request.post(`${API_URL}/chat/${game.chat_id}/messages`, {
+    data: { content: "bye", player_id: bob.player.id, sent_at: "", chat_id: game.chat_id },
+    headers,
+  });
+  expect(sent.status()).toBe(200);
+
+  const left = await request.delete(`${API_URL}/game/${game.id}/players/${bob.player.id}`, { headers });
+  expect(left.status()).toBe(200);
+
+  const players = await (await request.get(`${API_URL}/game/${game.id}/players`)).json();
+  expect(players.map((player: { id: string }) => player.id)).toEqual([alice.player.id]);
+});
+
+test("a private game can only be joined with its join code", async ({ request }) => {
+  const game = await (await createGame(request, { join_code: "secret" })).json();
+
+  const withoutCode = await request.post(`${API_URL}/game/${game.id}/players`, { data: { name: "Alice" } });
+  expect(withoutCode.status()).toBe(403);
+  const wrongCode = await request.post(`${API_URL}/game/${game.id}/players`, {
+    data: { name: "Alice", join_code: "guess" },
+  });
+  expect(wrongCode.status()).toBe(403);
+
+  await joinGame(request, game.id, { name: "Alice", join_code: "secret" });
+});
+
+test("the lobby lists public games but hides private ones", async ({ request }) => {
+  const publicGame = await (await createGame(request)).json();
+  await joinGame(request, publicGame.id, { name: "Alice" });
+  const emptyGame = await (await createGame(request)).json();
+  const privateGame = await (await createGame(request, { join_code: "secret" })).json();
+
+  const response = await request.get(`${API_URL}/games`);
+  expect(response.status()).toBe(200);
+  const ids = (await response.json()).map((game: { id: string }) => game.id);
+  expect(ids).toContain(publicGame.id);
+  expect(ids).toContain(emptyGame.id);
+  expect(ids).not.toContain(privateGame.id);
+});
+
+test("no claims can be submitted once a game has ended", async ({ request }) => {
+  const game = await (await createGame(request, { settings: { auto_start_at: 2 } })).json();
+  const alice = await joinGame(request, game.id, { name: "Alice", ready: true });
+  await joinGame(request, game.id, { name: "Bob", ready: true });
+  const headers = { Authorization: `Bearer ${alice.session_token}` };
+
+  const view = await (await request.get(`${API_URL}/game/${game.id}`, { headers })).json();
+  expect(view.state).toBe("InProgress");
+  const card = view.players.find((player: { id: string }) => player.id === alice.player.id).cards[0];
+
+  const ended = await request.post(`${API_URL}/game/${game.id}/ops`, {
+    data: [{ op: "SetState", state: "Ended" }],
+    headers,
+  });
+  expect(ended.status()).toBe(200);
+
+  const claim = await request.post(`${API_URL}/game/${game.id}/claims`, {
+    data: { id: "", created_by: "", announced_rank: view.card_to_play, number_of_cards: 1, cards: [card] },
+    headers,
+  });
+  expect(claim.status()).toBe(409);
+});
+
+test("no messages can be sent to the chat of an aborted game", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const alice = await joinGame(request, game.id, { name: "Alice" });
+  const headers = { Authorization: `Bearer ${alice.session_token}` };
+
+  const aborted = await request.post(`${API_URL}/game/${game.id}/ops`, {
+    data: [{ op: "SetState", state: "Aborted" }],
+    headers,
+  });
+  expect(aborted.status()).toBe(200);
+
+  const sent = await request.post(`${API_URL}/chat/${game.chat_id}/messages`, {
+    data: { content: "hello?", player_id: alice.player.id, sent_at: "", chat_id: game.chat_id },
+    headers,
+  });
+  expect(sent.status()).toBe(409);
+});
+
+test("a client can only create a limited number of games per window", async ({ request }) => {
+  const headers = { "CF-Connecting-IP": `test-${Math.random()}` };
+
+  // the worker of the tests uses the default limit of 5 games per minute, see `wrangler.toml`
+  for (let created = 0; created < 5; created++) {
+    expect((await request.post(`${API_URL}/game`, { data: {}, headers })).status()).toBe(201);
+  }
+  expect((await request.post(`${API_URL}/game`, { data: {}, headers })).status()).toBe(429);
+});
+
+test("a running game can only be rejoined by name with the session token of the player", async ({ request }) => {
+  const game = await (await createGame(request, { settings: { auto_start_at: 2 } })).json();
+  const quickJoin = (data: object, headers: Record<string, string> = {}) =>
+    request.post(`${API_URL}/game/${game.id}/quick-join`, { data, headers });
+
+  const alice = await (await quickJoin({ name: "Alice", ready: true })).json();
+  expect((await quickJoin({ name: "Bob", ready: true })).status()).toBe(200);
+
+  const withoutToken = await quickJoin({ name: "Alice" });
+  expect(withoutToken.ok()).toBe(false);
+
+  const withToken = await quickJoin({ name: "Alice" }, { Authorization: `Bearer ${alice.session_token}` });
+  expect(withToken.status()).toBe(200);
+  expect((await withToken.json()).player.id).toBe(alice.player.id);
+});
+
+test("a new game without players can be loaded with its children", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+
+  const response = await request.get(`${API_URL}/game/${game.id}/verify`);
+  expect(response.status()).toBe(200);
+  expect((await response.json()).is_consistent).toBe(true);
+});
+
+test("the cards of an open claim are only listed for the player who placed it", async ({ request }) => {
+  const game = await (await createGame(request, { settings: { auto_start_at: 2 } })).json();
+  const alice = await joinGame(request, game.id, { name: "Alice", ready: true });
+  const bob = await joinGame(request, game.id, { name: "Bob", ready: true });
+  const asAlice = { Authorization: `Bearer ${alice.session_token}` };
+  const asBob = { Authorization: `Bearer ${bob.session_token}` };
+
+  const view = await (await request.get(`${API_URL}/game/${game.id}`, { headers: asAlice })).json();
+  const card = view.players.find((player: { id: string }) => player.id === alice.player.id).cards[0];
+  const claim = await request.post(`${API_URL}/game/${game.id}/claims`, {
+    data: { id: "", created_by: "", announced_rank: view.card_to_play, number_of_cards: 1, cards: [card] },
+    headers: asAlice,
+  });
+  expect(claim.status()).toBe(200);
+
+  expect((await request.get(`${API_URL}/game/${game.id}/claims`)).status()).toBe(401);
+  const otherGame = await (await createGame(request)).json();
+  const outsider = await joinGame(request, otherGame.id, { name: "Carol" });
+  const asOutsider = await request.get(`${API_URL}/game/${game.id}/claims`, {
+    headers: { Authorization: `Bearer ${outsider.session_token}` },
+  });
+  expect(asOutsider.status()).toBe(403);
+
+  const ownClaims = await (await request.get(`${API_URL}/game/${game.id}/claims`, { headers: asAlice })).json();
+  expect(ownClaims[0].cards).toHaveLength(1);
+  const otherClaims = await (await request.get(`${API_URL}/game/${game.id}/claims`, { headers: asBob })).json();
+  expect(otherClaims[0].cards).toHaveLength(0);
+  expect(otherClaims[0].number_of_cards).toBe(1);
+});
+
+test("players can only list their own games", async ({ request }) => {
+  const game = await (await createGame(request)).json();
+  const alice = await joinGame(request, game.id, { name: "Alice" });
+  const bob = await joinGame(request, game.id, { name: "Bob" });
+  const url = `${API_URL}/player/${alice.player.id}/games`;
+
+  expect((await request.get(url)).status()).toBe(401);
+  const asBob = await request.get(url, { headers: { Authorization: `Bearer ${bob.session_token}` } });
+  expect(asBob.status()).toBe(403);
+
+  const asAlice = await request.get(url, { headers: { Authorization: `Bearer ${alice.session_token}` } });
+  expect(asAlice.status()).toBe(200);
+  expect((await asAlice.json()).map((summary: { game_id: string }) => summary.game_id)).toEqual([game.id]);
+});
+
+test("passing the turn with an operation closes the open claim and starts a new deadline", async ({ request }) => {
+  const game = await (await createGame(request, { settings: { auto_start_at: 2 } })).json();
+  const alice = await joinGame(request, game.id, { name: "Alice", ready: true });
+  const bob = await joinGame(request, game.id, { name: "Bob", ready: true });
+  const headers = { Authorization: `Bearer ${alice.session_token}` };
+
+  const view = await (await request.get(`${API_URL}/game/${game.id}`, { headers })).json();
+  const card = view.players.find((player: { id: string }) => player.id === alice.player.id).cards[0];
+  await request.post(`${API_URL}/game/${game.id}/claims`, {
+    data: { id: "", created_by: "", announced_rank: view.card_to_play, number_of_cards: 1, cards: [card] },
+    headers,
+  });
+
+  const response = await request.post(`${API_URL}/game/${game.id}/ops`, {
+    data: [{ op: "SetTurn", player_id: bob.player.id }],
+    headers,
+  });
+  expect(response.status()).toBe(200);
+  const updated = await response.json();
+  expect(updated.which_player_turn).toBe(bob.player.id);
+  expect(updated.current_claim_id).toBeNull();
+  expect(updated.challenge_deadline).toBeNull();
+  expect(updated.turn_deadline).not.toBe(view.turn_deadline);
+});
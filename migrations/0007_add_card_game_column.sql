@@ -0,0 +1 @@
+ALTER TABLE cards ADD COLUMN game_id TEXT REFERENCES games(id);
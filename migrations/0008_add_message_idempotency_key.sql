@@ -0,0 +1,3 @@
+ALTER TABLE chat_messages ADD COLUMN idempotency_key TEXT;
+
+CREATE UNIQUE INDEX chat_messages_idempotency_key ON chat_messages (chat_id, idempotency_key);
@@ -0,0 +1 @@
+ALTER TABLE games ADD COLUMN turn_deadline TIMESTAMP;
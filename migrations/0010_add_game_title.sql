@@ -0,0 +1,3 @@
+ALTER TABLE games ADD COLUMN title TEXT NOT NULL DEFAULT '';
+
+UPDATE games SET title = 'Game ' || substr(id, 1, 8) WHERE title = '';
@@ -0,0 +1,2 @@
+ALTER TABLE claims ADD COLUMN outcome TEXT NOT NULL DEFAULT 'Unchallenged';
+ALTER TABLE claims ADD COLUMN challenged_by TEXT REFERENCES players(id);
@@ -0,0 +1 @@
+ALTER TABLE chats ADD COLUMN max_chat_messages INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE claims ADD COLUMN announced_rank TEXT NOT NULL DEFAULT 'King';
@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN session_token_hash TEXT;
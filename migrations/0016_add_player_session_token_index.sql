@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS idx_players_session_token_hash ON players(session_token_hash);
@@ -0,0 +1 @@
+ALTER TABLE games ADD COLUMN phase TEXT NOT NULL DEFAULT 'Dealing';
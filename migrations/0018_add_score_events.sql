@@ -0,0 +1,13 @@
+CREATE TABLE score_events (
+  id TEXT PRIMARY KEY,
+  game_id TEXT NOT NULL,
+  player_id TEXT NOT NULL,
+  round_number INTEGER NOT NULL,
+  delta INTEGER NOT NULL,
+  reason TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(game_id) REFERENCES games(id),
+  FOREIGN KEY(player_id) REFERENCES players(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_score_events_game_id ON score_events(game_id);
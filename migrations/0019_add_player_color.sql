@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN color TEXT NOT NULL DEFAULT 'Red';
@@ -0,0 +1,6 @@
+CREATE TABLE rate_limits (
+  key TEXT NOT NULL,
+  window_start INTEGER NOT NULL,
+  hits INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY (key, window_start)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE message_reactions (
+  message_id TEXT NOT NULL,
+  player_id TEXT NOT NULL,
+  emoji TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (message_id, player_id, emoji),
+  FOREIGN KEY(message_id) REFERENCES chat_messages(id) ON DELETE CASCADE,
+  FOREIGN KEY(player_id) REFERENCES players(id) ON DELETE CASCADE
+);
@@ -0,0 +1,67 @@
+-- Every change of a game, its players, cards, claims or chat increments the sequence of the game,
+-- so clients can detect missed updates. The triggers keep it up to date for every write path.
+ALTER TABLE games ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+
+CREATE TRIGGER IF NOT EXISTS trg_games_sequence AFTER UPDATE ON games
+  WHEN NEW.sequence = OLD.sequence
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_players_insert_sequence AFTER INSERT ON players
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+-- heartbeats and session tokens don't change the game
+CREATE TRIGGER IF NOT EXISTS trg_players_update_sequence AFTER UPDATE OF name, score, color, forfeited_at ON players
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_players_delete_sequence AFTER DELETE ON players
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = OLD.game_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_cards_update_sequence AFTER UPDATE OF player_id, claim_id ON cards
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_claims_insert_sequence AFTER INSERT ON claims
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_claims_update_sequence AFTER UPDATE ON claims
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_claims_delete_sequence AFTER DELETE ON claims
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = OLD.game_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_chat_messages_insert_sequence AFTER INSERT ON chat_messages
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = (SELECT game_id FROM chats WHERE id = NEW.chat_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_chat_messages_delete_sequence AFTER DELETE ON chat_messages
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = (SELECT game_id FROM chats WHERE id = OLD.chat_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_message_reactions_insert_sequence AFTER INSERT ON message_reactions
+BEGIN
+  UPDATE games SET sequence = sequence + 1
+    WHERE id = (SELECT chats.game_id FROM chat_messages JOIN chats ON chats.id = chat_messages.chat_id WHERE chat_messages.id = NEW.message_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_message_reactions_delete_sequence AFTER DELETE ON message_reactions
+BEGIN
+  UPDATE games SET sequence = sequence + 1
+    WHERE id = (SELECT chats.game_id FROM chat_messages JOIN chats ON chats.id = chat_messages.chat_id WHERE chat_messages.id = OLD.message_id);
+END;
@@ -0,0 +1,10 @@
+CREATE TABLE read_receipts (
+  chat_id TEXT NOT NULL,
+  player_id TEXT NOT NULL,
+  last_read_message_id TEXT NOT NULL,
+  last_read_sent_at TEXT NOT NULL,
+  updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (chat_id, player_id),
+  FOREIGN KEY(chat_id) REFERENCES chats(id) ON DELETE CASCADE,
+  FOREIGN KEY(player_id) REFERENCES players(id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- Version of the schema, checked by the worker on startup. Every following migration has to
+-- update it and the `EXPECTED_SCHEMA_VERSION` constant of the worker.
+CREATE TABLE schema_version (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  version INTEGER NOT NULL
+);
+
+INSERT INTO schema_version (id, version) VALUES (1, 27);
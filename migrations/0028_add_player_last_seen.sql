@@ -0,0 +1,3 @@
+ALTER TABLE players ADD COLUMN last_seen TEXT;
+
+UPDATE schema_version SET version = 28 WHERE id = 1;
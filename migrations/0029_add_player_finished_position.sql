@@ -0,0 +1,8 @@
+ALTER TABLE players ADD COLUMN finished_position INTEGER;
+
+CREATE TRIGGER IF NOT EXISTS trg_players_finished_sequence AFTER UPDATE OF finished_position ON players
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+UPDATE schema_version SET version = 29 WHERE id = 1;
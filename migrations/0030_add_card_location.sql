@@ -0,0 +1,25 @@
+-- The location of a card used to be derived from its player_id and claim_id columns. It's stored
+-- explicitly now, cards of older claims are considered part of the pile.
+ALTER TABLE cards ADD COLUMN location TEXT NOT NULL DEFAULT 'Deck';
+
+UPDATE cards SET location = CASE
+    WHEN claim_id IS NOT NULL THEN 'Pile'
+    WHEN player_id IS NOT NULL THEN 'Hand'
+    ELSE 'Deck'
+  END;
+
+-- the latest claim of every game can still be challenged
+UPDATE cards SET location = 'Claim'
+  WHERE claim_id IN (
+    SELECT claims.id FROM claims
+      WHERE claims.rowid = (SELECT MAX(latest.rowid) FROM claims AS latest WHERE latest.game_id = claims.game_id)
+  );
+
+DROP TRIGGER IF EXISTS trg_cards_update_sequence;
+
+CREATE TRIGGER IF NOT EXISTS trg_cards_update_sequence AFTER UPDATE OF player_id, claim_id, location ON cards
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.game_id;
+END;
+
+UPDATE schema_version SET version = 30 WHERE id = 1;
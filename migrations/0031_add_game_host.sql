@@ -0,0 +1,9 @@
+-- The host used to be the player who joined a game first. It's stored now, so it can be passed on
+-- to another player.
+ALTER TABLE games ADD COLUMN host_id TEXT;
+
+UPDATE games SET host_id = (
+    SELECT players.id FROM players WHERE players.game_id = games.id ORDER BY players.joined_at ASC LIMIT 1
+  );
+
+UPDATE schema_version SET version = 31 WHERE id = 1;
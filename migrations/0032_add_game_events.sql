@@ -0,0 +1,121 @@
+-- Log of everything that happened in a game, so finished games can be replayed step by step. The
+-- events are numbered per game and recorded by triggers, so every write path is covered.
+CREATE TABLE game_events (
+  game_id TEXT NOT NULL,
+  sequence INTEGER NOT NULL,
+  kind TEXT NOT NULL,
+  player_id TEXT,
+  round_number INTEGER NOT NULL DEFAULT 0,
+  details TEXT NOT NULL DEFAULT '{}',
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY(game_id, sequence),
+  FOREIGN KEY(game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_player_joined AFTER INSERT ON players
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, player_id, round_number, details)
+    VALUES (
+      NEW.game_id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.game_id),
+      'PlayerJoined',
+      NEW.id,
+      COALESCE((SELECT round_number FROM games WHERE id = NEW.game_id), 0),
+      json_object('name', NEW.name)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_player_forfeited AFTER UPDATE OF forfeited_at ON players
+  WHEN NEW.forfeited_at IS NOT NULL AND OLD.forfeited_at IS NULL
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, player_id, round_number)
+    VALUES (
+      NEW.game_id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.game_id),
+      'PlayerForfeited',
+      NEW.id,
+      COALESCE((SELECT round_number FROM games WHERE id = NEW.game_id), 0)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_player_finished AFTER UPDATE OF finished_position ON players
+  WHEN NEW.finished_position IS NOT NULL AND OLD.finished_position IS NULL
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, player_id, round_number, details)
+    VALUES (
+      NEW.game_id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.game_id),
+      'PlayerFinished',
+      NEW.id,
+      COALESCE((SELECT round_number FROM games WHERE id = NEW.game_id), 0),
+      json_object('finished_position', NEW.finished_position)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_claim_placed AFTER INSERT ON claims
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, player_id, round_number, details)
+    VALUES (
+      NEW.game_id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.game_id),
+      'ClaimPlaced',
+      NEW.created_by,
+      COALESCE((SELECT round_number FROM games WHERE id = NEW.game_id), 0),
+      json_object('claim_id', NEW.id, 'announced_rank', NEW.announced_rank, 'number_of_cards', NEW.number_of_cards)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_claim_challenged AFTER UPDATE OF outcome ON claims
+  WHEN NEW.outcome != OLD.outcome
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, player_id, round_number, details)
+    VALUES (
+      NEW.game_id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.game_id),
+      'ClaimChallenged',
+      NEW.challenged_by,
+      COALESCE((SELECT round_number FROM games WHERE id = NEW.game_id), 0),
+      json_object('claim_id', NEW.id, 'outcome', NEW.outcome)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_score_changed AFTER INSERT ON score_events
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, player_id, round_number, details)
+    VALUES (
+      NEW.game_id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.game_id),
+      'ScoreChanged',
+      NEW.player_id,
+      NEW.round_number,
+      json_object('delta', NEW.delta, 'reason', NEW.reason)
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_round_started AFTER UPDATE OF round_number ON games
+  WHEN NEW.round_number != OLD.round_number
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, round_number)
+    VALUES (
+      NEW.id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.id),
+      'RoundStarted',
+      NEW.round_number
+    );
+END;
+
+-- the state is stored as the index of the `GameState` enum
+CREATE TRIGGER IF NOT EXISTS trg_game_events_state_changed AFTER UPDATE OF state ON games
+  WHEN NEW.state != OLD.state
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, round_number, details)
+    VALUES (
+      NEW.id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.id),
+      'StateChanged',
+      NEW.round_number,
+      json_object('state', NEW.state)
+    );
+END;
+
+UPDATE schema_version SET version = 32 WHERE id = 1;
@@ -0,0 +1,23 @@
+-- Every game has exactly one chat. Duplicates are merged into the first chat of their game before
+-- the constraint is added.
+CREATE TEMP TABLE duplicate_chats AS
+  SELECT duplicate.id AS id, (
+      SELECT kept.id FROM chats AS kept WHERE kept.game_id = duplicate.game_id ORDER BY kept.rowid ASC LIMIT 1
+    ) AS kept_id
+  FROM chats AS duplicate
+  WHERE duplicate.rowid NOT IN (SELECT MIN(rowid) FROM chats GROUP BY game_id);
+
+UPDATE chat_messages SET chat_id = (SELECT kept_id FROM duplicate_chats WHERE duplicate_chats.id = chat_messages.chat_id)
+  WHERE chat_id IN (SELECT id FROM duplicate_chats);
+
+UPDATE chats SET number_of_messages = (SELECT COUNT(*) FROM chat_messages WHERE chat_messages.chat_id = chats.id)
+  WHERE id IN (SELECT kept_id FROM duplicate_chats);
+
+DELETE FROM read_receipts WHERE chat_id IN (SELECT id FROM duplicate_chats);
+DELETE FROM chats WHERE id IN (SELECT id FROM duplicate_chats);
+
+DROP TABLE duplicate_chats;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_chats_game_id ON chats(game_id);
+
+UPDATE schema_version SET version = 33 WHERE id = 1;
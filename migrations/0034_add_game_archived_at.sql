@@ -0,0 +1,5 @@
+-- Games can be archived instead of being removed, so a client can tell an archived game from one
+-- that never existed.
+ALTER TABLE games ADD COLUMN archived_at TEXT;
+
+UPDATE schema_version SET version = 34 WHERE id = 1;
@@ -0,0 +1,10 @@
+-- The claim that can still be challenged is stored with the game, so it doesn't have to be found by
+-- scanning the claims. It's only set while the challenge window of the claim is open.
+ALTER TABLE games ADD COLUMN current_claim_id TEXT REFERENCES claims(id) ON DELETE SET NULL;
+
+UPDATE games SET current_claim_id = (
+    SELECT claims.id FROM claims WHERE claims.game_id = games.id ORDER BY claims.rowid DESC LIMIT 1
+  )
+  WHERE challenge_deadline IS NOT NULL;
+
+UPDATE schema_version SET version = 35 WHERE id = 1;
@@ -0,0 +1,5 @@
+-- Private games can only be joined with a code and are hidden from the lobby. Only the SHA-256
+-- hash of the code is stored, like the session tokens.
+ALTER TABLE games ADD COLUMN join_code_hash TEXT;
+
+UPDATE schema_version SET version = 36 WHERE id = 1;
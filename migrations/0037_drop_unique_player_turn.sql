@@ -0,0 +1,81 @@
+-- `which_player_turn` was `UNIQUE`, so only one game could wait for players with an empty turn and
+-- every further game failed to be created. SQLite can't drop a constraint, so the table is
+-- rebuilt. Dropping the old table deletes the `game_events` by their cascade, so the games and
+-- their events are kept aside and restored; the triggers on `games` are dropped with it and
+-- created again.
+PRAGMA defer_foreign_keys = true;
+
+CREATE TEMP TABLE kept_games AS SELECT * FROM games;
+CREATE TEMP TABLE kept_game_events AS SELECT * FROM game_events;
+
+DROP TABLE games;
+
+CREATE TABLE games (
+  id TEXT PRIMARY KEY,
+  which_player_turn TEXT NOT NULL DEFAULT '',
+  state INTEGER NOT NULL DEFAULT 0,
+  started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  round_number INTEGER NOT NULL DEFAULT 0,
+  card_to_play INTEGER NOT NULL,
+  turn_deadline TIMESTAMP,
+  title TEXT NOT NULL DEFAULT '',
+  challenge_deadline TIMESTAMP,
+  phase TEXT NOT NULL DEFAULT 'Dealing',
+  settings TEXT,
+  sequence INTEGER NOT NULL DEFAULT 0,
+  deck_seed INTEGER,
+  host_id TEXT,
+  archived_at TEXT,
+  current_claim_id TEXT REFERENCES claims(id) ON DELETE SET NULL,
+  join_code_hash TEXT
+);
+
+-- inserting the games again resolves the deferred references of their players, cards, ...
+INSERT INTO games (
+    id, which_player_turn, state, started_at, round_number, card_to_play, turn_deadline, title,
+    challenge_deadline, phase, settings, sequence, deck_seed, host_id, archived_at,
+    current_claim_id, join_code_hash
+  )
+  SELECT
+    id, which_player_turn, state, started_at, round_number, card_to_play, turn_deadline, title,
+    challenge_deadline, phase, settings, sequence, deck_seed, host_id, archived_at,
+    current_claim_id, join_code_hash
+  FROM kept_games;
+DROP TABLE kept_games;
+
+INSERT OR IGNORE INTO game_events SELECT * FROM kept_game_events;
+DROP TABLE kept_game_events;
+
+CREATE TRIGGER IF NOT EXISTS trg_games_sequence AFTER UPDATE ON games
+  WHEN NEW.sequence = OLD.sequence
+BEGIN
+  UPDATE games SET sequence = sequence + 1 WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_game_events_round_started AFTER UPDATE OF round_number ON games
+  WHEN NEW.round_number != OLD.round_number
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, round_number)
+    VALUES (
+      NEW.id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.id),
+      'RoundStarted',
+      NEW.round_number
+    );
+END;
+
+-- the state is stored as the index of the `GameState` enum
+CREATE TRIGGER IF NOT EXISTS trg_game_events_state_changed AFTER UPDATE OF state ON games
+  WHEN NEW.state != OLD.state
+BEGIN
+  INSERT INTO game_events (game_id, sequence, kind, round_number, details)
+    VALUES (
+      NEW.id,
+      (SELECT COALESCE(MAX(sequence), 0) + 1 FROM game_events WHERE game_id = NEW.id),
+      'StateChanged',
+      NEW.round_number,
+      json_object('state', NEW.state)
+    );
+END;
+
+UPDATE schema_version SET version = 37 WHERE id = 1;
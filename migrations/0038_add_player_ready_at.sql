@@ -0,0 +1,5 @@
+-- Players of a waiting game mark themselves as ready, a game with `auto_start_at` only starts once
+-- all of them are.
+ALTER TABLE players ADD COLUMN ready_at TEXT;
+
+UPDATE schema_version SET version = 38 WHERE id = 1;
@@ -0,0 +1,8 @@
+-- A client can send an idempotency key with a join, a retried join with the same key returns the
+-- player of the first one. Only the hash of the key is stored, because the session token of the
+-- player is derived from it.
+ALTER TABLE players ADD COLUMN join_key_hash TEXT;
+
+CREATE UNIQUE INDEX players_join_key_hash ON players (game_id, join_key_hash);
+
+UPDATE schema_version SET version = 39 WHERE id = 1;
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+
+/// Represents where a card of a game currently is.
+///
+/// This enum defines the possible locations of a card:
+///
+/// - `Deck`: The card wasn't dealt yet or was collected for a new round.
+/// - `Hand`: The card is held by a player.
+/// - `Claim`: The card was placed in the latest claim, which can still be challenged.
+/// - `Pile`: The card is part of an older claim and lies in the central pile.
+///
+/// During a round a card can only move from the deck into a hand, from a hand into a claim,
+/// from a claim onto the pile and from the pile back into a hand, when a player picks up the
+/// stack.
+///
+/// It's stored with its variant name in the `location` column of the `cards` table, so it can
+/// be deserialized from a database row directly.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum CardLocation {
+    /// The card is in the deck of its game.
+    #[default]
+    Deck,
+    /// The card is held by a player.
+    Hand,
+    /// The card was placed in the latest claim.
+    Claim,
+    /// The card lies in the central pile.
+    Pile,
+}
+
+impl CardLocation {
+    /// Returns a string representation of the card location like it is stored in the database.
+    ///
+    /// # Returns
+    /// A string slice representing the card location.
+    pub fn as_str(&self) -> &str {
+        match self {
+            CardLocation::Deck => "Deck",
+            CardLocation::Hand => "Hand",
+            CardLocation::Claim => "Claim",
+            CardLocation::Pile => "Pile",
+        }
+    }
+
+    /// Checks whether a card can be moved from this location to the `next` one during a round.
+    pub fn can_move_to(&self, next: &CardLocation) -> bool {
+        matches!(
+            (self, next),
+            (CardLocation::Deck, CardLocation::Hand)
+                | (CardLocation::Hand, CardLocation::Claim)
+                | (CardLocation::Claim, CardLocation::Pile)
+                | (CardLocation::Pile, CardLocation::Hand)
+        )
+    }
+}
+
+// Implementing the `Display` trait for `CardLocation` allows for easy printing of the location.
+
+impl Display for CardLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -23,7 +23,7 @@ use serde::{Deserialize, Serialize};
 ///     _ => println!("Unknown card type."),
 /// }
 /// ```
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum CardType {
     /// King card type.
     King,
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+
+/// Represents what happened with a claim after it was placed on the stack.
+///
+/// This enum defines the possible outcomes of a claim:
+///
+/// - `Unchallenged`: Nobody doubted the claim.
+/// - `Truthful`: The claim was challenged but was true, the challenger picked up the stack.
+/// - `Bluff`: The claim was challenged and was a bluff, its creator picked up the stack.
+///
+/// It's stored with its variant name in the `outcome` column of the `claims` table, so it can be
+/// deserialized from a database row directly.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum ClaimOutcome {
+    /// Nobody challenged the claim.
+    #[default]
+    Unchallenged,
+    /// The claim was challenged and turned out to be true.
+    Truthful,
+    /// The claim was challenged and turned out to be a bluff.
+    Bluff,
+}
+
+impl ClaimOutcome {
+    /// Returns a string representation of the claim outcome like it is stored in the database.
+    ///
+    /// # Returns
+    /// A string slice representing the claim outcome.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ClaimOutcome::Unchallenged => "Unchallenged",
+            ClaimOutcome::Truthful => "Truthful",
+            ClaimOutcome::Bluff => "Bluff",
+        }
+    }
+
+    /// Indicates whether the claim was challenged by another player.
+    pub fn was_challenged(&self) -> bool {
+        *self != ClaimOutcome::Unchallenged
+    }
+}
+
+// Implementing the `Display` trait for `ClaimOutcome` allows for easy printing of the outcome.
+
+impl Display for ClaimOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
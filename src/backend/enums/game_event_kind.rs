@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+
+/// Represents what happened in a recorded event of a game.
+///
+/// This enum defines the possible kinds of events:
+///
+/// - `PlayerJoined`: A player joined the game.
+/// - `PlayerForfeited`: A player forfeited the current round.
+/// - `PlayerFinished`: A player emptied their hand and got a finishing position.
+/// - `ClaimPlaced`: A player placed a claim on the stack.
+/// - `ClaimChallenged`: A claim was challenged, the outcome is part of the details.
+/// - `ScoreChanged`: The score of a player changed.
+/// - `RoundStarted`: A new round was dealt.
+/// - `StateChanged`: The game moved on to another state.
+///
+/// It's stored with its variant name in the `kind` column of the `game_events` table, so it can
+/// be deserialized from a database row directly.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum GameEventKind {
+    /// A player joined the game.
+    PlayerJoined,
+    /// A player forfeited the current round.
+    PlayerForfeited,
+    /// A player emptied their hand.
+    PlayerFinished,
+    /// A player placed a claim.
+    ClaimPlaced,
+    /// A claim was challenged.
+    ClaimChallenged,
+    /// The score of a player changed.
+    ScoreChanged,
+    /// A new round was dealt.
+    RoundStarted,
+    /// The game moved on to another state.
+    StateChanged,
+}
+
+impl GameEventKind {
+    /// Returns a string representation of the event kind like it is stored in the database.
+    ///
+    /// # Returns
+    /// A string slice representing the event kind.
+    pub fn as_str(&self) -> &str {
+        match self {
+            GameEventKind::PlayerJoined => "PlayerJoined",
+            GameEventKind::PlayerForfeited => "PlayerForfeited",
+            GameEventKind::PlayerFinished => "PlayerFinished",
+            GameEventKind::ClaimPlaced => "ClaimPlaced",
+            GameEventKind::ClaimChallenged => "ClaimChallenged",
+            GameEventKind::ScoreChanged => "ScoreChanged",
+            GameEventKind::RoundStarted => "RoundStarted",
+            GameEventKind::StateChanged => "StateChanged",
+        }
+    }
+}
+
+// Implementing the `Display` trait for `GameEventKind` allows for easy printing of the kind.
+
+impl Display for GameEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+
+/// Represents the phase of the current round of a running game.
+///
+/// While the `GameState` describes the lifecycle of a game, the phase describes what happens
+/// inside a round while the game is `InProgress`:
+///
+/// - `Dealing`: The cards are shuffled and dealt to the players.
+/// - `Playing`: The players place claims and challenge them.
+/// - `Scoring`: The round is over and the scores are calculated.
+///
+/// The phases follow each other in this order, after `Scoring` the next round starts with
+/// `Dealing` again.
+///
+/// It's stored with its variant name in the `phase` column of the `games` table, so it can be
+/// deserialized from a database row directly.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum GamePhase {
+    /// The cards are dealt to the players.
+    #[default]
+    Dealing,
+    /// The players place and challenge claims.
+    Playing,
+    /// The round is over and gets scored.
+    Scoring,
+}
+
+impl GamePhase {
+    /// Returns a string representation of the game phase like it is stored in the database.
+    ///
+    /// # Returns
+    /// A string slice representing the game phase.
+    pub fn as_str(&self) -> &str {
+        match self {
+            GamePhase::Dealing => "Dealing",
+            GamePhase::Playing => "Playing",
+            GamePhase::Scoring => "Scoring",
+        }
+    }
+
+    /// Checks whether a round can move on from this phase to the `next` one.
+    ///
+    /// Only the following phase of a round is allowed; staying in the same phase isn't a
+    /// transition.
+    pub fn can_transition_to(&self, next: &GamePhase) -> bool {
+        matches!(
+            (self, next),
+            (GamePhase::Dealing, GamePhase::Playing)
+                | (GamePhase::Playing, GamePhase::Scoring)
+                | (GamePhase::Scoring, GamePhase::Dealing)
+        )
+    }
+}
+
+// Implementing the `Display` trait for `GamePhase` allows for easy printing of the game phase.
+
+impl Display for GamePhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GamePhase;
+
+    #[test]
+    fn a_round_moves_from_dealing_to_playing() {
+        assert!(GamePhase::Dealing.can_transition_to(&GamePhase::Playing));
+    }
+
+    #[test]
+    fn a_round_moves_from_playing_to_scoring() {
+        assert!(GamePhase::Playing.can_transition_to(&GamePhase::Scoring));
+    }
+
+    #[test]
+    fn the_next_round_moves_from_scoring_to_dealing() {
+        assert!(GamePhase::Scoring.can_transition_to(&GamePhase::Dealing));
+    }
+
+    #[test]
+    fn a_round_can_not_skip_or_repeat_a_phase() {
+        let phases = [GamePhase::Dealing, GamePhase::Playing, GamePhase::Scoring];
+        for current in &phases {
+            let allowed = phases.iter().filter(|next| current.can_transition_to(next)).count();
+            assert_eq!(allowed, 1, "{} must have exactly one following phase", current);
+            assert!(!current.can_transition_to(current));
+        }
+        assert!(!GamePhase::Dealing.can_transition_to(&GamePhase::Scoring));
+        assert!(!GamePhase::Playing.can_transition_to(&GamePhase::Dealing));
+        assert!(!GamePhase::Scoring.can_transition_to(&GamePhase::Playing));
+    }
+}
@@ -10,10 +10,11 @@ use std::fmt::{Debug, Display};
 /// - `Ended`: The game has concluded.
 /// - `WaitingForPlayers`: The game is waiting for players to join.
 /// - `Starting`: The game is in the process of starting, preparing for the first turn.
+/// - `Aborted`: The game was cancelled before it could be finished regularly.
 ///
 /// Each variant represents a distinct phase in the lifecycle of a game, allowing for clear
 /// management and transitions between states.
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum GameState {
     /// The game is currently in progress.
     InProgress,
@@ -23,6 +24,8 @@ pub enum GameState {
     WaitingForPlayers,
     /// The game is starting, preparing for the first turn.
     Starting,
+    /// The game was aborted and can't be continued.
+    Aborted,
 }
 
 impl GameState {
@@ -36,6 +39,7 @@ impl GameState {
             GameState::Ended => "Ended",
             GameState::WaitingForPlayers => "Waiting for Players",
             GameState::Starting => "Starting",
+            GameState::Aborted => "Aborted",
         }
     }
 
@@ -50,6 +54,7 @@ impl GameState {
     /// - `Ended` is mapped to index `1`.
     /// - `WaitingForPlayers` is mapped to index `2`.
     /// - `Starting` is mapped to index `3`.
+    /// - `Aborted` is mapped to index `4`.
     ///
     pub fn index(&self) -> usize {
         match self {
@@ -57,6 +62,7 @@ impl GameState {
             GameState::Ended => 1,
             GameState::WaitingForPlayers => 2,
             GameState::Starting => 3,
+            GameState::Aborted => 4,
         }
     }
 
@@ -64,7 +70,42 @@ impl GameState {
     ///
     /// Needs to be updated if the number of variants is modified!
     pub fn number_of_values() -> usize {
-        4
+        5
+    }
+
+    /// Checks whether the lifecycle of a game can move on from this state to the `next` one.
+    ///
+    /// A game is set up while it's `Starting` or `WaitingForPlayers`, then it's played and
+    /// finally it either `Ended` or was `Aborted`. Finished games can't change their state
+    /// anymore.
+    pub fn can_transition_to(&self, next: &GameState) -> bool {
+        matches!(
+            (self, next),
+            (GameState::Starting, GameState::WaitingForPlayers)
+                | (GameState::Starting, GameState::InProgress)
+                | (GameState::WaitingForPlayers, GameState::Starting)
+                | (GameState::WaitingForPlayers, GameState::InProgress)
+                | (GameState::InProgress, GameState::Ended)
+                | (
+                    GameState::Starting | GameState::WaitingForPlayers | GameState::InProgress,
+                    GameState::Aborted
+                )
+        )
+    }
+
+    /// Creates a new instance of `GameState` from a ***usize*** like it is stored in the `games`
+    /// table.
+    ///
+    /// Returns `None` when the number doesn't match any known index.
+    pub fn from_usize(num: usize) -> Option<Self> {
+        match num {
+            0 => Some(GameState::InProgress),
+            1 => Some(GameState::Ended),
+            2 => Some(GameState::WaitingForPlayers),
+            3 => Some(GameState::Starting),
+            4 => Some(GameState::Aborted),
+            _ => None,
+        }
     }
 }
 
@@ -75,3 +116,31 @@ impl Display for GameState {
         write!(f, "{}", self.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GameState;
+
+    #[test]
+    fn a_waiting_game_can_start_or_be_aborted() {
+        assert!(GameState::WaitingForPlayers.can_transition_to(&GameState::InProgress));
+        assert!(GameState::WaitingForPlayers.can_transition_to(&GameState::Aborted));
+        assert!(!GameState::WaitingForPlayers.can_transition_to(&GameState::Ended));
+    }
+
+    #[test]
+    fn a_running_game_can_end_or_be_aborted() {
+        assert!(GameState::InProgress.can_transition_to(&GameState::Ended));
+        assert!(GameState::InProgress.can_transition_to(&GameState::Aborted));
+        assert!(!GameState::InProgress.can_transition_to(&GameState::WaitingForPlayers));
+    }
+
+    #[test]
+    fn a_finished_game_keeps_its_state() {
+        for finished in [GameState::Ended, GameState::Aborted] {
+            assert!(!finished.can_transition_to(&GameState::InProgress));
+            assert!(!finished.can_transition_to(&GameState::WaitingForPlayers));
+        }
+        assert!(!GameState::Ended.can_transition_to(&GameState::Aborted));
+    }
+}
@@ -1,2 +1,7 @@
+pub mod card_location;
 pub mod card_types;
+pub mod claim_outcome;
+pub mod game_event_kind;
+pub mod game_phase;
 pub mod game_state;
+pub mod player_color;
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+
+/// Represents the color a player is shown with in the UI.
+///
+/// Every player of a game gets a different color when joining. A game can't have more players
+/// than the palette has colors (see `Config::max_players`), so a free color is always left.
+///
+/// It's stored with its variant name in the `color` column of the `players` table, so it can be
+/// deserialized from a database row directly.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum PlayerColor {
+    /// The first color that is handed out.
+    #[default]
+    Red,
+    /// A blue player.
+    Blue,
+    /// A green player.
+    Green,
+    /// A yellow player.
+    Yellow,
+    /// A purple player.
+    Purple,
+    /// An orange player.
+    Orange,
+    /// A teal player.
+    Teal,
+    /// A pink player.
+    Pink,
+}
+
+impl PlayerColor {
+    /// All colors in the order they are handed out.
+    pub const PALETTE: [PlayerColor; 8] = [
+        PlayerColor::Red,
+        PlayerColor::Blue,
+        PlayerColor::Green,
+        PlayerColor::Yellow,
+        PlayerColor::Purple,
+        PlayerColor::Orange,
+        PlayerColor::Teal,
+        PlayerColor::Pink,
+    ];
+
+    /// Returns a string representation of the color like it is stored in the database.
+    ///
+    /// # Returns
+    /// A string slice representing the color.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PlayerColor::Red => "Red",
+            PlayerColor::Blue => "Blue",
+            PlayerColor::Green => "Green",
+            PlayerColor::Yellow => "Yellow",
+            PlayerColor::Purple => "Purple",
+            PlayerColor::Orange => "Orange",
+            PlayerColor::Teal => "Teal",
+            PlayerColor::Pink => "Pink",
+        }
+    }
+
+    /// Chooses the color for a player joining a game.
+    ///
+    /// The `preferred` color is used when no other player of the game has it, otherwise the first
+    /// free color of the palette. Only when every color is taken, colors are repeated.
+    ///
+    /// # Arguments
+    /// - `preferred`: The color the client asked for, if any.
+    /// - `taken`: The colors of the players already in the game.
+    pub fn assign(preferred: Option<&PlayerColor>, taken: &[PlayerColor]) -> PlayerColor {
+        if let Some(preferred) = preferred {
+            if !taken.contains(preferred) {
+                return preferred.clone();
+            }
+        }
+
+        PlayerColor::PALETTE
+            .iter()
+            .find(|color| !taken.contains(color))
+            .cloned()
+            .unwrap_or_else(|| PlayerColor::PALETTE[taken.len() % PlayerColor::PALETTE.len()].clone())
+    }
+}
+
+// Implementing the `Display` trait for `PlayerColor` allows for easy printing of the color.
+
+impl Display for PlayerColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
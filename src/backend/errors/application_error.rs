@@ -5,19 +5,119 @@ cfg_if! {
         // TODO: Rework all methods / functions to return a error object that implements the 'ApplicationError' trait
         use std::{error, fmt};
 
-        use axum::response::IntoResponse;
-        use serde::Deserialize;
+        use axum::{
+            http::{header, StatusCode},
+            response::{IntoResponse, Response},
+        };
+        use serde::{Deserialize, Serialize};
+        use serde_json::{Map, Value};
+
+        // constants
+
+        /// Content type of error responses, see RFC 7807.
+        pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+        /// Problem type of errors that don't have a more specific type, see RFC 7807.
+        pub const DEFAULT_PROBLEM_TYPE: &str = "about:blank";
 
         /// Global error trait that is implement by custom error types
         ///
         /// It brings all necessary traits that a Rust error struct needs to implement.
         ///
         /// Specific adjustments are made at all indiviual definition.
-        pub trait ApplicationError: fmt::Display + error::Error + fmt::Debug + IntoResponse {}
+        pub trait ApplicationError: fmt::Display + error::Error + fmt::Debug + IntoResponse {
+            /// The http status code the error is answered with, when it's returned by a handler.
+            fn status_code(&self) -> StatusCode;
+
+            /// Additional members of the problem details, so clients can react to the error
+            /// without parsing the message. Empty by default.
+            fn problem_extensions(&self) -> Map<String, Value> {
+                Map::new()
+            }
+        }
 
         /// Error object trait for data types that should be logged in the console or in the error message.
         ///
         /// In some error types the causing object is inbetted in the error message.
         pub trait ErrorObject<'a>: Deserialize<'a> + fmt::Display + fmt::Debug {}
+
+        /// Body of an error response in the 'application/problem+json' format of RFC 7807.
+        ///
+        /// # Props
+        ///
+        /// - `problem_type` -> URI identifying the kind of problem, serialized as `type`
+        /// - `title` -> Short summary of the problem, the reason phrase of the status code
+        /// - `status` -> Http status code of the response
+        /// - `detail` -> Explanation of this occurrence of the problem
+        /// - `extensions` -> Additional members of specific errors, serialized next to the others
+        #[derive(Serialize, Debug)]
+        pub struct ProblemDetails {
+            /// URI identifying the kind of problem
+            #[serde(rename = "type")]
+            pub problem_type: String,
+            /// Short summary of the problem
+            pub title: String,
+            /// Http status code of the response
+            pub status: u16,
+            /// Explanation of this occurrence of the problem
+            pub detail: String,
+            /// Additional members of specific errors
+            #[serde(flatten)]
+            pub extensions: Map<String, Value>,
+        }
+
+        impl ProblemDetails {
+            /// Creates the problem details for a status code and a detailed description.
+            pub fn new(status: StatusCode, detail: String) -> Self {
+                ProblemDetails {
+                    problem_type: DEFAULT_PROBLEM_TYPE.to_string(),
+                    title: status.canonical_reason().unwrap_or("Unknown Error").to_string(),
+                    status: status.as_u16(),
+                    detail,
+                    extensions: Map::new(),
+                }
+            }
+        }
+
+        impl IntoResponse for ProblemDetails {
+            /// Serializes the problem details and sets the 'application/problem+json' content type.
+            fn into_response(self) -> Response {
+                let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                let body = match serde_json::to_string(&self) {
+                    Ok(body) => body,
+                    Err(err) => format!("{{\"detail\": \"{}\"}}", err),
+                };
+
+                (status, [(header::CONTENT_TYPE, PROBLEM_JSON_CONTENT_TYPE)], body).into_response()
+            }
+        }
+
+        /// Converts any `ApplicationError` into a 'application/problem+json' response with the
+        /// status code of the error.
+        ///
+        /// Used by the `IntoResponse` implementations of all error types.
+        pub fn problem_response<E: ApplicationError + ?Sized>(error: &E) -> Response {
+            let mut problem = ProblemDetails::new(error.status_code(), error.to_string());
+            problem.extensions = error.problem_extensions();
+            problem.into_response()
+        }
+
+        impl<E: ApplicationError + 'static> From<E> for Box<dyn ApplicationError> {
+            /// Allows the `?` operator and `into()` calls to box any concrete error, which is
+            /// needed in handlers where the error type can't be coerced automatically.
+            fn from(err: E) -> Self {
+                Box::new(err)
+            }
+        }
+
+        // ----- Implementation of the Axum 'IntoResponse' trait for boxed 'ApplicationError' objects -----
+
+        impl IntoResponse for Box<dyn ApplicationError> {
+            /// Handlers return a `Box<dyn ApplicationError>` like the repositories, so the error
+            /// can be directly converted into a response.
+            fn into_response(self) -> axum::response::Response {
+                problem_response(self.as_ref())
+            }
+        }
     }
 }
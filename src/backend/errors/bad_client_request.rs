@@ -5,7 +5,7 @@ cfg_if! {
 
         use std::fmt::{self, Debug, Display};
         use axum::{http::StatusCode, response::IntoResponse, Json};
-        use crate::backend::errors::application_error::{ApplicationError, ErrorObject};
+        use crate::backend::errors::application_error::{problem_response, ApplicationError, ErrorObject};
 
 /// Error type for all request with invalid data a client sends to the backend.
 ///
@@ -78,7 +78,7 @@ impl<T: for<'a> ErrorObject<'a>> BadClientRequest<T> {
 
 impl<T: for<'a> ErrorObject<'a>> IntoResponse for BadClientRequest<T> {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::OK, self).into_response()
+        problem_response(&self)
     }
 }
 
@@ -94,6 +94,10 @@ impl<T: for<'a> ErrorObject<'a>> IntoResponse for BadClientRequest<T> {
 
 // ----- Implementation of the 'ApplicationError' trait for the 'BadClientRequest' struct -----
 
-impl<T: for<'a> ErrorObject<'a>> ApplicationError for BadClientRequest<T> {}
+impl<T: for<'a> ErrorObject<'a>> ApplicationError for BadClientRequest<T> {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
     }
 }
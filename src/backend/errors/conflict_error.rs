@@ -0,0 +1,71 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests that are valid by themselves but conflict with the current state of a
+/// resource.
+///
+/// For example a claim that is submitted to a game which isn't running anymore.
+///
+/// # Props
+///
+/// - 'message': Describes the conflict
+pub struct ConflictError {
+    /// Description of the conflict
+    pub message: String,
+}
+
+impl ConflictError {
+    /// Resembling http status code for a conflict
+    pub const STATUS_CODE: StatusCode = StatusCode::CONFLICT;
+
+    /// Creates a new 'ConflictError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        ConflictError { message }
+    }
+}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The request conflicts with the current state of the resource! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for ConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ConflictError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for ConflictError {}
+
+impl ApplicationError for ConflictError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'ConflictError' struct -----
+
+impl IntoResponse for ConflictError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
@@ -2,9 +2,16 @@ use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(feature = "ssr")] {
 
-use crate::backend::errors::application_error::{ApplicationError, ErrorObject};
+use crate::backend::errors::application_error::{problem_response, ApplicationError, ErrorObject};
 
 use axum::{http::StatusCode, response::IntoResponse, Json};
+use serde_json::{Map, Value};
+use wasm_bindgen::JsValue;
+
+// constants
+
+/// Maximum number of characters of the `received_data` that are shown in the `Display` output.
+const MAX_DISPLAYED_DATA_LENGTH: usize = 200;
 
 /// This module defines a custom error type for handling database query errors.
 ///
@@ -39,6 +46,55 @@ pub struct DatabaseQueryError<T: for<'a> ErrorObject<'a>> {
     pub received_data: Option<Json<T>>,
     /// The HTTP status code associated with the error.
     pub status_code: StatusCode,
+    /// The failed SQL query and a redacted summary of its bindings.
+    ///
+    /// Only captured in debug builds, see `DatabaseQueryError::with_query`. It's added to the
+    /// problem details of the response, so a failing query can be found without a debugger.
+    pub query_context: Option<QueryContext>,
+}
+
+/// SQL query that caused a `DatabaseQueryError` together with a summary of its bindings.
+///
+/// The bindings are redacted, only their types and the length of strings are kept, so no
+/// player data ends up in the logs.
+///
+/// # Properties
+///
+/// - `query`: The SQL query text.
+/// - `bindings`: Redacted summary of the bound values, e.g. `[string(36), number, null]`.
+#[derive(Debug, Clone)]
+pub struct QueryContext {
+    /// The SQL query text.
+    pub query: String,
+    /// Redacted summary of the bound values.
+    pub bindings: String,
+}
+
+impl QueryContext {
+    /// Creates a new `QueryContext` and redacts the provided bindings.
+    pub fn new(query: &str, bindings: &[JsValue]) -> Self {
+        let redacted: Vec<String> = bindings
+            .iter()
+            .map(|value| {
+                if value.is_null() || value.is_undefined() {
+                    "null".to_string()
+                } else if let Some(text) = value.as_string() {
+                    format!("string({})", text.chars().count())
+                } else if value.as_f64().is_some() {
+                    "number".to_string()
+                } else if value.as_bool().is_some() {
+                    "bool".to_string()
+                } else {
+                    "unknown".to_string()
+                }
+            })
+            .collect();
+
+        QueryContext {
+            query: query.to_string(),
+            bindings: format!("[{}]", redacted.join(", ")),
+        }
+    }
 }
 
 // ----- Implementation 'DatabaseQueryError' -----
@@ -59,6 +115,51 @@ impl<T: for<'a> ErrorObject<'a>> DatabaseQueryError<T> {
             message,
             received_data,
             status_code,
+            query_context: None,
+        }
+    }
+
+    /// Attaches the failed SQL query and a redacted summary of its bindings to the error.
+    ///
+    /// Does nothing in release builds, so queries are never exposed in production.
+    ///
+    /// # Arguments
+    ///
+    /// - `query`: The SQL query that failed.
+    /// - `bindings`: The values that were bound to the query.
+    pub fn with_query(mut self, query: &str, bindings: &[JsValue]) -> Self {
+        if cfg!(debug_assertions) {
+            self.query_context = Some(QueryContext::new(query, bindings));
+        }
+        self
+    }
+
+    /// Returns a short summary of the `received_data` that is safe to be written into logs.
+    ///
+    /// Small payloads are shown completely, large payloads are cut off after
+    /// `MAX_DISPLAYED_DATA_LENGTH` characters and only the type of the entity and the number of
+    /// omitted characters are added.
+    pub fn received_data_summary(&self) -> String {
+        match &self.received_data {
+            None => "None".to_string(),
+            Some(Json(data)) => {
+                let full_data = format!("{:?}", data);
+                let length = full_data.chars().count();
+                if length <= MAX_DISPLAYED_DATA_LENGTH {
+                    return full_data;
+                }
+
+                let type_name = std::any::type_name::<T>()
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or("Unknown");
+                format!(
+                    "{} {{ {} ... ({} more characters) }}",
+                    type_name,
+                    full_data.chars().take(MAX_DISPLAYED_DATA_LENGTH).collect::<String>(),
+                    length - MAX_DISPLAYED_DATA_LENGTH
+                )
+            }
         }
     }
 }
@@ -69,8 +170,9 @@ impl<T: for<'a> ErrorObject<'a>> std::fmt::Display for DatabaseQueryError<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "Database query error: {}. Received data: {:?}",
-            self.message, self.received_data
+            "Database query error: {}. Received data: {}",
+            self.message,
+            self.received_data_summary()
         )
     }
 }
@@ -79,8 +181,8 @@ impl<T: for<'a> ErrorObject<'a>> std::fmt::Debug for DatabaseQueryError<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "DatabaseQueryError {{ message: {}, received_data: {:?} }}",
-            self.message, self.received_data
+            "DatabaseQueryError {{ message: {}, received_data: {:?}, query_context: {:?} }}",
+            self.message, self.received_data, self.query_context
         )
     }
 }
@@ -95,16 +197,48 @@ impl<T: for<'a> ErrorObject<'a>> std::error::Error for DatabaseQueryError<T> {
     }
 }
 
-impl<T: for<'a> ErrorObject<'a>> ApplicationError for DatabaseQueryError<T> {}
+impl<T: for<'a> ErrorObject<'a>> ApplicationError for DatabaseQueryError<T> {
+    fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    fn problem_extensions(&self) -> Map<String, Value> {
+        let mut extensions = Map::new();
+        if let Some(query_context) = &self.query_context {
+            extensions.insert("query".to_string(), Value::String(query_context.query.clone()));
+            extensions.insert("bindings".to_string(), Value::String(query_context.bindings.clone()));
+        }
+        extensions
+    }
+}
 
 // ----- Implementation of the Axum 'IntoResponse' trait for the 'DatabaseQueryError<T>' struct
 
 impl<T: for<'a> ErrorObject<'a>> IntoResponse for DatabaseQueryError<T> {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::OK, self).into_response()
+        problem_response(&self)
     }
 }
 
     }
 }
 //     }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::backend::types::player::Player;
+
+    #[test]
+    fn the_failed_query_is_added_to_the_problem_details_of_debug_builds() {
+        let error = DatabaseQueryError::<Player>::new("failed".to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)
+            .with_query("SELECT * FROM players;", &[]);
+        let extensions = error.problem_extensions();
+
+        assert_eq!(extensions.get("query").is_some(), cfg!(debug_assertions));
+        if cfg!(debug_assertions) {
+            assert_eq!(extensions["query"], "SELECT * FROM players;");
+            assert_eq!(extensions["bindings"], "[]");
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests of a player who isn't allowed to perform the action.
+///
+/// For example a player who tries to rename a game without being its host.
+///
+/// # Props
+///
+/// - 'message': Describes which permission is missing
+pub struct ForbiddenError {
+    /// Description of the missing permission
+    pub message: String,
+}
+
+impl ForbiddenError {
+    /// Resembling http status code for a forbidden action
+    pub const STATUS_CODE: StatusCode = StatusCode::FORBIDDEN;
+
+    /// Creates a new 'ForbiddenError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        ForbiddenError { message }
+    }
+}
+
+impl fmt::Display for ForbiddenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The action isn't allowed! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for ForbiddenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ForbiddenError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for ForbiddenError {}
+
+impl ApplicationError for ForbiddenError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'ForbiddenError' struct -----
+
+impl IntoResponse for ForbiddenError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
@@ -0,0 +1,70 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests of a resource that existed but isn't available anymore.
+///
+/// For example a game that was archived, unlike a game that never existed.
+///
+/// # Props
+///
+/// - 'message': Describes which resource is gone
+pub struct GoneError {
+    /// Description of the resource that is gone
+    pub message: String,
+}
+
+impl GoneError {
+    /// Resembling http status code for a resource that is gone
+    pub const STATUS_CODE: StatusCode = StatusCode::GONE;
+
+    /// Creates a new 'GoneError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        GoneError { message }
+    }
+}
+
+impl fmt::Display for GoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The resource isn't available anymore! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for GoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GoneError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for GoneError {}
+
+impl ApplicationError for GoneError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'GoneError' struct -----
+
+impl IntoResponse for GoneError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
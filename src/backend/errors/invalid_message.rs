@@ -4,7 +4,10 @@ cfg_if! {
     if #[cfg(feature = "ssr")] {
 use axum::response::IntoResponse;
 
-use crate::backend::{errors::application_error::ApplicationError, types::chat::ChatMessage};
+use crate::backend::{
+    errors::application_error::{problem_response, ApplicationError},
+    types::chat::ChatMessage,
+};
 use std::fmt;
 
 /// Error for an invalid message created sent by a user.
@@ -41,13 +44,17 @@ impl fmt::Debug for InvalidMessageError {
 
 impl std::error::Error for InvalidMessageError {}
 
-impl ApplicationError for InvalidMessageError {}
+impl ApplicationError for InvalidMessageError {
+    fn status_code(&self) -> axum::http::StatusCode {
+        axum::http::StatusCode::BAD_REQUEST
+    }
+}
 
 // ----- Implementation of the Axum 'IntoResponse' trait for the 'InvalidMessageError' struct -----
 
 impl IntoResponse for InvalidMessageError {
     fn into_response(self) -> axum::response::Response {
-        (axum::http::StatusCode::OK, self).into_response()
+        problem_response(&self)
     }
 }
 
@@ -0,0 +1,70 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests whose body couldn't be read as JSON.
+///
+/// For example a body with a syntax error or a missing field.
+///
+/// # Props
+///
+/// - 'message': Describes why the body couldn't be read
+pub struct MalformedBodyError {
+    /// Reason why the body couldn't be read
+    pub message: String,
+}
+
+impl MalformedBodyError {
+    /// Resembling http status code for an unreadable body
+    pub const STATUS_CODE: StatusCode = StatusCode::BAD_REQUEST;
+
+    /// Creates a new 'MalformedBodyError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        MalformedBodyError { message }
+    }
+}
+
+impl fmt::Display for MalformedBodyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The body of the request couldn't be read! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for MalformedBodyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MalformedBodyError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for MalformedBodyError {}
+
+impl ApplicationError for MalformedBodyError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'MalformedBodyError' struct -----
+
+impl IntoResponse for MalformedBodyError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
@@ -1,5 +1,15 @@
 pub mod application_error;
 pub mod bad_client_request;
+pub mod conflict_error;
 pub mod database_query_error;
+pub mod forbidden_error;
+pub mod gone_error;
 pub mod invalid_message;
+pub mod malformed_body_error;
+pub mod not_found_error;
+pub mod not_your_turn_error;
 pub mod process_error;
+pub mod too_many_requests_error;
+pub mod unauthorized_error;
+pub mod unsupported_media_type_error;
+pub mod validation_error;
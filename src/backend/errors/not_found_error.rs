@@ -0,0 +1,70 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests that refer to a resource which doesn't exist.
+///
+/// For example a player who isn't part of the game the request was sent for.
+///
+/// # Props
+///
+/// - 'message': Describes what couldn't be found
+pub struct NotFoundError {
+    /// Description of the missing resource
+    pub message: String,
+}
+
+impl NotFoundError {
+    /// Resembling http status code for a missing resource
+    pub const STATUS_CODE: StatusCode = StatusCode::NOT_FOUND;
+
+    /// Creates a new 'NotFoundError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        NotFoundError { message }
+    }
+}
+
+impl fmt::Display for NotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The requested resource couldn't be found! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for NotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NotFoundError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for NotFoundError {}
+
+impl ApplicationError for NotFoundError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'NotFoundError' struct -----
+
+impl IntoResponse for NotFoundError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
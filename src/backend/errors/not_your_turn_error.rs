@@ -0,0 +1,93 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+use serde_json::{Map, Value};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for moves of a player while it's the turn of another player.
+///
+/// For example a claim that is submitted before the previous player finished their turn. The
+/// response contains the id of the player whose turn it is as `which_player_turn`, so clients can
+/// show a specific message.
+///
+/// # Props
+///
+/// - 'player_id': The player who tried to make a move
+/// - 'which_player_turn': The player whose turn it is
+pub struct NotYourTurnError {
+    /// ID of the player who tried to make a move
+    pub player_id: String,
+    /// ID of the player whose turn it is
+    pub which_player_turn: String,
+}
+
+impl NotYourTurnError {
+    /// Resembling http status code for a move out of turn
+    pub const STATUS_CODE: StatusCode = StatusCode::CONFLICT;
+
+    /// Creates a new 'NotYourTurnError' instance.
+    ///
+    /// # Params
+    ///
+    /// - 'player_id': ID of the player who tried to make a move
+    /// - 'which_player_turn': ID of the player whose turn it is
+    pub fn new(player_id: String, which_player_turn: String) -> Self {
+        NotYourTurnError {
+            player_id,
+            which_player_turn,
+        }
+    }
+}
+
+impl fmt::Display for NotYourTurnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "It isn't the turn of the player with the id ['{}']! It's the turn of the player with the id ['{}'].",
+            self.player_id, self.which_player_turn
+        )
+    }
+}
+
+impl fmt::Debug for NotYourTurnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "NotYourTurnError {{ player_id: {}, which_player_turn: {} }}",
+            self.player_id, self.which_player_turn
+        )
+    }
+}
+
+impl std::error::Error for NotYourTurnError {}
+
+impl ApplicationError for NotYourTurnError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+
+    fn problem_extensions(&self) -> Map<String, Value> {
+        let mut extensions = Map::new();
+        extensions.insert(
+            "which_player_turn".to_string(),
+            Value::String(self.which_player_turn.clone()),
+        );
+        extensions
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'NotYourTurnError' struct -----
+
+impl IntoResponse for NotYourTurnError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
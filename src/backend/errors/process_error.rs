@@ -6,7 +6,7 @@ use std::{error::Error, fmt::Display};
 
 use axum::{http, response::IntoResponse};
 
-use crate::backend::errors::application_error::{ApplicationError, ErrorObject};
+use crate::backend::errors::application_error::{problem_response, ApplicationError, ErrorObject};
 
 #[derive()]
 /// ## Error Struct
@@ -68,13 +68,17 @@ impl<T: for<'a> ErrorObject<'a>> Display for ProcessError<T> {
 
 impl<T: for<'a> ErrorObject<'a>> Error for ProcessError<T> {}
 
-impl<T: for<'a> ErrorObject<'a>> ApplicationError for ProcessError<T> {}
+impl<T: for<'a> ErrorObject<'a>> ApplicationError for ProcessError<T> {
+    fn status_code(&self) -> http::StatusCode {
+        http::StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
 
 // ----- Implementation of the Axum 'IntoResponse' trait for the 'ProcessError<T>' struct -----
 
 impl<T: for<'a> ErrorObject<'a>> IntoResponse for ProcessError<T> {
     fn into_response(self) -> axum::response::Response {
-        (http::StatusCode::OK, self).into_response()
+        problem_response(&self)
     }
 }
 
@@ -0,0 +1,66 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for clients that sent more requests than a rate limit allows.
+///
+/// For example a client that creates many games within a short time.
+///
+/// # Props
+///
+/// - 'message': Describes the exceeded limit
+pub struct TooManyRequestsError {
+    /// Description of the exceeded limit
+    pub message: String,
+}
+
+impl TooManyRequestsError {
+    /// Resembling http status code for an exceeded rate limit
+    pub const STATUS_CODE: StatusCode = StatusCode::TOO_MANY_REQUESTS;
+
+    /// Creates a new 'TooManyRequestsError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        TooManyRequestsError { message }
+    }
+}
+
+impl fmt::Display for TooManyRequestsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Too many requests were sent! Error: {}", self.message)
+    }
+}
+
+impl fmt::Debug for TooManyRequestsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TooManyRequestsError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for TooManyRequestsError {}
+
+impl ApplicationError for TooManyRequestsError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'TooManyRequestsError' struct -----
+
+impl IntoResponse for TooManyRequestsError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
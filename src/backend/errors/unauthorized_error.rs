@@ -0,0 +1,70 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests whose sender couldn't be identified.
+///
+/// For example a request with a missing or forged session token of a player.
+///
+/// # Props
+///
+/// - 'message': Describes why the sender couldn't be identified
+pub struct UnauthorizedError {
+    /// Reason why the sender couldn't be identified
+    pub message: String,
+}
+
+impl UnauthorizedError {
+    /// Resembling http status code for an unidentified sender
+    pub const STATUS_CODE: StatusCode = StatusCode::UNAUTHORIZED;
+
+    /// Creates a new 'UnauthorizedError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        UnauthorizedError { message }
+    }
+}
+
+impl fmt::Display for UnauthorizedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The sender of the request couldn't be authenticated! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for UnauthorizedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnauthorizedError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for UnauthorizedError {}
+
+impl ApplicationError for UnauthorizedError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'UnauthorizedError' struct -----
+
+impl IntoResponse for UnauthorizedError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
@@ -0,0 +1,71 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::fmt;
+
+use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests whose body isn't sent as JSON.
+///
+/// For example a form or plain text body sent to an endpoint that expects
+/// `Content-Type: application/json`.
+///
+/// # Props
+///
+/// - 'message': Describes which content type was sent
+pub struct UnsupportedMediaTypeError {
+    /// Description of the rejected content type
+    pub message: String,
+}
+
+impl UnsupportedMediaTypeError {
+    /// Resembling http status code for a body with an unsupported content type
+    pub const STATUS_CODE: StatusCode = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+
+    /// Creates a new 'UnsupportedMediaTypeError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        UnsupportedMediaTypeError { message }
+    }
+}
+
+impl fmt::Display for UnsupportedMediaTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The content type of the body isn't supported! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for UnsupportedMediaTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnsupportedMediaTypeError {{ message: {} }}", self.message)
+    }
+}
+
+impl std::error::Error for UnsupportedMediaTypeError {}
+
+impl ApplicationError for UnsupportedMediaTypeError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'UnsupportedMediaTypeError' struct -----
+
+impl IntoResponse for UnsupportedMediaTypeError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
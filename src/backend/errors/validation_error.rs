@@ -0,0 +1,123 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::{collections::BTreeMap, fmt};
+
+use axum::{http::StatusCode, response::IntoResponse};
+use serde_json::{Map, Value};
+
+use crate::backend::errors::application_error::{problem_response, ApplicationError};
+
+/// Error for requests whose data breaks a rule of the game, e.g. a round number that goes
+/// backwards.
+///
+/// When single fields of a request are invalid, all of them are listed with their reason as
+/// `errors` in the response, so a client can highlight every invalid field at once.
+///
+/// # Props
+///
+/// - 'message': Describes the broken rule
+/// - 'field_errors': Reason per invalid field, empty when the error isn't about single fields
+pub struct ValidationError {
+    /// Description of the broken rule
+    pub message: String,
+    /// Reason per invalid field
+    pub field_errors: BTreeMap<String, String>,
+}
+
+impl ValidationError {
+    /// Resembling http status code for invalid data
+    pub const STATUS_CODE: StatusCode = StatusCode::UNPROCESSABLE_ENTITY;
+
+    /// Creates a new 'ValidationError' instance with a individual error message.
+    ///
+    /// # Params
+    ///
+    /// - 'message': Error message
+    pub fn new(message: String) -> Self {
+        ValidationError {
+            message,
+            field_errors: BTreeMap::new(),
+        }
+    }
+
+    /// Checks the collected errors of the fields of a request.
+    ///
+    /// # Params
+    ///
+    /// - 'field_errors': Reason per invalid field
+    ///
+    /// # Returns
+    ///
+    /// 1.) Ok(()), WHEN no field is invalid.
+    /// 2.) Err(ValidationError), listing every invalid field.
+    pub fn check_fields(field_errors: BTreeMap<String, String>) -> Result<(), Self> {
+        if field_errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(ValidationError {
+            message: format!(
+                "Invalid fields: {}",
+                field_errors.keys().cloned().collect::<Vec<String>>().join(", ")
+            ),
+            field_errors,
+        })
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The request contains invalid data! Error: {}",
+            self.message
+        )
+    }
+}
+
+impl fmt::Debug for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ValidationError {{ message: {}, field_errors: {:?} }}",
+            self.message, self.field_errors
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl ApplicationError for ValidationError {
+    fn status_code(&self) -> StatusCode {
+        Self::STATUS_CODE
+    }
+
+    fn problem_extensions(&self) -> Map<String, Value> {
+        let mut extensions = Map::new();
+        if !self.field_errors.is_empty() {
+            extensions.insert(
+                "errors".to_string(),
+                Value::Object(
+                    self.field_errors
+                        .iter()
+                        .map(|(field, reason)| (field.clone(), Value::String(reason.clone())))
+                        .collect(),
+                ),
+            );
+        }
+        extensions
+    }
+}
+
+// ----- Implementation of the Axum 'IntoResponse' trait for the 'ValidationError' struct -----
+
+impl IntoResponse for ValidationError {
+    fn into_response(self) -> axum::response::Response {
+        problem_response(&self)
+    }
+}
+
+    }
+}
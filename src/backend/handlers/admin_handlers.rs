@@ -0,0 +1,104 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    errors::application_error::ApplicationError,
+    middleware::admin_access::AdminAccess,
+    types::player::Player,
+    utils::repository_provider::Repositories,
+};
+
+// constants
+
+/// Age in minutes after which a game that is still waiting for players counts as abandoned.
+const DEFAULT_ABANDONED_GAME_AGE_MINUTES: i64 = 24 * 60;
+
+/// Query parameters of the cleanup endpoint.
+///
+/// # Props
+///
+/// - `max_age_minutes` -> Optional age after which a waiting game is removed, defaults to
+///   `DEFAULT_ABANDONED_GAME_AGE_MINUTES`.
+#[derive(Deserialize)]
+pub struct CleanupQuery {
+    /// Age in minutes after which a waiting game is removed
+    pub max_age_minutes: Option<i64>,
+}
+
+/// Summary of a cleanup run that is returned to the caller.
+///
+/// # Props
+///
+/// - `cleaned_games` -> Number of games that were removed.
+#[derive(Serialize)]
+pub struct CleanupSummary {
+    /// Number of games that were removed
+    pub cleaned_games: usize,
+}
+
+/// Removes all games that are still waiting for players after a configurable time, together with
+/// their players, cards, claims and chat.
+///
+/// URL endpoint: /api/admin/cleanup
+#[worker::send]
+pub async fn cleanup_abandoned_games(
+    Extension(repos): Extension<Repositories>,
+    Query(query): Query<CleanupQuery>,
+) -> Result<Json<CleanupSummary>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+
+    let max_age = chrono::Duration::minutes(
+        query
+            .max_age_minutes
+            .unwrap_or(DEFAULT_ABANDONED_GAME_AGE_MINUTES),
+    );
+    let created_before = (chrono::Utc::now() - max_age).to_string();
+
+    let abandoned_game_ids = game_repo.get_abandoned_game_ids(&created_before).await?;
+    for game_id in &abandoned_game_ids {
+        game_repo.delete_game_cascading(game_id).await?;
+    }
+
+    Ok(Json(CleanupSummary {
+        cleaned_games: abandoned_game_ids.len(),
+    }))
+}
+
+/// Returns all players of a game with the cards in their hands, e.g. to debug a deal or review a
+/// game afterwards.
+///
+/// The request has to contain the admin token in the `X-Admin-Token` header, see `AdminAccess`.
+///
+/// URL endpoint: /api/game/{game_id}/debug/hands
+///
+/// # Errors
+///
+/// Returns a `ForbiddenError` (403) without a valid admin token and a 404 when the game doesn't
+/// exist.
+#[worker::send]
+pub async fn get_all_hands(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    _admin: AdminAccess,
+) -> Result<Json<Vec<Player>>, Box<dyn ApplicationError>> {
+    // fails with a 404 for unknown games
+    repos.games()?.get_game_state(&game_id).await?;
+
+    Ok(Json(
+        repos
+            .players()?
+            .get_all_players(Some(&game_id), &repos.cards()?)
+            .await?,
+    ))
+}
+
+    }
+}
@@ -0,0 +1,135 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+use axum::{extract::Path, Extension, Json};
+
+use crate::backend::{
+    errors::{
+        application_error::ApplicationError, bad_client_request::BadClientRequest,
+        forbidden_error::ForbiddenError, validation_error::ValidationError,
+    },
+    middleware::{authentication::AuthenticatedPlayer, json_body::JsonBody},
+    repositories::game_repository::GameRepository,
+    types::{
+        card::{CardDetails, MoveCardRequest},
+        player::Player,
+    },
+    utils::repository_provider::Repositories,
+};
+
+/// Returns a single card together with its game, owner and claim, e.g. to debug a deal.
+///
+/// Only the host of the game of the card and the player holding it can see it. The request has
+/// to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/card/{card_id}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player is neither the host nor holding the card and a
+/// `NotFoundError` (404) when the card doesn't exist.
+#[worker::send]
+pub async fn get_card(
+    Extension(repos): Extension<Repositories>,
+    Path(card_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<CardDetails>, Box<dyn ApplicationError>> {
+    let card = repos.cards()?.get_card_details(&card_id).await?;
+
+    if card.player_id.as_deref() != Some(player.id.as_str())
+        && !is_host_of_card_game(&repos.games()?, &card, &player).await?
+    {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] can't see the card ['{}']!",
+            player.id, card_id
+        ))
+        .into());
+    }
+
+    Ok(Json(card))
+}
+
+/// Moves a single card into the hand of a player, into a claim or back to the deck.
+///
+/// Only the host of the game of the card may move it. The request has to contain the session
+/// token of the host in the `Authorization` header.
+///
+/// URL endpoint: /api/card/{card_id}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't the host, a `NotFoundError` (404) when the card
+/// doesn't exist, a `BadClientRequest` (400) when both a player and a claim are given and a
+/// `ValidationError` (422) when the target player or claim isn't part of the game of the card.
+#[worker::send]
+pub async fn move_card(
+    Extension(repos): Extension<Repositories>,
+    Path(card_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(request): JsonBody<MoveCardRequest>,
+) -> Result<Json<CardDetails>, Box<dyn ApplicationError>> {
+    let card_repo = repos.cards()?;
+
+    let card = card_repo.get_card_details(&card_id).await?;
+    if !is_host_of_card_game(&repos.games()?, &card, &player).await? {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't the host of the game of the card ['{}'] and can't move it!",
+            player.id, card_id
+        ))
+        .into());
+    }
+
+    if request.player_id.is_some() && request.claim_id.is_some() {
+        return Err(BadClientRequest {
+            message: "A card can either be moved to a player or to a claim, not to both!".to_string(),
+            bad_data: Json(request),
+        }
+        .into());
+    }
+
+    // the host check above makes sure that the card belongs to a game
+    let game_id = card.game_id.unwrap_or_default();
+    if let Some(player_id) = &request.player_id {
+        if !repos.players()?.is_player_in_game(player_id, &game_id).await? {
+            return Err(ValidationError::new(format!(
+                "The player with the id ['{}'] isn't part of the game ['{}']!",
+                player_id, game_id
+            ))
+            .into());
+        }
+    }
+    if let Some(claim_id) = &request.claim_id {
+        if !repos.claims()?.is_claim_in_game(claim_id, &game_id).await? {
+            return Err(ValidationError::new(format!(
+                "The claim with the id ['{}'] isn't part of the game ['{}']!",
+                claim_id, game_id
+            ))
+            .into());
+        }
+    }
+
+    Ok(Json(
+        card_repo
+            .move_card(&card_id, request.player_id.as_deref(), request.claim_id.as_deref())
+            .await?,
+    ))
+}
+
+/// Checks whether the player is the host of the game the card belongs to.
+async fn is_host_of_card_game(
+    game_repo: &GameRepository,
+    card: &CardDetails,
+    player: &Player,
+) -> Result<bool, Box<dyn ApplicationError>> {
+    match &card.game_id {
+        Some(game_id) => Ok(game_repo.get_host_id(game_id).await?.as_deref() == Some(player.id.as_str())),
+        None => Ok(false),
+    }
+}
+
+    }
+}
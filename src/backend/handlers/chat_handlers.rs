@@ -1 +1,391 @@
+use cfg_if::cfg_if;
 
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, RawQuery},
+    http::{HeaderMap, StatusCode},
+    Extension, Json,
+};
+use futures::join;
+use serde::Deserialize;
+
+use crate::backend::{
+    enums::game_state::GameState,
+    middleware::{authentication::AuthenticatedPlayer, json_body::JsonBody},
+    errors::{
+        application_error::ApplicationError, conflict_error::ConflictError,
+        forbidden_error::ForbiddenError, not_found_error::NotFoundError,
+        validation_error::ValidationError,
+    },
+    repositories::chat::chat_message_repository::DEFAULT_NUMBER_OF_RECENT_MESSAGES,
+    types::{
+        chat::{Chat, ChatMessage, ReactionRequest, ReadReceiptRequest, MAX_REACTION_LENGTH},
+        player::Player,
+    },
+    utils::{
+        config::Config, id_generator::generate_id, pagination::Page,
+        repository_provider::Repositories,
+    },
+};
+
+// constants
+
+/// Name of the optional header a client uses to mark resent messages and retried joins.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Sends a new message to the chat of a game.
+///
+/// URL endpoint: /api/chat/{chat_id}/messages
+///
+/// A client can send an `Idempotency-Key` header. If a message with the same key was already
+/// stored in the chat, the stored message is returned instead of inserting the message again.
+///
+/// The client can generate the `id` of the message itself, so its optimistic local echo matches
+/// the stored message. Without an `id` the server generates one.
+///
+/// The message is sent in the name of the player identified by the session token in the
+/// `Authorization` header.
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid and a
+/// `ConflictError` (409) when the game of the chat was aborted and
+/// `Config::reject_messages_in_aborted_games` is enabled or the `id` of the message is already
+/// used.
+#[worker::send]
+pub async fn send_message(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(chat_id): Path<String>,
+    headers: HeaderMap,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(message): JsonBody<ChatMessage>,
+) -> Result<Json<ChatMessage>, Box<dyn ApplicationError>> {
+    let chat_repo = repos.chats()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let game_repo = repos.games()?;
+
+    if config.reject_messages_in_aborted_games {
+        let chat = chat_repo
+            .get_chat(Some(&chat_id), None, &chat_message_repo)
+            .await?;
+        if game_repo.get_game_state(&chat.game_id).await? == GameState::Aborted {
+            return Err(ConflictError::new(format!(
+                "The game with the id ['{}'] was aborted! No messages can be sent to its chat anymore.",
+                chat.game_id
+            ))
+            .into());
+        }
+    }
+
+    // validate the client data
+    let new_message = match ChatMessage::new(
+        match message.id.trim() {
+            "" => generate_id(),
+            id => id.to_string(),
+        },
+        // the message is always sent in the name of the authenticated player
+        player.id,
+        message.content,
+        message.sent_at,
+        chat_id.clone(),
+    ) {
+        Ok(new_message) => new_message,
+        Err(err) => return Err(err.into()),
+    };
+
+    let idempotency_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    let saved_message = chat_repo
+        .add_new_message_to_chat(&chat_id, new_message, &chat_message_repo, idempotency_key)
+        .await?;
+
+    Ok(Json(saved_message))
+}
+
+/// Query parameters of the endpoint to load the messages of a chat.
+///
+/// # Props
+///
+/// - `limit` -> Optional maximum number of messages, defaults to
+///   `DEFAULT_NUMBER_OF_RECENT_MESSAGES`.
+/// - `offset` -> Optional number of the newest messages that are skipped, defaults to 0.
+#[derive(Deserialize)]
+pub struct RecentMessagesQuery {
+    /// Maximum number of returned messages
+    pub limit: Option<usize>,
+    /// Number of skipped messages, counted from the newest one
+    pub offset: Option<usize>,
+}
+
+/// Loads the most recent messages of a chat, the oldest message first.
+///
+/// The messages are paginated from the newest to the oldest, the next page contains older
+/// messages. The number of all messages is sent in the `X-Total-Count` header and the `Link`
+/// header points to the next and previous pages.
+///
+/// URL endpoint: /api/chat/{chat_id}/messages
+#[worker::send]
+pub async fn get_recent_messages(
+    Extension(repos): Extension<Repositories>,
+    Path(chat_id): Path<String>,
+    Query(query): Query<RecentMessagesQuery>,
+    RawQuery(raw_query): RawQuery,
+) -> Result<(HeaderMap, Json<Vec<ChatMessage>>), Box<dyn ApplicationError>> {
+    let chat_message_repo = repos.chat_messages()?;
+    let page = Page::new(query.offset, query.limit, DEFAULT_NUMBER_OF_RECENT_MESSAGES);
+
+    let (messages, total) = join!(
+        chat_message_repo.get_recent_messages(&chat_id, page.limit, page.offset),
+        chat_message_repo.count_messages(&chat_id),
+    );
+
+    Ok((page.headers(total?, raw_query.as_deref()), Json(messages?)))
+}
+
+/// Query parameters of the endpoint to load a chat.
+///
+/// # Props
+///
+/// - `unread_counts` -> Whether the number of unread messages of every player is added,
+///   defaults to `false`.
+#[derive(Deserialize)]
+pub struct ChatQuery {
+    /// Whether the unread counts are added to the chat
+    #[serde(default)]
+    pub unread_counts: bool,
+}
+
+/// Loads a chat with all of its messages.
+///
+/// The request has to contain the session token of a player of the game in the `Authorization`
+/// header.
+///
+/// URL endpoint: /api/chat/{chat_id}?unread_counts=true
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't part of the game of the chat and a 404 error when
+/// the chat doesn't exist.
+#[worker::send]
+pub async fn get_chat(
+    Extension(repos): Extension<Repositories>,
+    Path(chat_id): Path<String>,
+    Query(query): Query<ChatQuery>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<Chat>, Box<dyn ApplicationError>> {
+    let chat_repo = repos.chats()?;
+
+    let mut chat = chat_repo
+        .get_chat(Some(&chat_id), None, &repos.chat_messages()?)
+        .await?;
+    check_chat_member(&chat, &player)?;
+
+    if query.unread_counts {
+        chat.unread_counts = Some(chat_repo.get_unread_counts(&chat_id).await?);
+    }
+
+    Ok(Json(chat))
+}
+
+/// Marks every message of a chat up to and including the given message as read.
+///
+/// The messages are marked as read for the player identified by the session token in the
+/// `Authorization` header, the `player_id` of the body is ignored.
+///
+/// URL endpoint: /api/chat/{chat_id}/read
+///
+/// # Returns
+///
+/// The number of unread messages of every player in the game, keyed by the player id.
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't part of the game of the chat and a `NotFoundError`
+/// (404) when the message isn't part of the chat.
+#[worker::send]
+pub async fn mark_messages_read(
+    Extension(repos): Extension<Repositories>,
+    Path(chat_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(request): JsonBody<ReadReceiptRequest>,
+) -> Result<Json<HashMap<String, usize>>, Box<dyn ApplicationError>> {
+    let chat_repo = repos.chats()?;
+    let chat_message_repo = repos.chat_messages()?;
+
+    let chat = chat_repo
+        .get_chat(Some(&chat_id), None, &chat_message_repo)
+        .await?;
+    check_chat_member(&chat, &player)?;
+
+    let message = chat_message_repo
+        .get_message_by_id(&request.message_id, Some(&chat_id))
+        .await?;
+
+    chat_repo
+        .mark_messages_read(&chat_id, &player.id, &message)
+        .await?;
+
+    Ok(Json(chat_repo.get_unread_counts(&chat_id).await?))
+}
+
+/// Adds the reaction of a player to a message of a chat.
+///
+/// The reaction is added in the name of the player identified by the session token in the
+/// `Authorization` header. Reacting twice with the same emoji counts only once.
+///
+/// URL endpoint: /api/chat/{chat_id}/messages/{message_id}/reactions
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't part of the game of the chat, a `NotFoundError`
+/// (404) when the message isn't part of the chat and a `ValidationError` (422) when the emoji is
+/// empty or too long.
+#[worker::send]
+pub async fn add_reaction(
+    Extension(repos): Extension<Repositories>,
+    Path((chat_id, message_id)): Path<(String, String)>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(request): JsonBody<ReactionRequest>,
+) -> Result<Json<ChatMessage>, Box<dyn ApplicationError>> {
+    let chat_message_repo = repos.chat_messages()?;
+
+    let emoji = match request.validated_emoji() {
+        Some(emoji) => emoji,
+        None => {
+            return Err(ValidationError::new(format!(
+                "A reaction must have between 1 and {} characters!",
+                MAX_REACTION_LENGTH
+            ))
+            .into())
+        }
+    };
+    check_reaction_access(&repos, &chat_id, &message_id, &player).await?;
+
+    chat_message_repo.add_reaction(&message_id, &player.id, &emoji).await?;
+
+    Ok(Json(chat_message_repo.get_message_by_id(&message_id, Some(&chat_id)).await?))
+}
+
+/// Removes the reaction of a player to a message of a chat.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/chat/{chat_id}/messages/{message_id}/reactions/{emoji}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't part of the game of the chat and a
+/// `NotFoundError` (404) when the message isn't part of the chat or the player didn't react with
+/// the emoji.
+#[worker::send]
+pub async fn remove_reaction(
+    Extension(repos): Extension<Repositories>,
+    Path((chat_id, message_id, emoji)): Path<(String, String, String)>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<ChatMessage>, Box<dyn ApplicationError>> {
+    let chat_message_repo = repos.chat_messages()?;
+
+    check_reaction_access(&repos, &chat_id, &message_id, &player).await?;
+
+    if !chat_message_repo.remove_reaction(&message_id, &player.id, &emoji).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] didn't react with '{}' to the message ['{}']!",
+            player.id, emoji, message_id
+        ))
+        .into());
+    }
+
+    Ok(Json(chat_message_repo.get_message_by_id(&message_id, Some(&chat_id)).await?))
+}
+
+/// Removes every message of a chat and resets its number of messages.
+///
+/// Only the host of the game of the chat may clear it. The request has to contain the session
+/// token of the host in the `Authorization` header.
+///
+/// URL endpoint: /api/chat/{chat_id}/messages
+///
+/// # Returns
+///
+/// `204 No Content` when the chat was cleared.
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't the host of the game of the chat and a 404 error
+/// when the chat doesn't exist.
+#[worker::send]
+pub async fn clear_chat(
+    Extension(repos): Extension<Repositories>,
+    Path(chat_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<StatusCode, Box<dyn ApplicationError>> {
+    let chat_repo = repos.chats()?;
+    let chat_message_repo = repos.chat_messages()?;
+
+    let chat = chat_repo
+        .get_chat(Some(&chat_id), None, &chat_message_repo)
+        .await?;
+    check_chat_member(&chat, &player)?;
+
+    if repos.games()?.get_host_id(&chat.game_id).await?.as_deref() != Some(player.id.as_str()) {
+        return Err(ForbiddenError::new(format!(
+            "Only the host of the game ['{}'] can clear its chat!",
+            chat.game_id
+        ))
+        .into());
+    }
+
+    chat_message_repo.delete_all_messages_in_chat(&chat_id).await?;
+    chat_repo
+        .update_number_of_messages_of_chat(0, Some(&chat_id), None)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Checks that the message is part of the chat and the player is part of the game of the chat.
+async fn check_reaction_access(
+    repos: &Repositories,
+    chat_id: &str,
+    message_id: &str,
+    player: &Player,
+) -> Result<(), Box<dyn ApplicationError>> {
+    let chat_message_repo = repos.chat_messages()?;
+
+    // fails with a 404 when the message isn't part of the chat
+    chat_message_repo.get_message_by_id(message_id, Some(chat_id)).await?;
+
+    let chat = repos
+        .chats()?
+        .get_chat(Some(chat_id), None, &chat_message_repo)
+        .await?;
+    check_chat_member(&chat, player)
+}
+
+/// Checks that the player is part of the game of the chat.
+fn check_chat_member(chat: &Chat, player: &Player) -> Result<(), Box<dyn ApplicationError>> {
+    if chat.game_id != player.game_id {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't part of the game of the chat ['{}']!",
+            player.id, chat.id
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+    }
+}
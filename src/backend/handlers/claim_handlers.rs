@@ -0,0 +1,59 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+use axum::{extract::Path, Extension, Json};
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, forbidden_error::ForbiddenError},
+    middleware::authentication::AuthenticatedPlayer,
+    types::card::Card,
+    utils::repository_provider::Repositories,
+};
+
+/// Returns only the cards placed in a single claim, e.g. to animate a revealed challenge
+/// without fetching the whole game.
+///
+/// Like in the `GameView` the cards can only be seen once the claim was challenged, before only
+/// by the player who placed it. The request has to contain the session token of a player of the
+/// game in the `Authorization` header.
+///
+/// URL endpoint: /api/claim/{claim_id}/cards
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `NotFoundError` (404) when the claim doesn't exist and a `ForbiddenError` (403) when the
+/// player isn't part of the game of the claim or the cards are still hidden.
+#[worker::send]
+pub async fn get_claim_cards(
+    Extension(repos): Extension<Repositories>,
+    Path(claim_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<Vec<Card>>, Box<dyn ApplicationError>> {
+    let claim_repo = repos.claims()?;
+
+    // fails with a 404 when the claim doesn't exist
+    let claim = claim_repo.get_claim_by_id(claim_id.clone()).await?;
+
+    if !claim_repo.is_claim_in_game(&claim_id, &player.game_id).await? {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't part of the game of the claim ['{}']!",
+            player.id, claim_id
+        ))
+        .into());
+    }
+    if claim.created_by != player.id && !claim.outcome.was_challenged() {
+        return Err(ForbiddenError::new(format!(
+            "The cards of the claim ['{}'] are hidden until it's challenged!",
+            claim_id
+        ))
+        .into());
+    }
+
+    Ok(Json(repos.cards()?.get_all_cards(Some(claim_id), None).await?))
+}
+
+    }
+}
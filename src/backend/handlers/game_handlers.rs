@@ -4,15 +4,172 @@ use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(feature = "ssr")] {
 
-
 use axum::{
-    extract::Request,
-    http::{self, StatusCode},
-    Json,
+    extract::{Path, Query, Request},
+    http::{self, HeaderMap, StatusCode},
+    response::Response,
+    Extension, Json,
 };
 use axum_macros::debug_handler;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    enums::{card_location::CardLocation, game_phase::GamePhase, game_state::GameState},
+    middleware::{
+        admin_access::AdminAccess, authentication::AuthenticatedPlayer, json_body::JsonBody,
+        response_format::ResponseFormat,
+    },
+    errors::{
+        application_error::ApplicationError, bad_client_request::BadClientRequest,
+        conflict_error::ConflictError, forbidden_error::ForbiddenError, gone_error::GoneError,
+        not_found_error::NotFoundError, not_your_turn_error::NotYourTurnError,
+        unauthorized_error::UnauthorizedError, validation_error::ValidationError,
+    },
+    logic::game_logic::{
+        advance_card_to_play, challenge_deadline_from, deal_cards, is_challenge_window_open,
+        new_deck, rank_players, resolve_challenge, shuffle_deck, turn_deadline_from,
+    },
+    types::{
+        challenge::{ChallengeRequest, ChallengeResponse},
+        claim::Claim,
+        game::{
+            CreateGameRequest, Game, GameSummary, RenameGameRequest, TransferHostRequest, UpdateGameDTO,
+        },
+        game_event::GameEvent,
+        game_operation::{validate_operations, GameOperation},
+        game_view::GameView,
+        score_event::ScoreEvent,
+        scoreboard::Scoreboard,
+        player::Player,
+    },
+    utils::{
+        config::Config,
+        pagination::MAX_PAGE_SIZE,
+        rate_limiter::client_key,
+        repository_provider::Repositories,
+        session_token::hash_session_token,
+        turn_service::{advance_expired_turns, award_finishing_positions},
+    },
+};
+
+// constants
+
+/// Number of events that are returned when a client doesn't ask for a specific amount.
+const DEFAULT_NUMBER_OF_EVENTS: usize = 50;
+
+/// Result of an integrity check of a game.
+///
+/// # Props
+///
+/// - `game_id` -> Identifier of the checked game.
+/// - `is_consistent` -> Whether no invariant was violated.
+/// - `violations` -> Descriptions of all violated invariants.
+#[derive(Serialize)]
+pub struct VerificationReport {
+    /// Identifier of the checked game
+    pub game_id: String,
+    /// Whether no invariant was violated
+    pub is_consistent: bool,
+    /// Descriptions of all violated invariants
+    pub violations: Vec<String>,
+}
+
+/// Full state of a game for a client that missed updates.
+///
+/// # Props
+///
+/// - `sequence` -> Sequence number of the game the state belongs to.
+/// - `game` -> The game as seen by the requesting player.
+#[derive(Serialize)]
+pub struct ResyncResponse {
+    /// Sequence number of the game the state belongs to
+    pub sequence: usize,
+    /// The game as seen by the requesting player
+    pub game: GameView,
+}
+
+/// Query parameters to fetch a game.
+///
+/// # Props
+///
+/// - `full` -> Returns the hands of all players, only for administrators in `get_game` and for
+///   the host in `get_players`.
+#[derive(Deserialize)]
+pub struct GameViewQuery {
+    /// Whether all hands should be shown
+    #[serde(default)]
+    pub full: bool,
+}
+
+/// Query parameters to fetch the score ledger of a game.
+///
+/// # Props
+///
+/// - `player_id` -> Optional player whose events are returned, all players when missing.
+#[derive(Deserialize)]
+pub struct ScoreLedgerQuery {
+    /// Player whose events are returned
+    pub player_id: Option<String>,
+}
+
+/// Query parameters to step through the events of a game.
+///
+/// # Props
+///
+/// - `after` -> Sequence of the last known event, the events start with the first one when it's
+///   missing.
+/// - `limit` -> Optional maximum number of events, defaults to `DEFAULT_NUMBER_OF_EVENTS`.
+#[derive(Deserialize)]
+pub struct GameEventsQuery {
+    /// Sequence of the last known event
+    pub after: Option<usize>,
+    /// Maximum number of returned events
+    pub limit: Option<usize>,
+}
+
+/// Creates a new game that waits for players, together with its chat.
+///
+/// The creator isn't part of the game yet, the first player who joins becomes the host. A game
+/// created with a `join_code` is private: it's hidden from the lobby and only players who know
+/// the code can join it. To keep clients from filling the database, every client can only create
+/// a limited number of games per time window, see `Config::create_game_rate_limit`.
+///
+/// URL endpoint: /api/game
+///
+/// # Errors
+///
+/// Returns a `TooManyRequestsError` (429) when the client created too many games recently and a
+/// `ValidationError` (422) listing every invalid field, e.g. a title that is empty or too long.
+#[worker::send]
+pub async fn create_game(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    headers: HeaderMap,
+    JsonBody(request): JsonBody<CreateGameRequest>,
+) -> Result<(StatusCode, Json<GameView>), Box<dyn ApplicationError>> {
+    config
+        .create_game_rate_limit
+        .check(&repos.rate_limits()?, "create-game", &client_key(&headers))
+        .await?;
 
-use crate::backend::types::game::Game;
+    let mut game = Game::new();
+    game.state = GameState::WaitingForPlayers;
+    game.chat.max_chat_messages = config.max_chat_messages;
+    game.settings = request.settings.clone();
+    game.deck_seed = request.deck_seed;
+    request.validate(config.max_players)?;
+    game.join_code_hash = request.join_code.as_deref().map(|join_code| hash_session_token(join_code.trim()));
+    // the title was validated above
+    game.title = request
+        .validated_title(&game.id)
+        .unwrap_or_else(|| Game::default_title(&game.id));
+
+    let mut created_game = repos.games()?.add_game(game.clone()).await?;
+    created_game.chat = repos.chats()?.create_chat(game.chat).await?;
+    repos.cards()?.create_deck(&created_game.id, &new_deck()).await?;
+
+    Ok((StatusCode::CREATED, Json(GameView::full(created_game))))
+}
 
 /// Updates a game instance and modifies the database entries by using the provided id.
 ///
@@ -24,5 +181,946 @@ pub async fn update_game(request: Request) -> Result<Json<Game>, StatusCode> {
     Err(http::StatusCode::OK)
 }
 
+/// Applies a list of explicit operations to a game, e.g. adding a player or passing the turn.
+///
+/// Alternative to `update_game` that doesn't need to guess what changed by comparing lists. The
+/// operations are validated together and stored in one batch in their order. Only the host of
+/// the game, identified by the session token in the `Authorization` header, may send them.
+///
+/// URL endpoint: /api/game/{game_id}/ops
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't the host and a `ValidationError` (422) when an
+/// operation can't be applied.
+#[worker::send]
+pub async fn apply_game_operations(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(operations): JsonBody<Vec<GameOperation>>,
+) -> Result<Json<GameView>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    if game_repo.get_host_id(&game_id).await?.as_deref() != Some(player.id.as_str()) {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't the host of the game ['{}'] and can't modify it!",
+            player.id, game_id
+        ))
+        .into());
+    }
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+    if let Err(message) = validate_operations(&game, &operations, config.max_players) {
+        return Err(ValidationError::new(message).into());
+    }
+    game_repo
+        .apply_operations(&game, &operations, &turn_deadline_from(chrono::Utc::now(), config.turn_duration_seconds))
+        .await?;
+
+    let updated_game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+
+    Ok(Json(GameView::for_player(updated_game, &player.id)))
+}
+
+/// Places a new claim of a player on the stack of a game.
+///
+/// Completes the turn of the player, so the card to play advances to the next rank. The other
+/// players can challenge the claim until the challenge window closes, afterwards the turn passes
+/// on to the next player.
+///
+/// URL endpoint: /api/game/{game_id}/claims
+///
+/// The claim is placed in the name of the player identified by the session token in the
+/// `Authorization` header.
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ConflictError` (409) when the game isn't `InProgress`, so a stale client can't add
+/// claims to a game that already ended or was aborted, or when the turn was already taken, a
+/// `NotYourTurnError` (409) with the id of the current player when it's the turn of another
+/// player, a `NotFoundError` (404) when the player isn't part of the game, a `ValidationError` (422) when the claim announces no cards and
+/// a `BadClientRequest` (400) when the announced rank isn't the card to play or the number of
+/// cards doesn't match the announcement.
+#[worker::send]
+pub async fn submit_claim(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(mut claim): JsonBody<Claim>,
+) -> Result<Json<Claim>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claims_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    // the claim is always placed in the name of the authenticated player
+    claim.created_by = player.id;
+    claim.validate_number_of_cards()?;
+
+    let state = game_repo.get_game_state(&game_id).await?;
+    if state != GameState::InProgress {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] is not in progress (state: {})! No claims can be submitted anymore.",
+            game_id, state
+        ))
+        .into());
+    }
+
+    if !player_repo.is_player_in_game(&claim.created_by, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            claim.created_by, game_id
+        ))
+        .into());
+    }
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claims_repo, &chat_message_repo, &card_repo)
+        .await?;
+    if game.which_player_turn != claim.created_by {
+        return Err(NotYourTurnError::new(claim.created_by, game.which_player_turn).into());
+    }
+    if claim.announced_rank.index() != game.card_to_play.index() {
+        return Err(BadClientRequest {
+            message: format!(
+                "The announced rank has to be the card to play ({})!",
+                game.card_to_play
+            ),
+            bad_data: Json(claim),
+        }
+        .into());
+    }
+
+    // validate the client data by creating a fresh claim
+    let new_claim = Claim::new(claim.created_by, claim.announced_rank, claim.number_of_cards, claim.cards)?;
+
+    // only one claim per turn, even when several requests arrive at the same time
+    let challenge_deadline = challenge_deadline_from(chrono::Utc::now(), config.challenge_window_seconds);
+    if !game_repo
+        .take_turn(&game_id, &new_claim.created_by, &challenge_deadline)
+        .await?
+    {
+        return Err(ConflictError::new(format!(
+            "It isn't the turn of the player with the id ['{}'] in the game ['{}'] or the turn was already taken!",
+            new_claim.created_by, game_id
+        ))
+        .into());
+    }
+
+    let created_claim = claims_repo
+        .create_claim(new_claim, &game_id, &card_repo)
+        .await?;
+    game_repo.set_current_claim(&game_id, &created_claim.id).await?;
+
+    // the turn is completed -> the next rank has to be played
+    let next_card = advance_card_to_play(&game);
+    game_repo
+        .update_game(
+            UpdateGameDTO::new(game_id.clone(), None, None, None, None, None, Some(next_card), None),
+            &player_repo,
+            &claims_repo,
+            &card_repo,
+            &chat_repo,
+            &chat_message_repo,
+        )
+        .await?;
+
+    Ok(Json(created_claim))
+}
+
+/// Resolves a challenge of a claim on the stack.
+///
+/// The loser picks up all cards of the stack and the outcome is stored with the claim. Players
+/// who emptied their hand get their finishing position. The response contains
+/// the outcome, the updated game and the new number of cards of the challenger and the accused
+/// player, so clients don't need to fetch the game again.
+///
+/// URL endpoint: /api/game/{game_id}/challenge
+///
+/// The challenge is called by the player identified by the session token in the `Authorization`
+/// header.
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ConflictError` (409) when the game isn't `InProgress`, the claim was already challenged, isn't
+/// the current claim of the game or its challenge window has closed, a `NotFoundError` (404) when
+/// the challenger isn't part of the game or the claim wasn't placed in it and a `ValidationError`
+/// (422) when a player challenges their own claim.
+#[worker::send]
+pub async fn challenge_claim(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(mut challenge): JsonBody<ChallengeRequest>,
+) -> Result<Json<ChallengeResponse>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    // the challenge is always called by the authenticated player
+    challenge.challenger_id = player.id;
+
+    let state = game_repo.get_game_state(&game_id).await?;
+    if state != GameState::InProgress {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] is not in progress (state: {})! Claims can't be challenged anymore.",
+            game_id, state
+        ))
+        .into());
+    }
+
+    if !player_repo.is_player_in_game(&challenge.challenger_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            challenge.challenger_id, game_id
+        ))
+        .into());
+    }
+
+    if !claim_repo.is_claim_in_game(&challenge.claim_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The claim with the id ['{}'] wasn't placed in the game ['{}']!",
+            challenge.claim_id, game_id
+        ))
+        .into());
+    }
+
+    let claim = claim_repo.get_claim_by_id(challenge.claim_id.clone()).await?;
+    if claim.created_by == challenge.challenger_id {
+        return Err(ValidationError::new("A player can't challenge their own claim!".to_string()).into());
+    }
+    if claim.outcome.was_challenged() {
+        return Err(ConflictError::new(format!(
+            "The claim with the id ['{}'] was already challenged (outcome: {})!",
+            claim.id, claim.outcome
+        ))
+        .into());
+    }
+    let claimed_cards = card_repo.get_all_cards(Some(claim.id.clone()), None).await?;
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+    // the window of the game belongs to the latest claim, older claims can't be challenged
+    if !game.is_current_claim(&claim.id) {
+        return Err(ConflictError::new(format!(
+            "The claim with the id ['{}'] isn't the current claim of the game ['{}'] and can't be challenged anymore!",
+            claim.id, game_id
+        ))
+        .into());
+    }
+    if !is_challenge_window_open(game.challenge_deadline.as_deref(), chrono::Utc::now()) {
+        return Err(ConflictError::new(format!(
+            "The challenge window of the game with the id ['{}'] has closed! The claim can't be challenged anymore.",
+            game_id
+        ))
+        .into());
+    }
+    let outcome = resolve_challenge(&claim, &claimed_cards, &challenge.challenger_id);
+
+    // the loser picks up the whole stack, the claim is kept for the history of the game
+    card_repo
+        .assign_claimed_cards_to_player(&game_id, &outcome.loser_id)
+        .await?;
+    claim_repo
+        .set_outcome(&claim.id, &outcome.claim_outcome(), &outcome.challenger_id)
+        .await?;
+    game_repo.set_challenge_deadline(&game_id, None).await?;
+
+    // a truthful claim leaves its creator without cards
+    let mut players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+    award_finishing_positions(&game_repo, &player_repo, &claim_repo, &repos.score_events()?, &game_id, &mut players).await?;
+
+    let updated_game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+    let card_counts = updated_game
+        .players
+        .iter()
+        .filter(|player| player.id == outcome.challenger_id || player.id == outcome.accused_id)
+        .map(|player| (player.id.clone(), player.assigned_cards.len()))
+        .collect();
+    let game = GameView::for_player(updated_game, &outcome.challenger_id);
+
+    Ok(Json(ChallengeResponse {
+        outcome,
+        game,
+        card_counts,
+    }))
+}
+
+/// Takes back the latest claim of a game while its challenge window is still open.
+///
+/// The cards of the claim return to the hand of its creator, the claim is removed from the stack
+/// and the turn is reverted, so the player can place another claim. Only the player who placed
+/// the claim can undo it. The response contains the game as seen by that player.
+///
+/// URL endpoint: /api/game/{game_id}/claims/{claim_id}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `NotFoundError` (404) when the claim wasn't placed in the game, a `ForbiddenError` (403) when
+/// the player didn't place the claim and a `ConflictError` (409) when the claim isn't the latest
+/// one, was already challenged or its challenge window has closed.
+#[worker::send]
+pub async fn undo_claim(
+    Extension(repos): Extension<Repositories>,
+    Path((game_id, claim_id)): Path<(String, String)>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<GameView>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let claim_repo = repos.claims()?;
+
+    if !claim_repo.is_claim_in_game(&claim_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The claim with the id ['{}'] wasn't placed in the game ['{}']!",
+            claim_id, game_id
+        ))
+        .into());
+    }
+
+    let claim = claim_repo.get_claim_by_id(claim_id.clone()).await?;
+    if claim.created_by != player.id {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] didn't place the claim ['{}'] and can't undo it!",
+            player.id, claim_id
+        ))
+        .into());
+    }
+
+    if claim.outcome.was_challenged()
+        || claim_repo.get_latest_claim_id(&game_id).await?.as_deref() != Some(claim_id.as_str())
+    {
+        return Err(ConflictError::new(format!(
+            "The claim with the id ['{}'] isn't the latest unchallenged claim of the game ['{}'] and can't be undone!",
+            claim_id, game_id
+        ))
+        .into());
+    }
+
+    if !game_repo
+        .undo_claim(&game_id, &claim, &chrono::Utc::now().to_string())
+        .await?
+    {
+        return Err(ConflictError::new(format!(
+            "The challenge window of the claim with the id ['{}'] has closed! The claim can't be undone anymore.",
+            claim_id
+        ))
+        .into());
+    }
+
+    let game = game_repo
+        .get_game_by_id(
+            &game_id,
+            &repos.chats()?,
+            &repos.players()?,
+            &claim_repo,
+            &repos.chat_messages()?,
+            &repos.cards()?,
+        )
+        .await?;
+
+    Ok(Json(GameView::for_player(game, &player.id)))
+}
+
+/// Returns the score ledger of a game, so the scoreboard of every round can be shown.
+///
+/// URL endpoint: /api/game/{game_id}/scores
+#[worker::send]
+pub async fn get_score_ledger(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    Query(query): Query<ScoreLedgerQuery>,
+) -> Result<Json<Vec<ScoreEvent>>, Box<dyn ApplicationError>> {
+    Ok(Json(
+        repos
+            .score_events()?
+            .get_score_events(&game_id, query.player_id.as_deref())
+            .await?,
+    ))
+}
+
+/// Returns the next recorded events of a game, e.g. for a replay viewer.
+///
+/// A viewer steps through a game by passing the sequence of the last event it received as
+/// `?after=`. An empty list means that there are no newer events.
+///
+/// URL endpoint: /api/game/{game_id}/events
+#[worker::send]
+pub async fn get_game_events(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    Query(query): Query<GameEventsQuery>,
+) -> Result<Json<Vec<GameEvent>>, Box<dyn ApplicationError>> {
+    let limit = query.limit.unwrap_or(DEFAULT_NUMBER_OF_EVENTS).clamp(1, MAX_PAGE_SIZE);
+
+    Ok(Json(
+        repos
+            .game_events()?
+            .get_events_after(&game_id, query.after.unwrap_or(0), limit)
+            .await?,
+    ))
+}
+
+/// Returns the final ranking of the players of an ended game.
+///
+/// URL endpoint: /api/game/{game_id}/scoreboard
+///
+/// # Errors
+///
+/// Returns a `ConflictError` (409) when the game hasn't `Ended` yet.
+#[worker::send]
+pub async fn get_scoreboard(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+) -> Result<Json<Scoreboard>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let card_repo = repos.cards()?;
+
+    let state = game_repo.get_game_state(&game_id).await?;
+    if state != GameState::Ended {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] hasn't ended yet (state: {})! There is no final scoreboard.",
+            game_id, state
+        ))
+        .into());
+    }
+
+    let players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+
+    Ok(Json(Scoreboard {
+        entries: rank_players(&players),
+        game_id,
+    }))
+}
+
+/// Returns all claims of a game including their outcome, so the history can be annotated.
+///
+/// Like in the `GameView` the cards of a claim can only be seen once it was challenged, before
+/// only by the player who placed it; for everybody else the `cards` are left empty. The request
+/// has to contain the session token of a player of the game in the `Authorization` header.
+///
+/// URL endpoint: /api/game/{game_id}/claims
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid and a
+/// `ForbiddenError` (403) when the player isn't part of the game.
+#[worker::send]
+pub async fn get_claims(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<Vec<Claim>>, Box<dyn ApplicationError>> {
+    let claim_repo = repos.claims()?;
+    let card_repo = repos.cards()?;
+
+    if player.game_id != game_id {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            player.id, game_id
+        ))
+        .into());
+    }
+
+    let mut claims = claim_repo.get_all_claims(Some(&game_id), None, &card_repo).await?;
+    for claim in claims
+        .iter_mut()
+        .filter(|claim| claim.created_by != player.id && !claim.outcome.was_challenged())
+    {
+        claim.cards.clear();
+    }
+
+    Ok(Json(claims))
+}
+
+/// Resets the current round of a game.
+///
+/// All cards are collected back from the hands and claims, the claims are removed and the
+/// shuffled deck is dealt again to the players. The new deal counts as the next round, so the
+/// round number is incremented and players who forfeited or finished the last round take part
+/// again. Games created with a `deck_seed` get the same deal for the same round.
+///
+/// The round starts over with `Dealing` and the `King` to play, and moves on to `Playing` once
+/// the cards are dealt. The first player in the seating order takes the first turn. Only the
+/// host of the game, identified by the session token in the `Authorization` header, may reset it.
+///
+/// URL endpoint: /api/game/{game_id}/reset
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid and a
+/// `ForbiddenError` (403) when the player isn't the host.
+#[worker::send]
+pub async fn reset_game(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<Vec<Player>>, Box<dyn ApplicationError>> {
+    let card_repo = repos.cards()?;
+    let claims_repo = repos.claims()?;
+    let player_repo = repos.players()?;
+    let game_repo = repos.games()?;
+
+    if game_repo.get_host_id(&game_id).await?.as_deref() != Some(player.id.as_str()) {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't the host of the game ['{}'] and can't reset it!",
+            player.id, game_id
+        ))
+        .into());
+    }
+
+    // collect all cards before the claims referencing them are removed
+    card_repo.clear_all_owners(&game_id).await?;
+    claims_repo.delete_all_claims_of_game(&game_id).await?;
+    let round_number = game_repo.start_next_round(&game_id).await?;
+    // players who forfeited or finished the last round rejoin the new one
+    player_repo.clear_round_results(&game_id).await?;
+
+    let mut deck = card_repo.get_all_cards_of_game(&game_id).await?;
+    shuffle_deck(&mut deck, game_repo.get_deck_seed(&game_id).await?, round_number);
+
+    let players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+    for assignment in deal_cards(&deck, &players)? {
+        card_repo
+            .transition_card(&assignment.id, CardLocation::Hand, assignment.player_id.as_deref())
+            .await?;
+    }
+    game_repo.set_phase(&game_id, &GamePhase::Dealing, &GamePhase::Playing).await?;
+
+    if let Some(first_player) = players.first() {
+        game_repo
+            .set_turn(&game_id, &first_player.id, &turn_deadline_from(chrono::Utc::now(), config.turn_duration_seconds))
+            .await?;
+    }
+
+    Ok(Json(player_repo.get_all_players(Some(&game_id), &card_repo).await?))
+}
+
+/// Lists the public games for the lobby.
+///
+/// Private games, which can only be joined with a join code, are left out. The cards of the
+/// players are hidden, except for the requesting player, who is identified by the optional
+/// session token in the `Authorization` header. The list is empty when there are no public games.
+///
+/// URL endpoint: /api/games
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when a session token is sent but invalid.
+#[worker::send]
+pub async fn get_games(
+    Extension(repos): Extension<Repositories>,
+    viewer: Option<AuthenticatedPlayer>,
+    format: ResponseFormat,
+) -> Result<Response, Box<dyn ApplicationError>> {
+    // without a session token no cards are shown
+    let viewer_id = viewer.map(|AuthenticatedPlayer(player)| player.id).unwrap_or_default();
+
+    let games = repos
+        .games()?
+        .get_all_games(
+            &repos.players()?,
+            &repos.cards()?,
+            &repos.claims()?,
+            &repos.chats()?,
+            &repos.chat_messages()?,
+        )
+        .await?;
+
+    Ok(format.respond(
+        games
+            .into_iter()
+            .map(|game| GameView::for_player(game, &viewer_id))
+            .collect::<Vec<GameView>>(),
+    ))
+}
+
+/// Returns a game as seen by a single player.
+///
+/// The player is identified by the session token in the `Authorization` header, the cards of the
+/// other players are hidden. Administrators can request the hands of all players with
+/// `?full=true`, see `AdminAccess`. The body is encoded as MessagePack when the client sends
+/// `Accept: application/msgpack`.
+///
+/// A player whose cards can't be loaded is shown with an empty hand when `degrade_failed_hands`
+/// is configured.
+///
+/// URL endpoint: /api/game/{game_id}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the full view is requested without a valid admin token, a 404
+/// error when the game never existed and a `GoneError` (410) when it was archived.
+#[worker::send]
+pub async fn get_game(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+    Query(query): Query<GameViewQuery>,
+    viewer: Option<AuthenticatedPlayer>,
+    admin: Option<AdminAccess>,
+    format: ResponseFormat,
+) -> Result<Response, Box<dyn ApplicationError>> {
+    // `None` when all hands are shown
+    let viewer_id = match (query.full, admin, viewer) {
+        (true, Some(_), _) => None,
+        (true, None, _) => {
+            return Err(ForbiddenError::new("Only administrators can see the cards of all players!".to_string()).into())
+        }
+        (false, _, Some(AuthenticatedPlayer(player))) => Some(player.id),
+        (false, _, None) => {
+            return Err(UnauthorizedError::new("The request contains no session token!".to_string()).into())
+        }
+    };
+
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?.with_degraded_hands(config.degrade_failed_hands);
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+    if game.is_archived() {
+        return Err(GoneError::new(format!(
+            "The game with the id ['{}'] was archived!",
+            game_id
+        ))
+        .into());
+    }
+
+    match viewer_id {
+        Some(viewer_id) => Ok(format.respond(GameView::for_player(game, &viewer_id))),
+        None => Ok(format.respond(GameView::full(game))),
+    }
+}
+
+/// Returns the full state of a game together with its current sequence number.
+///
+/// A client that detects a gap in the sequence numbers discards its local state and starts again
+/// from this one. The sequence is read together with the game row before the players, claims and
+/// chat are loaded, so the state is never older than the sequence; at worst the client applies an
+/// update it already contains.
+///
+/// The request has to contain the session token of the player in the `Authorization` header, the
+/// cards of the other players are hidden.
+///
+/// URL endpoint: /api/game/{game_id}/resync
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid and a
+/// `ForbiddenError` (403) when the player isn't part of the game.
+#[worker::send]
+pub async fn resync_game(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<ResyncResponse>, Box<dyn ApplicationError>> {
+    if player.game_id != game_id {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            player.id, game_id
+        ))
+        .into());
+    }
+
+    let game = repos
+        .games()?
+        .get_game_by_id(
+            &game_id,
+            &repos.chats()?,
+            &repos.players()?,
+            &repos.claims()?,
+            &repos.chat_messages()?,
+            &repos.cards()?,
+        )
+        .await?;
+
+    Ok(Json(ResyncResponse {
+        sequence: game.sequence,
+        game: GameView::for_player(game, &player.id),
+    }))
+}
+
+/// Renames a game. Only the host of the game, identified by the session token in the
+/// `Authorization` header, is allowed to do so.
+///
+/// URL endpoint: /api/game/{game_id}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ValidationError` (422) listing every invalid field, e.g. a title that is empty or too long,
+/// and a `ForbiddenError` (403) when the requesting player isn't the host.
+#[worker::send]
+pub async fn rename_game(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(request): JsonBody<RenameGameRequest>,
+) -> Result<Json<GameView>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    request.validate()?;
+
+    if game_repo.get_host_id(&game_id).await?.as_deref() != Some(player.id.as_str()) {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't the host of the game ['{}'] and can't rename it!",
+            player.id, game_id
+        ))
+        .into());
+    }
+
+    // the title was validated above
+    let title = request.validated_title().unwrap_or_default();
+    game_repo.set_title(&game_id, &title).await?;
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+
+    Ok(Json(GameView::for_player(game, &player.id)))
+}
+
+/// Passes the host role of a game on to another player of the game.
+///
+/// Only the current host can do so. The response contains the game as seen by the former host.
+///
+/// URL endpoint: /api/game/{game_id}/host
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when the player isn't the host and a `ValidationError` (422) when the
+/// new host isn't part of the game.
+#[worker::send]
+pub async fn transfer_host(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    JsonBody(request): JsonBody<TransferHostRequest>,
+) -> Result<Json<GameView>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    if game_repo.get_host_id(&game_id).await?.as_deref() != Some(player.id.as_str()) {
+        return Err(ForbiddenError::new(format!(
+            "The player with the id ['{}'] isn't the host of the game ['{}'] and can't pass the host role on!",
+            player.id, game_id
+        ))
+        .into());
+    }
+
+    if !game_repo.set_host(&game_id, &request.player_id).await? {
+        return Err(ValidationError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            request.player_id, game_id
+        ))
+        .into());
+    }
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+
+    Ok(Json(GameView::for_player(game, &player.id)))
+}
+
+/// Returns a `GameSummary` without loading the players, claims and chat of the game.
+///
+/// URL endpoint: /api/game/{game_id}/summary
+#[worker::send]
+pub async fn get_game_summary(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+) -> Result<Json<GameSummary>, Box<dyn ApplicationError>> {
+    match repos.games()?.get_summaries(std::slice::from_ref(&game_id)).await?.pop() {
+        Some(summary) => Ok(Json(summary)),
+        None => Err(NotFoundError::new(format!("The game with the id ['{}'] doesn't exist!", game_id)).into()),
+    }
+}
+
+/// Lists the players of a game with their scores and the number of their cards.
+///
+/// The cards themselves are hidden, except for the requesting player, who is identified by the
+/// session token in the `Authorization` header. The host can request the hands of all players
+/// with `?full=true`. The body is encoded as MessagePack when the client sends
+/// `Accept: application/msgpack`.
+///
+/// URL endpoint: /api/game/{game_id}/players
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when a session token is sent but invalid and a
+/// `ForbiddenError` (403) when the full view is requested by anybody but the host.
+#[worker::send]
+pub async fn get_players(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+    Query(query): Query<GameViewQuery>,
+    viewer: Option<AuthenticatedPlayer>,
+    format: ResponseFormat,
+) -> Result<Response, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let card_repo = repos.cards()?;
+
+    // without a session token no cards are shown
+    let player_id = viewer.map(|AuthenticatedPlayer(player)| player.id).unwrap_or_default();
+    if query.full {
+        let host_id = game_repo.get_host_id(&game_id).await?;
+        if player_id.is_empty() || host_id.as_deref() != Some(player_id.as_str()) {
+            return Err(ForbiddenError::new(format!(
+                "Only the host of the game ['{}'] can see the cards of all players!",
+                game_id
+            ))
+            .into());
+        }
+    }
+
+    let players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+    if query.full {
+        return Ok(format.respond(players));
+    }
+
+    Ok(format.respond(
+        players
+            .into_iter()
+            .map(|player| player.redact_for(&player_id))
+            .collect::<Vec<Player>>(),
+    ))
+}
+
+/// Checks the invariants of a game that can be violated, because an update touches several
+/// tables one after another.
+///
+/// - The `number_of_messages` of the chat equals the number of stored messages.
+/// - Every card in a claim of the game belongs to the game.
+/// - The player whose turn it is, is part of the game.
+///
+/// URL endpoint: /api/game/{game_id}/verify
+#[worker::send]
+pub async fn verify_game(
+    Extension(repos): Extension<Repositories>,
+    Path(game_id): Path<String>,
+) -> Result<Json<VerificationReport>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+    let mut violations = vec![];
+
+    let stored_messages = chat_message_repo
+        .get_all_messages_in_chat(&game.chat.id)
+        .await?
+        .len();
+    if stored_messages != game.chat.number_of_messages {
+        violations.push(format!(
+            "The chat ['{}'] counts {} messages, but {} messages are stored!",
+            game.chat.id, game.chat.number_of_messages, stored_messages
+        ));
+    }
+
+    for card in card_repo.get_claimed_cards_of_other_games(&game_id).await? {
+        violations.push(format!(
+            "The card ['{}'] is placed in a claim of the game, but doesn't belong to it!",
+            card.id
+        ));
+    }
+
+    let turn_is_assigned = game.state == GameState::InProgress || !game.which_player_turn.is_empty();
+    if turn_is_assigned && !game.players.iter().any(|player| player.id == game.which_player_turn) {
+        violations.push(format!(
+            "It's the turn of the player ['{}'], who isn't part of the game!",
+            game.which_player_turn
+        ));
+    }
+
+    Ok(Json(VerificationReport {
+        game_id,
+        is_consistent: violations.is_empty(),
+        violations,
+    }))
+}
+
+/// Passes the turn of a game on to the next player when the current player missed the turn
+/// deadline.
+///
+/// Manual alternative to the scheduled Cron trigger, which does the same for all games.
+///
+/// URL endpoint: /api/game/{game_id}/tick
+#[worker::send]
+pub async fn tick(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+) -> Result<Json<GameView>, Box<dyn ApplicationError>> {
+    advance_expired_turns(repos.env(), &config, Some(&game_id)).await?;
+
+    let game_repo = repos.games()?;
+    let chat_repo = repos.chats()?;
+    let player_repo = repos.players()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    let game = game_repo
+        .get_game_by_id(
+            &game_id,
+            &chat_repo,
+            &player_repo,
+            &claim_repo,
+            &chat_message_repo,
+            &card_repo,
+        )
+        .await?;
+
+    // nobody is identified by a tick, so only the public parts of the game are sent
+    Ok(Json(GameView::for_player(game, "")))
+}
+
     }
 }
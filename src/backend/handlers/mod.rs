@@ -1,4 +1,7 @@
+pub mod admin_handlers;
+pub mod card_handlers;
 pub mod chat_handlers;
+pub mod claim_handlers;
 pub mod game_handlers;
 pub mod player_handlers;
 pub mod status_handlers;
@@ -1 +1,902 @@
+use cfg_if::cfg_if;
 
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+use axum::{
+    extract::{Path, Query, RawQuery},
+    http::HeaderMap,
+    response::Response,
+    Extension, Json,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::backend::{
+    enums::{
+        card_location::CardLocation, game_phase::GamePhase, game_state::GameState,
+        player_color::PlayerColor,
+    },
+    errors::{
+        application_error::ApplicationError, conflict_error::ConflictError,
+        forbidden_error::ForbiddenError, not_found_error::NotFoundError,
+        not_your_turn_error::NotYourTurnError, process_error::ProcessError,
+        validation_error::ValidationError,
+    },
+    handlers::chat_handlers::IDEMPOTENCY_KEY_HEADER,
+    logic::game_logic::{
+        deal_cards, late_join_hand_size, next_host, next_player_turn, should_auto_start,
+        shuffle_deck, turn_deadline_from,
+    },
+    middleware::{
+        authentication::AuthenticatedPlayer, json_body::JsonBody, response_format::ResponseFormat,
+    },
+    repositories::{game_repository::GameRepository, player_repository::PlayerRepository},
+    types::{
+        card::Card,
+        game::GameSummary,
+        game_view::GameView,
+        player::{JoinGameRequest, JoinGameResponse, Player, PlayerBatchRequest, UpdatePlayerDTO},
+    },
+    utils::{
+        config::Config,
+        pagination::Page,
+        repository_provider::Repositories,
+        session_token::{
+            generate_session_token, hash_session_token, session_token_for_join_key,
+            MIN_JOIN_KEY_LENGTH,
+        },
+        turn_service::award_finishing_positions,
+    },
+};
+
+// constants
+
+/// Number of games listed per page when the client doesn't ask for a specific amount.
+const DEFAULT_NUMBER_OF_LISTED_GAMES: usize = 20;
+
+/// Query parameters to list the games of a player.
+///
+/// # Props
+///
+/// - `state` -> Optional state the listed games have to be in.
+/// - `limit` -> Optional maximum number of games, defaults to `DEFAULT_NUMBER_OF_LISTED_GAMES`.
+/// - `offset` -> Optional number of skipped games, defaults to 0.
+#[derive(Deserialize)]
+pub struct PlayerGamesQuery {
+    /// State the listed games have to be in
+    pub state: Option<GameState>,
+    /// Maximum number of listed games
+    pub limit: Option<usize>,
+    /// Number of skipped games
+    pub offset: Option<usize>,
+}
+
+/// Adds a new player to a game that is still waiting for players.
+///
+/// When the settings of the game allow late joins, a game in progress can be joined as well.
+/// The player is dealt as many cards from the deck as the smallest hand of the other players.
+/// The player and the cards are stored together, so a failed deal doesn't leave a player
+/// without a hand.
+///
+/// The response contains the secret session token of the player. Only its hash is stored, so the
+/// client has to keep the token to act in the name of the player.
+///
+/// The player gets the requested color when no other player of the game has it, otherwise the
+/// first free color of the palette.
+///
+/// A client can send an `Idempotency-Key` header of at least `MIN_JOIN_KEY_LENGTH` characters
+/// with the join. A join with a key that was already used in the game counts as a retry: the
+/// existing player is returned instead of adding the player twice. The session token is derived
+/// from the key, so the retry gets the same token and the token of the player never changes.
+///
+/// A private game can only be joined with its join code.
+///
+/// URL endpoint: /api/game/{game_id}/players
+///
+/// # Errors
+///
+/// Returns a `ForbiddenError` (403) when the game is private and the join code is missing or
+/// wrong, a `ConflictError` (409) when the game isn't waiting for players and doesn't allow late
+/// joins or is full and a `ValidationError` (422) when the name is empty or the idempotency key
+/// is too short.
+#[worker::send]
+pub async fn join_game(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+    headers: HeaderMap,
+    JsonBody(request): JsonBody<JoinGameRequest>,
+) -> Result<Json<JoinGameResponse>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let card_repo = repos.cards()?;
+
+    let state = game_repo.get_game_state(&game_id).await?;
+    check_join_code(&game_repo, &game_id, request.join_code.as_deref()).await?;
+
+    let join_key = join_key_from_headers(&headers)?;
+    let join_key_hash = join_key.map(hash_session_token);
+    // a retry is answered like the first join, even when that one filled or started the game
+    if let (Some(join_key), Some(join_key_hash)) = (join_key, &join_key_hash) {
+        if let Some(player) = player_repo.get_player_by_join_key_hash(&game_id, join_key_hash).await? {
+            return Ok(Json(JoinGameResponse {
+                player,
+                session_token: session_token_for_join_key(join_key),
+            }));
+        }
+    }
+
+    // a running game is only joined when its settings allow it
+    let running_game = match state {
+        GameState::InProgress => {
+            let game = game_repo
+                .get_game_by_id(
+                    &game_id,
+                    &repos.chats()?,
+                    &player_repo,
+                    &repos.claims()?,
+                    &repos.chat_messages()?,
+                    &card_repo,
+                )
+                .await?;
+            game.settings.allow_late_join.then_some(game)
+        }
+        _ => None,
+    };
+    if state != GameState::WaitingForPlayers && running_game.is_none() {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] isn't waiting for players (state: {})!",
+            game_id, state
+        ))
+        .into());
+    }
+
+    request.validate()?;
+    let name = request.name.trim().to_string();
+
+    let players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+    if players.len() >= config.max_players {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] already has {} players!",
+            game_id, config.max_players
+        ))
+        .into());
+    }
+
+    let color = PlayerColor::assign(request.color.as_ref(), &taken_colors(&players));
+    let mut new_player = Player::new(name, game_id.clone(), color);
+    new_player.ready_at = request.ready.then(|| chrono::Utc::now().to_string());
+    let player = match running_game {
+        None => {
+            let player = match &join_key_hash {
+                Some(join_key_hash) => player_repo.add_player_once(new_player, join_key_hash).await?,
+                None => player_repo.add_player(new_player).await?,
+            };
+            auto_start_game(&repos, &config, &game_id).await?;
+            player
+        }
+        Some(game) => {
+            let hand_size = late_join_hand_size(&game.players, game.card_counts.deck_remaining);
+            player_repo
+                .add_player_with_hand(new_player, hand_size, join_key_hash.as_deref(), &card_repo)
+                .await?
+        }
+    };
+
+    Ok(Json(start_session(&player_repo, player, join_key).await?))
+}
+
+/// Reads the idempotency key of a join from the `Idempotency-Key` header.
+///
+/// # Errors
+///
+/// Returns a `ValidationError` when the key is shorter than `MIN_JOIN_KEY_LENGTH`, because the
+/// session token of the player is derived from it.
+fn join_key_from_headers(headers: &HeaderMap) -> Result<Option<&str>, ValidationError> {
+    let join_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim);
+
+    let mut field_errors = BTreeMap::new();
+    if join_key.is_some_and(|join_key| join_key.len() < MIN_JOIN_KEY_LENGTH) {
+        field_errors.insert(
+            IDEMPOTENCY_KEY_HEADER.to_string(),
+            format!("The idempotency key of a join needs at least {} characters!", MIN_JOIN_KEY_LENGTH),
+        );
+    }
+    ValidationError::check_fields(field_errors)?;
+
+    Ok(join_key)
+}
+
+/// Joins a game only by a name, so casual players don't need to keep track of their id.
+///
+/// While the game waits for players, a player with the name whose session is inactive is taken
+/// over and gets a new session token. The earliest joined of these players is chosen. Once the
+/// game started, the player holds a hand, so it can only be taken over with the session token of
+/// the player in the `Authorization` header. Otherwise a new player is created; when the name is
+/// used by an active player, the first free name of the form 'Name (2)', 'Name (3)', ... is used.
+///
+/// Like `join_game`, a private game can only be joined with its join code.
+///
+/// URL endpoint: /api/game/{game_id}/quick-join
+///
+/// # Errors
+///
+/// Returns a `ForbiddenError` (403) when the game is private and the join code is missing or
+/// wrong or when an inactive player of a running game is taken over without their session token,
+/// a `ValidationError` (422) when the name is empty and a `ConflictError` (409) when a new player
+/// is needed but the game isn't waiting for players or is full.
+#[worker::send]
+pub async fn quick_join_game(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path(game_id): Path<String>,
+    authenticated: Option<AuthenticatedPlayer>,
+    JsonBody(request): JsonBody<JoinGameRequest>,
+) -> Result<Json<JoinGameResponse>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let card_repo = repos.cards()?;
+
+    check_join_code(&game_repo, &game_id, request.join_code.as_deref()).await?;
+    request.validate()?;
+    let name = request.name.trim().to_string();
+
+    // ordered by the time the players joined
+    let players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+    let state = game_repo.get_game_state(&game_id).await?;
+
+    // rejoin as a player whose session expired
+    let now = chrono::Utc::now();
+    let own_player = authenticated.as_ref().and_then(|AuthenticatedPlayer(authenticated_player)| {
+        players
+            .iter()
+            .find(|player| player.id == authenticated_player.id && player.name == name)
+    });
+    let inactive_player = players
+        .iter()
+        .find(|player| player.name == name && !player.has_active_session(now));
+    let rejoined_player = match (own_player, inactive_player) {
+        (Some(player), _) => Some(player),
+        (None, Some(player)) if state == GameState::WaitingForPlayers => Some(player),
+        (None, Some(_)) => {
+            return Err(ForbiddenError::new(format!(
+                "The game with the id ['{}'] already started (state: {})! The player '{}' can only rejoin with their session token.",
+                game_id, state, name
+            ))
+            .into())
+        }
+        (None, None) => None,
+    };
+    if let Some(player) = rejoined_player {
+        let (player, _) = player_repo
+            .update_player(UpdatePlayerDTO::new(player.id.clone(), None, Some(now.to_string())))
+            .await?;
+        return Ok(Json(start_session(&player_repo, player, None).await?));
+    }
+
+    if state != GameState::WaitingForPlayers {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] isn't waiting for players (state: {})!",
+            game_id, state
+        ))
+        .into());
+    }
+    if players.len() >= config.max_players {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] already has {} players!",
+            game_id, config.max_players
+        ))
+        .into());
+    }
+
+    let color = PlayerColor::assign(request.color.as_ref(), &taken_colors(&players));
+    let mut new_player = Player::new(free_player_name(&players, &name), game_id.clone(), color);
+    new_player.ready_at = request.ready.then(|| chrono::Utc::now().to_string());
+    let player = player_repo.add_player(new_player).await?;
+    auto_start_game(&repos, &config, &game_id).await?;
+
+    Ok(Json(start_session(&player_repo, player, None).await?))
+}
+
+/// Checks the join code sent by a player against the one of a private game.
+///
+/// Public games can be joined without a code, a code sent for them is ignored.
+async fn check_join_code(
+    game_repo: &GameRepository,
+    game_id: &str,
+    join_code: Option<&str>,
+) -> Result<(), Box<dyn ApplicationError>> {
+    let Some(join_code_hash) = game_repo.get_join_code_hash(game_id).await? else {
+        return Ok(());
+    };
+
+    match join_code {
+        Some(join_code) if hash_session_token(join_code.trim()) == join_code_hash => Ok(()),
+        _ => Err(ForbiddenError::new(format!(
+            "The game with the id ['{}'] is private and needs a matching join code!",
+            game_id
+        ))
+        .into()),
+    }
+}
+
+/// Starts a waiting game and deals the cards once it reached the `auto_start_at` number of
+/// players of its settings and all players are ready.
+///
+/// Games without `auto_start_at` are started by the host. The first joined player takes the
+/// first turn.
+async fn auto_start_game(
+    repos: &Repositories,
+    config: &Config,
+    game_id: &str,
+) -> Result<(), Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let card_repo = repos.cards()?;
+
+    let players = player_repo.get_all_players(Some(game_id), &card_repo).await?;
+    if !should_auto_start(game_repo.get_settings(game_id).await?.auto_start_at, &players) {
+        return Ok(());
+    }
+
+    // a concurrent join may have started the game already
+    let round_number = match game_repo.start_waiting_game(game_id).await? {
+        Some(round_number) => round_number,
+        None => return Ok(()),
+    };
+
+    let mut deck = card_repo.get_all_cards_of_game(game_id).await?;
+    shuffle_deck(&mut deck, game_repo.get_deck_seed(game_id).await?, round_number);
+
+    for assignment in deal_cards(&deck, &players)? {
+        card_repo
+            .transition_card(&assignment.id, CardLocation::Hand, assignment.player_id.as_deref())
+            .await?;
+    }
+    game_repo.set_phase(game_id, &GamePhase::Dealing, &GamePhase::Playing).await?;
+
+    if let Some(first_player) = players.first() {
+        game_repo
+            .set_turn(game_id, &first_player.id, &turn_deadline_from(chrono::Utc::now(), config.turn_duration_seconds))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Returns several players at once, e.g. to render a scoreboard.
+///
+/// The hands of the players are hidden, only their number of cards is sent. Unknown ids are
+/// skipped, the players are returned in the order of the requested ids.
+///
+/// URL endpoint: /api/players/batch
+///
+/// # Errors
+///
+/// Returns a `ValidationError` (422) when more than `MAX_PAGE_SIZE` players are requested.
+#[worker::send]
+pub async fn get_players_batch(
+    Extension(repos): Extension<Repositories>,
+    JsonBody(request): JsonBody<PlayerBatchRequest>,
+) -> Result<Json<Vec<Player>>, Box<dyn ApplicationError>> {
+    request.validate()?;
+
+    Ok(Json(repos.players()?.get_players_by_ids(&request.ids).await?))
+}
+
+/// Hands out a new session token to a player and stores its hash.
+///
+/// A previous token of the player becomes invalid. A player who joined with an idempotency key
+/// gets the token derived from the key, see `session_token_for_join_key`.
+async fn start_session(
+    player_repo: &PlayerRepository,
+    player: Player,
+    join_key: Option<&str>,
+) -> Result<JoinGameResponse, Box<dyn ApplicationError>> {
+    let session_token = match join_key.map(session_token_for_join_key).or_else(generate_session_token) {
+        Some(session_token) => session_token,
+        None => {
+            return Err(ProcessError::<Player>::new(
+                "Couldn't create a session token for the player!".to_string(),
+                "player_handlers::start_session".to_string(),
+                Some(player),
+            )
+            .into())
+        }
+    };
+
+    player_repo
+        .set_session_token_hash(&player.id, &hash_session_token(&session_token))
+        .await?;
+
+    Ok(JoinGameResponse {
+        player,
+        session_token,
+    })
+}
+
+/// Returns the name itself when no player of the game uses it, otherwise the first free name of
+/// the form 'Name (2)', 'Name (3)', ...
+fn free_player_name(players: &[Player], name: &str) -> String {
+    let is_taken = |candidate: &str| players.iter().any(|player| player.name == candidate);
+    if !is_taken(name) {
+        return name.to_string();
+    }
+
+    (2..)
+        .map(|number| format!("{} ({})", name, number))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Returns the colors that are already used by the players of a game.
+fn taken_colors(players: &[Player]) -> Vec<PlayerColor> {
+    players.iter().map(|player| player.color.clone()).collect()
+}
+
+/// Removes a player from a game that is still waiting for players.
+///
+/// When the host leaves, the next player in the seating order becomes the host.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/game/{game_id}/players/{player_id}
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when it belongs to another player, a `NotFoundError` (404) when the
+/// player isn't part of the game and a `ConflictError` (409) when the game already started.
+#[worker::send]
+pub async fn leave_game(
+    Extension(repos): Extension<Repositories>,
+    Path((game_id, player_id)): Path<(String, String)>,
+    AuthenticatedPlayer(authenticated_player): AuthenticatedPlayer,
+) -> Result<Json<Player>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+
+    if authenticated_player.id != player_id {
+        return Err(ForbiddenError::new(format!(
+            "Players can only remove themselves from a game, not the player with the id ['{}']!",
+            player_id
+        ))
+        .into());
+    }
+
+    if !player_repo.is_player_in_game(&player_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    // players of a running game hold cards and claims, so they can only leave the lobby
+    let state = game_repo.get_game_state(&game_id).await?;
+    if state != GameState::WaitingForPlayers {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] already started (state: {})! Players can't leave it anymore.",
+            game_id, state
+        ))
+        .into());
+    }
+
+    // the game has to stay manageable, so the host role is passed on first
+    if game_repo.get_host_id(&game_id).await?.as_deref() == Some(player_id.as_str()) {
+        let players = player_repo.get_all_players(Some(&game_id), &repos.cards()?).await?;
+        if let Some(new_host) = next_host(&players, &player_id) {
+            game_repo.set_host(&game_id, &new_host.id).await?;
+        }
+    }
+
+    let player = player_repo.get_player(&player_id).await?;
+    if !player_repo.delete_player(&player_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] was already removed from the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    Ok(Json(player))
+}
+
+/// Marks a player of a waiting game as ready to start it.
+///
+/// A game with `auto_start_at` in its settings starts and deals the cards once it has enough
+/// players and the last of them is ready. Marking a player who already is ready changes nothing.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/game/{game_id}/players/{player_id}/ready
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when it belongs to another player, a `NotFoundError` (404) when the
+/// player isn't part of the game and a `ConflictError` (409) when the game already started.
+#[worker::send]
+pub async fn mark_ready(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path((game_id, player_id)): Path<(String, String)>,
+    AuthenticatedPlayer(authenticated_player): AuthenticatedPlayer,
+) -> Result<Json<Player>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+
+    if authenticated_player.id != player_id {
+        return Err(ForbiddenError::new(format!(
+            "Players can only mark themselves as ready, not the player with the id ['{}']!",
+            player_id
+        ))
+        .into());
+    }
+
+    if !player_repo.is_player_in_game(&player_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    let state = game_repo.get_game_state(&game_id).await?;
+    if state != GameState::WaitingForPlayers {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] already started (state: {})!",
+            game_id, state
+        ))
+        .into());
+    }
+
+    if player_repo.set_ready(&player_id, &game_id, &chrono::Utc::now().to_string()).await? {
+        auto_start_game(&repos, &config, &game_id).await?;
+    }
+
+    Ok(Json(player_repo.get_player(&player_id).await?))
+}
+
+/// Takes a player out of the current round of a running game without leaving the game.
+///
+/// The player is skipped in the turn rotation until the next round is dealt with `reset_game`.
+/// When it's their turn, it's passed on right away unless their claim can still be challenged.
+/// If only one player is left in the round, that player finishes it: when nobody finished before,
+/// they win the round and get `ROUND_WIN_POINTS`, and the game ends with the `Scoring` phase,
+/// see `award_finishing_positions`. The response contains the game as seen by the player who
+/// forfeited.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/game/{game_id}/players/{player_id}/forfeit
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when it belongs to another player, a `NotFoundError` (404) when the
+/// player isn't part of the game and a `ConflictError` (409) when the game isn't `InProgress`,
+/// the round is already being scored, the player already forfeited or is the last one left in
+/// the round.
+#[worker::send]
+pub async fn forfeit_round(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path((game_id, player_id)): Path<(String, String)>,
+    AuthenticatedPlayer(authenticated_player): AuthenticatedPlayer,
+) -> Result<Json<GameView>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let chat_repo = repos.chats()?;
+    let claim_repo = repos.claims()?;
+    let chat_message_repo = repos.chat_messages()?;
+    let card_repo = repos.cards()?;
+
+    if authenticated_player.id != player_id {
+        return Err(ForbiddenError::new(format!(
+            "Players can only forfeit a round themselves, not for the player with the id ['{}']!",
+            player_id
+        ))
+        .into());
+    }
+
+    if !player_repo.is_player_in_game(&player_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    let game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+    if game.state != GameState::InProgress || game.phase == GamePhase::Scoring {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] has no round that is being played (state: {}, phase: {})! The round can't be forfeited.",
+            game_id, game.state, game.phase
+        ))
+        .into());
+    }
+
+    let remaining = game
+        .players
+        .iter()
+        .filter(|player| !player.has_forfeited() && player.finished_position.is_none())
+        .count();
+    if remaining <= 1 {
+        return Err(ConflictError::new(format!(
+            "The player with the id ['{}'] is the last one left in the round of the game ['{}'] and can't forfeit it!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    let now = chrono::Utc::now();
+    if !player_repo.forfeit_round(&player_id, &game_id, &now.to_string()).await? {
+        return Err(ConflictError::new(format!(
+            "The player with the id ['{}'] already forfeited the current round of the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    let mut players = player_repo.get_all_players(Some(&game_id), &card_repo).await?;
+
+    // an open challenge window passes the turn on by itself once it expires
+    if game.which_player_turn == player_id && game.challenge_deadline.is_none() {
+        if let Some(next_player) = next_player_turn(&players, &player_id) {
+            game_repo
+                .set_turn(&game_id, &next_player.id, &turn_deadline_from(now, config.turn_duration_seconds))
+                .await?;
+        }
+    }
+
+    // the last player left in the round finishes it
+    award_finishing_positions(&game_repo, &player_repo, &claim_repo, &repos.score_events()?, &game_id, &mut players).await?;
+
+    let updated_game = game_repo
+        .get_game_by_id(&game_id, &chat_repo, &player_repo, &claim_repo, &chat_message_repo, &card_repo)
+        .await?;
+
+    Ok(Json(GameView::for_player(updated_game, &player_id)))
+}
+
+/// Draws a card from the deck instead of placing a claim, for games whose settings allow it.
+///
+/// Drawing uses up the turn of the player, so the turn is passed on to the next player. The
+/// response contains the drawn card, which only the player can see.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/game/{game_id}/players/{player_id}/draw
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid, a
+/// `ForbiddenError` (403) when it belongs to another player or the settings of the game don't
+/// allow drawing, a `NotFoundError` (404) when the player isn't part of the game, a
+/// `NotYourTurnError` (409) with the id of the current player when it's the turn of another
+/// player and a `ConflictError` (409) when the game isn't `InProgress`, the turn was already
+/// taken or the deck is empty.
+#[worker::send]
+pub async fn draw_card(
+    Extension(repos): Extension<Repositories>,
+    Extension(config): Extension<Config>,
+    Path((game_id, player_id)): Path<(String, String)>,
+    AuthenticatedPlayer(authenticated_player): AuthenticatedPlayer,
+) -> Result<Json<Card>, Box<dyn ApplicationError>> {
+    let game_repo = repos.games()?;
+    let player_repo = repos.players()?;
+    let card_repo = repos.cards()?;
+
+    if authenticated_player.id != player_id {
+        return Err(ForbiddenError::new(format!(
+            "Players can only draw cards themselves, not for the player with the id ['{}']!",
+            player_id
+        ))
+        .into());
+    }
+
+    if !player_repo.is_player_in_game(&player_id, &game_id).await? {
+        return Err(NotFoundError::new(format!(
+            "The player with the id ['{}'] isn't part of the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    let game = game_repo
+        .get_game_by_id(
+            &game_id,
+            &repos.chats()?,
+            &player_repo,
+            &repos.claims()?,
+            &repos.chat_messages()?,
+            &card_repo,
+        )
+        .await?;
+    if !game.settings.allow_draw {
+        return Err(ForbiddenError::new(format!(
+            "The rules of the game ['{}'] don't allow drawing cards from the deck!",
+            game_id
+        ))
+        .into());
+    }
+    if game.state != GameState::InProgress {
+        return Err(ConflictError::new(format!(
+            "The game with the id ['{}'] is not in progress (state: {})! No cards can be drawn.",
+            game_id, game.state
+        ))
+        .into());
+    }
+    if game.which_player_turn != player_id {
+        return Err(NotYourTurnError::new(player_id, game.which_player_turn).into());
+    }
+    if game.challenge_deadline.is_some() {
+        return Err(ConflictError::new(format!(
+            "The player with the id ['{}'] already took the turn in the game ['{}']!",
+            player_id, game_id
+        ))
+        .into());
+    }
+
+    let card = match card_repo.draw_card(&game_id, &player_id).await? {
+        Some(card) => card,
+        None => {
+            return Err(ConflictError::new(format!(
+                "The deck of the game with the id ['{}'] is empty! No cards can be drawn.",
+                game_id
+            ))
+            .into())
+        }
+    };
+
+    if let Some(next_player) = next_player_turn(&game.players, &player_id) {
+        game_repo
+            .set_turn(
+                &game_id,
+                &next_player.id,
+                &turn_deadline_from(chrono::Utc::now(), config.turn_duration_seconds),
+            )
+            .await?;
+    }
+
+    Ok(Json(card))
+}
+
+/// Returns the game the authenticated player is currently taking part in, e.g. when the client
+/// reconnects and only kept the session token.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+/// The body is encoded as MessagePack when the client sends `Accept: application/msgpack`.
+///
+/// URL endpoint: /api/player/game
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid and a
+/// `NotFoundError` (404) when the game of the player is already over.
+#[worker::send]
+pub async fn resume_game(
+    Extension(repos): Extension<Repositories>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+    format: ResponseFormat,
+) -> Result<Response, Box<dyn ApplicationError>> {
+    let player_repo = repos.players()?;
+
+    let game_id = match player_repo.find_active_game(&player.id).await? {
+        Some(game_id) => game_id,
+        None => {
+            return Err(NotFoundError::new(format!(
+                "The player with the id ['{}'] isn't part of any running game!",
+                player.id
+            ))
+            .into())
+        }
+    };
+
+    let game = repos
+        .games()?
+        .get_game_by_id(
+            &game_id,
+            &repos.chats()?,
+            &player_repo,
+            &repos.claims()?,
+            &repos.chat_messages()?,
+            &repos.cards()?,
+        )
+        .await?;
+
+    Ok(format.respond(GameView::for_player(game, &player.id)))
+}
+
+/// Lists the games a player took part in as `GameSummary`s, the most recently started first.
+///
+/// The games can be filtered by their state with `?state=`, e.g. `?state=Ended`, and are
+/// paginated with `?offset=` and `?limit=`. The number of all matching games is sent in the
+/// `X-Total-Count` header and the `Link` header points to the next and previous pages.
+///
+/// Players can only list their own games, so the request has to contain the session token of the
+/// player in the `Authorization` header.
+///
+/// URL endpoint: /api/player/{player_id}/games
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid and a
+/// `ForbiddenError` (403) when it belongs to another player.
+#[worker::send]
+pub async fn get_player_games(
+    Extension(repos): Extension<Repositories>,
+    Path(player_id): Path<String>,
+    AuthenticatedPlayer(authenticated_player): AuthenticatedPlayer,
+    Query(query): Query<PlayerGamesQuery>,
+    RawQuery(raw_query): RawQuery,
+) -> Result<(HeaderMap, Json<Vec<GameSummary>>), Box<dyn ApplicationError>> {
+    if authenticated_player.id != player_id {
+        return Err(ForbiddenError::new(format!(
+            "Players can only list their own games, not the ones of the player with the id ['{}']!",
+            player_id
+        ))
+        .into());
+    }
+
+    let game_ids = repos
+        .players()?
+        .get_games_for_player(&player_id, query.state.as_ref())
+        .await?;
+
+    let page = Page::new(query.offset, query.limit, DEFAULT_NUMBER_OF_LISTED_GAMES);
+    let headers = page.headers(game_ids.len(), raw_query.as_deref());
+
+    Ok((headers, Json(summarize_games(&repos, page.slice(game_ids), |_| true).await?)))
+}
+
+/// Lists the games the authenticated player can resume as `GameSummary`s, the most recently
+/// started first.
+///
+/// Games that `Ended` or were `Aborted` are left out. The list is empty when the player has no
+/// active game.
+///
+/// The request has to contain the session token of the player in the `Authorization` header.
+///
+/// URL endpoint: /api/me/games
+///
+/// # Errors
+///
+/// Returns an `UnauthorizedError` (401) when the session token is missing or invalid.
+#[worker::send]
+pub async fn get_my_games(
+    Extension(repos): Extension<Repositories>,
+    AuthenticatedPlayer(player): AuthenticatedPlayer,
+) -> Result<Json<Vec<GameSummary>>, Box<dyn ApplicationError>> {
+    let game_ids = repos.players()?.get_games_for_player(&player.id, None).await?;
+
+    Ok(Json(
+        summarize_games(&repos, game_ids, |state| {
+            !matches!(state, GameState::Ended | GameState::Aborted)
+        })
+        .await?,
+    ))
+}
+
+/// Creates the `GameSummary`s of the games whose state is accepted by `include`, in the order of
+/// the ids.
+async fn summarize_games(
+    repos: &Repositories,
+    game_ids: Vec<String>,
+    include: impl Fn(&GameState) -> bool,
+) -> Result<Vec<GameSummary>, Box<dyn ApplicationError>> {
+    let mut summaries = repos.games()?.get_summaries(&game_ids).await?;
+    summaries.retain(|summary| include(&summary.state));
+
+    Ok(summaries)
+}
+
+    }
+}
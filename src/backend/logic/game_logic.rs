@@ -0,0 +1,760 @@
+// Pure game rules that don't need any database access.
+use cfg_if::cfg_if;
+
+use crate::backend::{enums::card_types::CardType, types::game::Game};
+
+// constants
+
+/// Number of cards of every rank in the deck of a game, like the four suits of a regular deck.
+pub const CARDS_PER_RANK: usize = 4;
+
+/// Number of `Joker`s in the deck of a game.
+pub const JOKERS_PER_DECK: usize = 2;
+
+/// Number of cards in the deck of a game, see `new_deck`.
+pub const CARDS_PER_DECK: usize = 4 * CARDS_PER_RANK + JOKERS_PER_DECK;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use rand_chacha::{
+    rand_core::{RngCore, SeedableRng},
+    ChaCha8Rng,
+};
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, process_error::ProcessError},
+    types::{
+        card::{Card, UpdateCardDTO},
+        challenge::ChallengeOutcome,
+        claim::Claim,
+        player::Player,
+        scoreboard::ScoreboardEntry,
+    },
+};
+
+// constants
+
+/// Number of seconds a player has to make a move before the turn is passed on, when the
+/// `TURN_DURATION_SECONDS` variable of the worker isn't set.
+pub const TURN_DURATION_SECONDS: i64 = 60;
+
+/// Number of seconds the other players have to challenge a claim, when the
+/// `CHALLENGE_WINDOW_SECONDS` variable of the worker isn't set.
+pub const DEFAULT_CHALLENGE_WINDOW_SECONDS: i64 = 10;
+
+/// Points a player gets for winning a round.
+pub const ROUND_WIN_POINTS: i64 = 1;
+
+/// Creates the unshuffled deck a game is played with.
+///
+/// The deck has `CARDS_PER_RANK` cards of every rank and `JOKERS_PER_DECK` `Joker`s. It's created
+/// once together with the game and dealt again in every round.
+pub fn new_deck() -> Vec<Card> {
+    let ranks = [CardType::King, CardType::Queen, CardType::Jack, CardType::Ace];
+    ranks
+        .iter()
+        .flat_map(|rank| std::iter::repeat_with(|| Card::new(rank.clone())).take(CARDS_PER_RANK))
+        .chain(std::iter::repeat_with(|| Card::new(CardType::Joker)).take(JOKERS_PER_DECK))
+        .collect()
+}
+
+/// Shuffles a deck of cards in place with the Fisher-Yates algorithm.
+///
+/// Without a seed the random number generator is seeded by the operating system / the JS
+/// runtime. With a seed the deck is first sorted by rank, so the same seed and round always
+/// result in the same order of ranks, no matter in which order the cards were loaded.
+///
+/// # Arguments
+///
+/// - `cards` -> The deck which gets shuffled.
+/// - `deck_seed` -> Optional seed of the game to reproduce the shuffle.
+/// - `round_number` -> Round the deck is shuffled for, so every round of a seeded game gets
+///   another deal.
+pub fn shuffle_deck(cards: &mut [Card], deck_seed: Option<u32>, round_number: usize) {
+    let mut rng = match deck_seed {
+        Some(deck_seed) => {
+            cards.sort_by_key(|card| card.card_type.index());
+            let mut rng = ChaCha8Rng::seed_from_u64(u64::from(deck_seed));
+            rng.set_stream(round_number as u64);
+            rng
+        }
+        None => {
+            let mut seed = [0u8; 32];
+            if let Err(err) = getrandom::fill(&mut seed) {
+                log::warn!("Couldn't get a random seed to shuffle the deck! Error: {}", err);
+            }
+            ChaCha8Rng::from_seed(seed)
+        }
+    };
+
+    for i in (1..cards.len()).rev() {
+        let j = (rng.next_u32() as usize) % (i + 1);
+        cards.swap(i, j);
+    }
+}
+
+/// Returns the number of cards a player gets who joins a game that is already in progress.
+///
+/// The player gets as many cards as the smallest hand of the other players, so they don't start
+/// with an advantage, but at most the cards left in the deck.
+///
+/// # Arguments
+///
+/// - `players` -> The players who are already part of the game, with their hands.
+/// - `deck_remaining` -> Number of cards that weren't dealt yet.
+pub fn late_join_hand_size(players: &[Player], deck_remaining: usize) -> usize {
+    players
+        .iter()
+        .map(|player| player.assigned_cards.len())
+        .min()
+        .unwrap_or(0)
+        .min(deck_remaining)
+}
+
+/// Deals all cards one by one to the players, like it's done at a real table.
+///
+/// The first card goes to the first player in the list, the second card to the second player
+/// and so on.
+///
+/// # Arguments
+///
+/// - `cards` -> The (shuffled) deck that is dealt.
+/// - `players` -> All players of the game in their seating order.
+///
+/// # Returns
+///
+/// 1.) Ok(Vec<UpdateCardDTO>), with one DTO per card that assigns the card to its new owner.
+/// 2.) Err(ProcessError<Player>), WHEN there are no players to deal to.
+pub fn deal_cards(
+    cards: &[Card],
+    players: &[Player],
+) -> Result<Vec<UpdateCardDTO>, Box<dyn ApplicationError>> {
+    if players.is_empty() {
+        return Err(ProcessError::<Player>::new(
+            "Can't deal the cards! There are no players in the game!".to_string(),
+            "game_logic::deal_cards".to_string(),
+            None,
+        )
+        .into());
+    }
+
+    let mut assignments = Vec::with_capacity(cards.len());
+    for (index, card) in cards.iter().enumerate() {
+        let owner = &players[index % players.len()];
+        assignments.push(UpdateCardDTO {
+            id: card.id.clone(),
+            card_type: None,
+            player_id: Some(owner.id.clone()),
+            claim_id: None,
+        });
+    }
+
+    Ok(assignments)
+}
+/// Determines the player who has to make the next move after the current one.
+///
+/// The players are taken in their seating order and the rotation starts again with the first
+/// player after the last one. Players who forfeited the round or already emptied their hand are
+/// skipped. If the current player isn't part of the list anymore, the first player continues.
+///
+/// # Arguments
+///
+/// - `players` -> All players of the game in their seating order.
+/// - `current_player_id` -> Identifier of the player whose turn it is right now.
+///
+/// # Returns
+///
+/// The next `Player` or `None` when every player forfeited or finished or there are no players.
+pub fn next_player_turn<'a>(players: &'a [Player], current_player_id: &str) -> Option<&'a Player> {
+    let start = match players.iter().position(|player| player.id == current_player_id) {
+        Some(index) => index + 1,
+        None => 0,
+    };
+
+    (0..players.len())
+        .map(|offset| &players[(start + offset) % players.len()])
+        .find(|player| !player.has_forfeited() && player.finished_position.is_none())
+}
+
+/// Determines the player who takes over as host when the current host leaves the game.
+///
+/// Like the turn, the host role is passed on in the seating order, but every other player can
+/// take it over, no matter if they forfeited or finished the round.
+///
+/// # Arguments
+///
+/// - `players` -> All players of the game in their seating order.
+/// - `host_id` -> Identifier of the current host.
+///
+/// # Returns
+///
+/// The next host or `None` when the host is the only player.
+pub fn next_host<'a>(players: &'a [Player], host_id: &str) -> Option<&'a Player> {
+    let start = match players.iter().position(|player| player.id == host_id) {
+        Some(index) => index + 1,
+        None => 0,
+    };
+
+    (0..players.len())
+        .map(|offset| &players[(start + offset) % players.len()])
+        .find(|player| player.id != host_id)
+}
+
+/// Determines the players who get their finishing position now, in the order of their positions.
+///
+/// These are the players who emptied their hand and have no position yet. Like in
+/// `check_winner`, the player who placed the last claim comes first, the others in their seating
+/// order. Players who forfeited the round don't finish it. Once only one player is left in the
+/// round, because the others finished or forfeited, they are added as the last one. As long as
+/// no cards were dealt, nobody finishes.
+///
+/// # Arguments
+///
+/// - `players` -> All players of the game with their cards in their seating order.
+/// - `last_claim_by` -> Identifier of the player who placed the latest claim, if any.
+///
+/// # Returns
+///
+/// The newly finished players, empty when nobody finished.
+pub fn newly_finished<'a>(players: &'a [Player], last_claim_by: Option<&str>) -> Vec<&'a Player> {
+    if players.iter().all(|player| player.assigned_cards.is_empty()) {
+        return vec![];
+    }
+
+    let (mut finished, remaining): (Vec<&Player>, Vec<&Player>) = players
+        .iter()
+        .filter(|player| player.finished_position.is_none() && !player.has_forfeited())
+        .partition(|player| player.assigned_cards.is_empty());
+    // stable, so the seating order is kept for the other players
+    finished.sort_by_key(|player| Some(player.id.as_str()) != last_claim_by);
+
+    if remaining.len() == 1 && players.len() > 1 {
+        finished.extend(remaining);
+    }
+
+    finished
+}
+
+/// Decides whether a waiting game starts on its own.
+///
+/// Only games with `auto_start_at` in their settings start on their own, once they have at least
+/// that many players and all of them are ready.
+///
+/// # Arguments
+///
+/// - `auto_start_at` -> Number of players the game starts with, see `GameSettings`.
+/// - `players` -> All players of the game.
+pub fn should_auto_start(auto_start_at: Option<usize>, players: &[Player]) -> bool {
+    auto_start_at.is_some_and(|auto_start_at| {
+        players.len() >= auto_start_at && players.iter().all(Player::is_ready)
+    })
+}
+
+/// Decides whether a player wins the current round now.
+///
+/// The winner is the player who finishes the round first, see `newly_finished`: the first one
+/// to empty their hand, or the last one left when every other player forfeited. When a pickup
+/// leaves several players with an empty hand at the same time, the tie is broken like this:
+///
+/// 1. The player who placed the last claim wins, because they emptied their hand with it.
+/// 2. Otherwise the player who comes first in the seating order wins.
+///
+/// A round has a single winner: once a player got a finishing position the round is decided,
+/// so nobody wins it again and the points aren't awarded twice.
+///
+/// # Arguments
+///
+/// - `players` -> All players of the game with their cards in their seating order.
+/// - `last_claim_by` -> Identifier of the player who placed the latest claim, if any.
+///
+/// # Returns
+///
+/// The winner of the round or `None` when the round goes on or was already decided.
+pub fn check_winner<'a>(players: &'a [Player], last_claim_by: Option<&str>) -> Option<&'a Player> {
+    if players.iter().any(|player| player.finished_position.is_some()) {
+        return None;
+    }
+
+    newly_finished(players, last_claim_by).first().copied()
+}
+
+/// Calculates the deadline of a turn that starts now.
+///
+/// The timestamp has the same format as all other timestamps of a `Game`, so it can be compared
+/// in the database.
+///
+/// # Arguments
+///
+/// - `now` -> Point in time when the turn starts.
+/// - `turn_duration_seconds` -> Length of a turn.
+pub fn turn_deadline_from(now: chrono::DateTime<chrono::Utc>, turn_duration_seconds: i64) -> String {
+    (now + chrono::Duration::seconds(turn_duration_seconds)).to_string()
+}
+
+/// Calculates the end of the challenge window of a claim that is placed now.
+///
+/// # Arguments
+///
+/// - `now` -> Point in time when the claim was placed.
+/// - `window_seconds` -> Length of the challenge window.
+pub fn challenge_deadline_from(now: chrono::DateTime<chrono::Utc>, window_seconds: i64) -> String {
+    (now + chrono::Duration::seconds(window_seconds)).to_string()
+}
+
+/// Checks whether a claim can still be challenged.
+///
+/// The timestamps are compared like in the database, which works because all of them have the
+/// same format.
+///
+/// # Arguments
+///
+/// - `challenge_deadline` -> End of the challenge window of the game, `None` if no window is open.
+/// - `now` -> The current point in time.
+pub fn is_challenge_window_open(challenge_deadline: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> bool {
+    match challenge_deadline {
+        Some(deadline) => now.to_string().as_str() <= deadline,
+        None => false,
+    }
+}
+
+/// Decides who lost a challenge of a claim.
+///
+/// A claim is a bluff when one of its cards is neither the announced rank nor a `Joker`, which can
+/// be used as a wild card. If the claim was a bluff, the player who placed it loses, otherwise the
+/// challenger does.
+///
+/// # Arguments
+///
+/// - `claim` -> The challenged claim.
+/// - `claimed_cards` -> Cards that were placed with the claim.
+/// - `challenger_id` -> Identifier of the player calling the bluff.
+///
+/// # Returns
+///
+/// The resolved `ChallengeOutcome`.
+pub fn resolve_challenge(
+    claim: &Claim,
+    claimed_cards: &[Card],
+    challenger_id: &str,
+) -> ChallengeOutcome {
+    let was_bluff = claimed_cards.iter().any(|card| {
+        card.card_type.index() != claim.announced_rank.index()
+            && card.card_type.index() != CardType::Joker.index()
+    });
+
+    ChallengeOutcome {
+        claim_id: claim.id.clone(),
+        challenger_id: challenger_id.to_string(),
+        accused_id: claim.created_by.clone(),
+        was_bluff,
+        loser_id: if was_bluff {
+            claim.created_by.clone()
+        } else {
+            challenger_id.to_string()
+        },
+    }
+}
+
+/// Determines the card type that has to be played after a turn was completed.
+///
+/// The ranks progress from King over Queen and Jack to Ace and start with the King again. The
+/// `Joker` is a wild card and is never the card to play, so a game still expecting it continues
+/// with the King.
+///
+/// # Arguments
+///
+/// - `game` -> The game whose turn was completed.
+///
+/// # Returns
+///
+/// The next `CardType` to play.
+pub fn advance_card_to_play(game: &Game) -> CardType {
+    match game.card_to_play {
+        CardType::King => CardType::Queen,
+        CardType::Queen => CardType::Jack,
+        CardType::Jack => CardType::Ace,
+        CardType::Ace | CardType::Joker => CardType::King,
+    }
+}
+
+/// Ranks the players of an ended game for the scoreboard.
+///
+/// Players with a higher score rank first. On equal scores the player with fewer cards left in
+/// their hand ranks first, because they came closer to emptying it. Players who are still equal
+/// share a rank and the following rank is skipped.
+///
+/// # Arguments
+///
+/// - `players` -> All players of the game.
+///
+/// # Returns
+///
+/// The `ScoreboardEntry`s from the first to the last place.
+pub fn rank_players(players: &[Player]) -> Vec<ScoreboardEntry> {
+    let mut ranked: Vec<&Player> = players.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.assigned_cards.len().cmp(&b.assigned_cards.len()))
+    });
+
+    let mut entries: Vec<ScoreboardEntry> = Vec::with_capacity(ranked.len());
+    for (index, player) in ranked.iter().enumerate() {
+        let rank = match entries.last() {
+            Some(previous)
+                if previous.score == player.score
+                    && previous.number_of_cards == player.assigned_cards.len() =>
+            {
+                previous.rank
+            }
+            _ => index + 1,
+        };
+
+        entries.push(ScoreboardEntry {
+            rank,
+            player_id: player.id.clone(),
+            name: player.name.clone(),
+            score: player.score,
+            number_of_cards: player.assigned_cards.len(),
+            finished_position: player.finished_position,
+        });
+    }
+
+    entries
+}
+
+    }
+}
+
+/// Counts how many cards of a game could truthfully be played as `rank`.
+///
+/// These are the cards of the rank and the `Joker`s, which are wild cards. Every deck of a game
+/// has `CARDS_PER_RANK` cards of each rank and `JOKERS_PER_DECK` `Joker`s, no matter whether they
+/// are in the hands, in claims, on the pile or still in the deck. The number of decks follows
+/// from the number of all cards of the game, so a game played with several decks has more of each
+/// rank. Only the composition of the decks is counted, which every player knows, so the result
+/// doesn't reveal who holds which card.
+///
+/// A claim with more cards than this is an obvious bluff, see `ClaimView::impossible`.
+///
+/// # Arguments
+///
+/// - `game` -> The game with the hands of all players and the `card_counts` of the pile and the
+///   deck.
+/// - `rank` -> The announced rank.
+pub fn max_possible_of_rank(game: &Game, rank: &CardType) -> usize {
+    let number_of_cards = game
+        .players
+        .iter()
+        .map(|player| player.assigned_cards.len())
+        .sum::<usize>()
+        + game.card_counts.pile_size
+        + game.card_counts.deck_remaining;
+    let number_of_decks = number_of_cards.div_ceil(CARDS_PER_DECK).max(1);
+
+    if rank.index() == CardType::Joker.index() {
+        number_of_decks * JOKERS_PER_DECK
+    } else {
+        number_of_decks * (CARDS_PER_RANK + JOKERS_PER_DECK)
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::backend::enums::player_color::PlayerColor;
+
+    /// Creates a player of a waiting game who is ready or not.
+    fn player(name: &str, ready: bool) -> Player {
+        let mut player = Player::new(name.to_string(), "game".to_string(), PlayerColor::Red);
+        player.ready_at = ready.then(|| "2026-01-01 00:00:00 UTC".to_string());
+        player
+    }
+
+    #[test]
+    fn a_new_deck_has_every_rank_and_the_jokers() {
+        let deck = new_deck();
+        assert_eq!(deck.len(), CARDS_PER_DECK);
+
+        for rank in [CardType::King, CardType::Queen, CardType::Jack, CardType::Ace] {
+            assert_eq!(deck.iter().filter(|card| card.card_type == rank).count(), CARDS_PER_RANK);
+        }
+        assert_eq!(deck.iter().filter(|card| card.card_type == CardType::Joker).count(), JOKERS_PER_DECK);
+    }
+
+    #[test]
+    fn every_card_of_a_new_deck_has_its_own_id() {
+        let deck = new_deck();
+        let ids: std::collections::HashSet<&str> = deck.iter().map(|card| card.id.as_str()).collect();
+
+        assert_eq!(ids.len(), deck.len());
+    }
+
+    /// Creates a player of a running game with `cards` cards in their hand.
+    fn player_with_cards(name: &str, cards: usize) -> Player {
+        let mut player = player(name, true);
+        player.id = name.to_string();
+        player.assigned_cards = (0..cards).map(|_| Card::new(CardType::King)).collect();
+        player
+    }
+
+    #[test]
+    fn the_last_player_who_didnt_forfeit_wins_the_round() {
+        let mut players = vec![player_with_cards("alice", 3), player_with_cards("bob", 2), player_with_cards("carol", 1)];
+        players[0].forfeited_at = Some("2026-01-01 00:00:00 UTC".to_string());
+        assert!(check_winner(&players, None).is_none());
+
+        players[2].forfeited_at = Some("2026-01-01 00:00:01 UTC".to_string());
+        assert_eq!(check_winner(&players, None).map(|player| player.id.as_str()), Some("bob"));
+    }
+
+    #[test]
+    fn the_player_who_placed_the_last_claim_wins_a_tie() {
+        let players = vec![player_with_cards("alice", 0), player_with_cards("bob", 0), player_with_cards("carol", 1)];
+
+        assert_eq!(check_winner(&players, Some("bob")).map(|player| player.id.as_str()), Some("bob"));
+        assert_eq!(check_winner(&players, None).map(|player| player.id.as_str()), Some("alice"));
+    }
+
+    #[test]
+    fn a_decided_round_isnt_won_again_by_a_finished_player() {
+        let mut players = vec![player_with_cards("alice", 0), player_with_cards("bob", 2), player_with_cards("carol", 1)];
+        players[0].finished_position = Some(1);
+        assert!(check_winner(&players, None).is_none());
+
+        // forfeiting the rest of the round doesn't score the finished player again
+        players[2].forfeited_at = Some("2026-01-01 00:00:00 UTC".to_string());
+        assert!(check_winner(&players, None).is_none());
+    }
+
+    /// Gives the newly finished players their positions, like `award_finishing_positions`.
+    fn finish(players: &mut [Player], last_claim_by: Option<&str>) -> Vec<String> {
+        let ids: Vec<String> = newly_finished(players, last_claim_by)
+            .into_iter()
+            .map(|player| player.id.clone())
+            .collect();
+        for id in &ids {
+            let position = players.iter().filter(|player| player.finished_position.is_some()).count() + 1;
+            if let Some(player) = players.iter_mut().find(|player| player.id == *id) {
+                player.finished_position = Some(position);
+            }
+        }
+        ids
+    }
+
+    #[test]
+    fn a_round_of_three_players_is_played_to_the_end() {
+        let mut players = vec![player_with_cards("alice", 2), player_with_cards("bob", 1), player_with_cards("carol", 3)];
+        assert!(check_winner(&players, None).is_none());
+        assert!(finish(&mut players, None).is_empty());
+
+        // bob places his last card and the claim stands
+        players[1].assigned_cards.clear();
+        assert_eq!(check_winner(&players, Some("bob")).map(|player| player.id.as_str()), Some("bob"));
+        assert_eq!(finish(&mut players, Some("bob")), vec!["bob"]);
+        assert!(check_winner(&players, Some("bob")).is_none());
+
+        // alice empties her hand too, carol is the last one left and finishes last
+        players[0].assigned_cards.clear();
+        assert_eq!(finish(&mut players, Some("alice")), vec!["alice", "carol"]);
+        assert!(check_winner(&players, Some("alice")).is_none());
+
+        let positions: Vec<Option<usize>> = players.iter().map(|player| player.finished_position).collect();
+        assert_eq!(positions, vec![Some(2), Some(1), Some(3)]);
+        assert!(finish(&mut players, None).is_empty());
+    }
+
+    #[test]
+    fn a_round_of_three_players_ends_when_the_others_forfeit() {
+        let mut players = vec![player_with_cards("alice", 2), player_with_cards("bob", 1), player_with_cards("carol", 3)];
+        players[0].forfeited_at = Some("2026-01-01 00:00:00 UTC".to_string());
+        assert!(finish(&mut players, None).is_empty());
+
+        players[1].forfeited_at = Some("2026-01-01 00:00:01 UTC".to_string());
+        assert_eq!(check_winner(&players, None).map(|player| player.id.as_str()), Some("carol"));
+        assert_eq!(finish(&mut players, None), vec!["carol"]);
+        assert_eq!(players[2].finished_position, Some(1));
+        assert!(players[..2].iter().all(|player| player.finished_position.is_none()));
+    }
+
+    #[test]
+    fn a_game_without_auto_start_never_starts_on_its_own() {
+        assert!(!should_auto_start(None, &[player("Alice", true), player("Bob", true)]));
+    }
+
+    #[test]
+    fn the_joining_player_who_reaches_the_threshold_starts_the_game() {
+        let mut players = vec![player("Alice", true)];
+        assert!(!should_auto_start(Some(2), &players));
+
+        players.push(player("Bob", true));
+        assert!(should_auto_start(Some(2), &players));
+    }
+
+    #[test]
+    fn a_full_game_waits_until_all_players_are_ready() {
+        let mut players = vec![player("Alice", true), player("Bob", false)];
+        assert!(!should_auto_start(Some(2), &players));
+
+        players[1].ready_at = Some("2026-01-01 00:00:01 UTC".to_string());
+        assert!(should_auto_start(Some(2), &players));
+    }
+
+    /// Creates a game whose `decks` decks are spread over two hands, the pile and the deck.
+    fn game_with_decks(decks: usize) -> Game {
+        let mut game = Game::new();
+        game.players = vec![player_with_cards("alice", 3 * decks), player_with_cards("bob", 2 * decks)];
+        game.card_counts.pile_size = 4 * decks;
+        game.card_counts.deck_remaining = CARDS_PER_DECK * decks - 9 * decks;
+        game
+    }
+
+    #[test]
+    fn a_single_deck_holds_every_card_of_a_rank_once() {
+        let game = game_with_decks(1);
+
+        assert_eq!(max_possible_of_rank(&game, &CardType::Queen), CARDS_PER_RANK + JOKERS_PER_DECK);
+        assert_eq!(max_possible_of_rank(&game, &CardType::Joker), JOKERS_PER_DECK);
+    }
+
+    #[test]
+    fn the_cards_on_the_pile_and_in_the_deck_still_count() {
+        let mut game = game_with_decks(1);
+        // after a while most cards are on the pile, the hands don't show all cards of a rank
+        game.players[0].assigned_cards.truncate(1);
+        game.card_counts.pile_size += 2;
+
+        assert_eq!(max_possible_of_rank(&game, &CardType::King), CARDS_PER_RANK + JOKERS_PER_DECK);
+    }
+
+    #[test]
+    fn a_game_with_several_decks_has_more_of_every_rank() {
+        let game = game_with_decks(2);
+
+        assert_eq!(max_possible_of_rank(&game, &CardType::Ace), 2 * (CARDS_PER_RANK + JOKERS_PER_DECK));
+        assert_eq!(max_possible_of_rank(&game, &CardType::Joker), 2 * JOKERS_PER_DECK);
+    }
+
+    #[test]
+    fn the_cards_are_dealt_round_the_table() {
+        let players = vec![player_with_cards("alice", 0), player_with_cards("bob", 0)];
+        let deck = new_deck();
+        let assignments = deal_cards(&deck, &players).unwrap();
+
+        assert_eq!(assignments.len(), deck.len());
+        assert_eq!(assignments[0].player_id.as_deref(), Some("alice"));
+        assert_eq!(assignments[1].player_id.as_deref(), Some("bob"));
+        assert_eq!(assignments[2].player_id.as_deref(), Some("alice"));
+        assert!(deal_cards(&deck, &[]).is_err());
+    }
+
+    /// Creates a claim of `created_by` announcing `rank` with the given cards.
+    fn claim(created_by: &str, rank: CardType, cards: &[CardType]) -> (Claim, Vec<Card>) {
+        let cards: Vec<Card> = cards.iter().map(|card_type| Card::new(card_type.clone())).collect();
+        let claim = Claim::new(created_by.to_string(), rank, cards.len(), cards.clone()).unwrap();
+        (claim, cards)
+    }
+
+    #[test]
+    fn a_challenged_bluff_is_lost_by_the_player_who_placed_it() {
+        let (claim, cards) = claim("alice", CardType::King, &[CardType::King, CardType::Queen]);
+        let outcome = resolve_challenge(&claim, &cards, "bob");
+
+        assert!(outcome.was_bluff);
+        assert_eq!(outcome.accused_id, "alice");
+        assert_eq!(outcome.loser_id, "alice");
+    }
+
+    #[test]
+    fn a_challenged_truthful_claim_is_lost_by_the_challenger() {
+        // the joker is a wild card, so it counts as the announced rank
+        let (claim, cards) = claim("alice", CardType::Ace, &[CardType::Ace, CardType::Joker]);
+        let outcome = resolve_challenge(&claim, &cards, "bob");
+
+        assert!(!outcome.was_bluff);
+        assert_eq!(outcome.loser_id, "bob");
+    }
+
+    #[test]
+    fn the_card_to_play_advances_through_all_ranks() {
+        let mut game = Game::new();
+        let mut ranks = vec![];
+        for _ in 0..4 {
+            game.card_to_play = advance_card_to_play(&game);
+            ranks.push(game.card_to_play.clone());
+        }
+
+        assert_eq!(ranks, vec![CardType::Queen, CardType::Jack, CardType::Ace, CardType::King]);
+
+        game.card_to_play = CardType::Joker;
+        assert_eq!(advance_card_to_play(&game), CardType::King);
+    }
+
+    #[test]
+    fn a_claim_can_only_be_challenged_until_its_deadline() {
+        let placed_at = chrono::Utc::now();
+        let deadline = challenge_deadline_from(placed_at, DEFAULT_CHALLENGE_WINDOW_SECONDS);
+
+        assert!(is_challenge_window_open(Some(&deadline), placed_at));
+        assert!(!is_challenge_window_open(
+            Some(&deadline),
+            placed_at + chrono::Duration::seconds(DEFAULT_CHALLENGE_WINDOW_SECONDS + 1)
+        ));
+        assert!(!is_challenge_window_open(None, placed_at));
+    }
+
+    #[test]
+    fn equal_players_share_a_rank_on_the_scoreboard() {
+        let mut players = vec![player_with_cards("alice", 1), player_with_cards("bob", 1), player_with_cards("carol", 0)];
+        players[2].score = 2;
+
+        let ranks: Vec<(String, usize)> = rank_players(&players)
+            .into_iter()
+            .map(|entry| (entry.player_id, entry.rank))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![("carol".to_string(), 1), ("alice".to_string(), 2), ("bob".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn the_turn_skips_players_who_forfeited_or_finished() {
+        let mut players = vec![player_with_cards("alice", 2), player_with_cards("bob", 2), player_with_cards("carol", 2)];
+        assert_eq!(next_player_turn(&players, "alice").map(|player| player.id.as_str()), Some("bob"));
+        assert_eq!(next_player_turn(&players, "carol").map(|player| player.id.as_str()), Some("alice"));
+
+        players[1].forfeited_at = Some("2026-01-01 00:00:00 UTC".to_string());
+        players[2].finished_position = Some(1);
+        assert_eq!(next_player_turn(&players, "alice").map(|player| player.id.as_str()), Some("alice"));
+    }
+
+    #[test]
+    fn a_seeded_deck_is_shuffled_the_same_way_in_every_game() {
+        let ranks = |round_number: usize| {
+            let mut deck = new_deck();
+            shuffle_deck(&mut deck, Some(42), round_number);
+            deck.into_iter().map(|card| card.card_type).collect::<Vec<CardType>>()
+        };
+
+        assert_eq!(ranks(1), ranks(1));
+        assert_ne!(ranks(1), ranks(2));
+    }
+
+    #[test]
+    fn a_late_player_gets_the_smallest_hand_the_deck_allows() {
+        let players = vec![player_with_cards("alice", 5), player_with_cards("bob", 3)];
+
+        assert_eq!(late_join_hand_size(&players, 10), 3);
+        assert_eq!(late_join_hand_size(&players, 2), 2);
+        assert_eq!(late_join_hand_size(&[], 10), 0);
+    }
+
+    #[test]
+    fn the_host_role_passes_to_the_next_seat() {
+        let players = vec![player_with_cards("alice", 0), player_with_cards("bob", 0)];
+
+        assert_eq!(next_host(&players, "bob").map(|player| player.id.as_str()), Some("alice"));
+        assert!(next_host(&players[..1], "alice").is_none());
+    }
+}
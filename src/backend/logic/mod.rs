@@ -1 +1 @@
-
+pub mod game_logic;
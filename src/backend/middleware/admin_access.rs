@@ -0,0 +1,91 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::request::Parts,
+};
+
+use crate::backend::{
+    errors::{
+        application_error::ApplicationError, forbidden_error::ForbiddenError,
+        process_error::ProcessError,
+    },
+    types::player::Player,
+    utils::{repository_provider::Repositories, session_token::hash_session_token},
+};
+
+// constants
+
+/// Name of the worker secret holding the token of the administrators.
+const ADMIN_TOKEN_SECRET: &str = "ADMIN_TOKEN";
+
+/// Header in which an administrator sends the admin token.
+const ADMIN_TOKEN_HEADER: &str = "X-Admin-Token";
+
+/// Marks a request as sent by an administrator, identified by the token in the `X-Admin-Token`
+/// header.
+///
+/// Used as extractor in handlers that reveal data the players must not see, e.g. the hands of
+/// all players. The token is compared with the `ADMIN_TOKEN` secret of the worker; without the
+/// secret nobody gets access.
+///
+/// # Errors
+///
+/// Rejects the request with a `ForbiddenError` (403) when the header is missing or the token is
+/// wrong.
+pub struct AdminAccess;
+
+impl<S: Send + Sync> FromRequestParts<S> for AdminAccess {
+    type Rejection = Box<dyn ApplicationError>;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let admin_token = match parts
+            .headers
+            .get(ADMIN_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(admin_token) => admin_token.trim(),
+            None => return Err(ForbiddenError::new("The request contains no admin token!".to_string()).into()),
+        };
+
+        let repos = match parts.extensions.get::<Repositories>() {
+            Some(repos) => repos,
+            None => {
+                return Err(ProcessError::<Player>::new(
+                    "The repositories weren't provided to the router!".to_string(),
+                    "AdminAccess::from_request_parts".to_string(),
+                    None,
+                )
+                .into())
+            }
+        };
+
+        // comparing the hashes doesn't reveal how much of the token was right
+        match repos.env().secret(ADMIN_TOKEN_SECRET) {
+            Ok(secret) if hash_session_token(admin_token) == hash_session_token(&secret.to_string()) => {
+                Ok(AdminAccess)
+            }
+            _ => Err(ForbiddenError::new("The admin token is invalid!".to_string()).into()),
+        }
+    }
+}
+
+/// Extracts the access as `Option<AdminAccess>`, `None` when the request contains no admin token.
+/// A wrong token is still rejected.
+impl<S: Send + Sync> OptionalFromRequestParts<S> for AdminAccess {
+    type Rejection = Box<dyn ApplicationError>;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(ADMIN_TOKEN_HEADER) {
+            return Ok(None);
+        }
+
+        <AdminAccess as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
+    }
+}
@@ -1 +1,100 @@
-// TODO: Add client authentication -> https://rust-classes.com/chapter_7_2
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::request::Parts,
+};
+use worker::send::SendFuture;
+
+use crate::backend::{
+    errors::{
+        application_error::ApplicationError, process_error::ProcessError,
+        unauthorized_error::UnauthorizedError,
+    },
+    types::player::Player,
+    utils::{
+        repository_provider::Repositories,
+        session_token::{hash_session_token, session_token_from_headers},
+    },
+};
+
+/// The player who sent a request, identified by the session token in the
+/// `Authorization: Bearer <token>` header.
+///
+/// Used as extractor in the handlers, so they act in the name of the authenticated player
+/// instead of a player id sent in the request body. The `last_seen` time of the player is updated
+/// with every request.
+///
+/// # Errors
+///
+/// Rejects the request with an `UnauthorizedError` (401) when the header is missing or the token
+/// doesn't belong to any player.
+pub struct AuthenticatedPlayer(pub Player);
+
+impl<S: Send + Sync> FromRequestParts<S> for AuthenticatedPlayer {
+    type Rejection = Box<dyn ApplicationError>;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let session_token_hash = match session_token_from_headers(&parts.headers) {
+            Some(session_token) => hash_session_token(session_token),
+            None => return Err(UnauthorizedError::new("The request contains no session token!".to_string()).into()),
+        };
+
+        let repos = match parts.extensions.get::<Repositories>() {
+            Some(repos) => repos.clone(),
+            None => {
+                return Err(ProcessError::<Player>::new(
+                    "The repositories weren't provided to the router!".to_string(),
+                    "AuthenticatedPlayer::from_request_parts".to_string(),
+                    None,
+                )
+                .into())
+            }
+        };
+
+        // database futures aren't 'Send', like in the handlers they run on the single worker thread
+        let player = SendFuture::new(async move {
+            let player_repo = repos.players()?;
+            let mut player = match player_repo
+                .get_player_by_session_token_hash(&session_token_hash)
+                .await?
+            {
+                Some(player) => player,
+                None => return Ok(None),
+            };
+
+            // every authenticated request counts as activity of the player
+            let now = chrono::Utc::now().to_string();
+            player_repo.set_last_seen(&player.id, &now).await?;
+            player.last_seen = Some(now);
+
+            Ok::<_, Box<dyn ApplicationError>>(Some(player))
+        })
+        .await?;
+
+        match player {
+            Some(player) => Ok(AuthenticatedPlayer(player)),
+            None => Err(UnauthorizedError::new("The session token doesn't belong to any player!".to_string()).into()),
+        }
+    }
+}
+
+/// Extracts the player as `Option<AuthenticatedPlayer>`, `None` when the request contains no
+/// session token. A token that doesn't belong to any player is still rejected.
+impl<S: Send + Sync> OptionalFromRequestParts<S> for AuthenticatedPlayer {
+    type Rejection = Box<dyn ApplicationError>;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if session_token_from_headers(&parts.headers).is_none() {
+            return Ok(None);
+        }
+
+        <AuthenticatedPlayer as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
+    }
+}
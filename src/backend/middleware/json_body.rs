@@ -0,0 +1,75 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::{
+    body::Bytes,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::header,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::backend::{
+    errors::{
+        application_error::ApplicationError, malformed_body_error::MalformedBodyError,
+        unsupported_media_type_error::UnsupportedMediaTypeError,
+    },
+    middleware::response_format::MSGPACK_CONTENT_TYPE,
+    utils::msgpack,
+};
+
+/// A JSON request body, used as extractor in the handlers instead of `axum::Json`.
+///
+/// The rejection of `axum::Json` is a plain text response. This extractor answers with the same
+/// problem+json format as all other errors of the API.
+///
+/// Clients that get MessagePack responses can send their bodies as MessagePack as well, with
+/// `Content-Type: application/msgpack`.
+///
+/// # Errors
+///
+/// Rejects the request with an `UnsupportedMediaTypeError` (415) when the `Content-Type` header
+/// is missing or is neither `application/json` nor `application/msgpack` and with a
+/// `MalformedBodyError` (400) when the body can't be decoded or doesn't match the expected type.
+pub struct JsonBody<T>(pub T);
+
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Box<dyn ApplicationError>;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_msgpack = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| content_type.split(';').next().unwrap_or_default().trim() == MSGPACK_CONTENT_TYPE);
+        if is_msgpack {
+            let bytes = match Bytes::from_request(request, state).await {
+                Ok(bytes) => bytes,
+                Err(rejection) => return Err(MalformedBodyError::new(rejection.body_text()).into()),
+            };
+            let value = match msgpack::decode(&bytes) {
+                Ok(value) => value,
+                Err(message) => return Err(MalformedBodyError::new(message).into()),
+            };
+            return match serde_json::from_value(value) {
+                Ok(value) => Ok(JsonBody(value)),
+                Err(err) => Err(MalformedBodyError::new(err.to_string()).into()),
+            };
+        }
+
+        match Json::<T>::from_request(request, state).await {
+            Ok(Json(value)) => Ok(JsonBody(value)),
+            Err(rejection @ JsonRejection::MissingJsonContentType(_)) => {
+                Err(UnsupportedMediaTypeError::new(rejection.body_text()).into())
+            }
+            Err(rejection) => Err(MalformedBodyError::new(rejection.body_text()).into()),
+        }
+    }
+}
+    }
+}
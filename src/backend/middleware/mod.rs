@@ -1 +1,4 @@
+pub mod admin_access;
 pub mod authentication;
+pub mod json_body;
+pub mod response_format;
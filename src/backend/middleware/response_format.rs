@@ -0,0 +1,82 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, process_error::ProcessError},
+    types::player::Player,
+    utils::msgpack,
+};
+
+// constants
+
+/// Media type of MessagePack bodies, which clients can ask for in the `Accept` header and send in
+/// the `Content-Type` header.
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Format of the response body the client asked for in the `Accept` header.
+///
+/// Bandwidth-sensitive clients, e.g. on mobile devices, can send
+/// `Accept: application/msgpack` to get the much smaller MessagePack encoding. All other clients
+/// get JSON like before.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResponseFormat {
+    /// A JSON body, the default.
+    Json,
+    /// A MessagePack body.
+    MessagePack,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Box<dyn ApplicationError>;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let accepts_msgpack = parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|accept| {
+                accept
+                    .split(',')
+                    .any(|media_type| media_type.split(';').next().unwrap_or_default().trim() == MSGPACK_CONTENT_TYPE)
+            });
+
+        Ok(if accepts_msgpack {
+            ResponseFormat::MessagePack
+        } else {
+            ResponseFormat::Json
+        })
+    }
+}
+
+impl ResponseFormat {
+    /// Serializes the body of a response in this format and sets the matching content type.
+    ///
+    /// # Arguments
+    ///
+    /// - `body` -> The data sent to the client.
+    pub fn respond<T: Serialize>(self, body: T) -> Response {
+        match self {
+            ResponseFormat::Json => Json(body).into_response(),
+            ResponseFormat::MessagePack => match serde_json::to_value(&body) {
+                Ok(value) => ([(header::CONTENT_TYPE, MSGPACK_CONTENT_TYPE)], msgpack::encode(&value)).into_response(),
+                Err(err) => Box::<dyn ApplicationError>::from(ProcessError::<Player>::new(
+                    err.to_string(),
+                    "ResponseFormat::respond".to_string(),
+                    None,
+                ))
+                .into_response(),
+            },
+        }
+    }
+}
+    }
+}
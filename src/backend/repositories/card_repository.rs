@@ -5,11 +5,13 @@ cfg_if! {
 
 use axum::{http::StatusCode, Json};
 use wasm_bindgen::JsValue;
-use worker::D1Database;
+use crate::backend::utils::query_budget::CountedDatabase;
 
 use crate::backend::{
-    errors::{database_query_error::DatabaseQueryError, process_error::ProcessError, application_error::ApplicationError},
-    types::card::{Card, UpdateCardDTO},
+    enums::card_location::CardLocation,
+    errors::{database_query_error::DatabaseQueryError, not_found_error::NotFoundError, process_error::ProcessError, application_error::ApplicationError, conflict_error::ConflictError, validation_error::ValidationError},
+    types::card::{Card, CardCounts, CardDetails, UpdateCardDTO},
+    utils::database_service::{run_labeled_batch, LabeledStatement},
 };
 
 /// A database repository for interacting with the `cards` table.
@@ -19,7 +21,7 @@ use crate::backend::{
 /// It will be accessible in the context element in the handler functions.
 pub struct CardRepository {
     /// Database pointer to execute queries.
-    db: D1Database,
+    db: CountedDatabase,
 }
 
 impl CardRepository {
@@ -30,8 +32,8 @@ impl CardRepository {
     /// - `db` -> Database service pointer to execute queries.
     ///
     /// # Returns a `CardRepository` instance.
-    pub fn new(db: D1Database) -> Self {
-        CardRepository { db }
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        CardRepository { db: db.into() }
     }
 
     /// Gets a `Card` struct from the database by using its ID.
@@ -81,7 +83,7 @@ impl CardRepository {
                             err.to_string(),
                             None,
                             StatusCode::INTERNAL_SERVER_ERROR,
-                        )));
+                        ).with_query(&query, &params)));
                     }
                 };
 
@@ -91,7 +93,7 @@ impl CardRepository {
                 err.to_string(),
                 None,
                 StatusCode::INTERNAL_SERVER_ERROR,
-            ))),
+            ).with_query(&query, &params))),
         }
     }
 
@@ -137,6 +139,244 @@ impl CardRepository {
         }
     }
 
+    /// Gets a card together with its game, owner and claim.
+    ///
+    /// # Arguments
+    ///
+    /// - `card_id` -> Identifier of the `Card` object.
+    ///
+    /// # Returns the `CardDetails`, a `NotFoundError` when the card doesn't exist or an error if
+    /// the query fails.
+    pub async fn get_card_details(&self, card_id: &str) -> Result<CardDetails, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT id, card_type, game_id, player_id, claim_id, location FROM cards WHERE id = ?;")
+            .bind(&[JsValue::from(card_id)])
+        {
+            Ok(fetched_data) => fetched_data.first::<CardDetails>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(Some(card)) => Ok(card),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!(
+                "The card with the id ['{}'] couldn't be found!",
+                card_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Moves a card into the hand of a player, into a claim or back to the deck.
+    ///
+    /// Unlike `update_card` both columns are always written, so a missing value removes the card
+    /// from its previous hand or claim. The location follows the target and isn't checked against
+    /// the rules of a round, use `transition_card` for moves during the game.
+    ///
+    /// # Arguments
+    ///
+    /// - `card_id` -> Identifier of the `Card` object.
+    /// - `player_id` -> Identifier of the new owner, if any.
+    /// - `claim_id` -> Identifier of the new claim, if any.
+    ///
+    /// # Returns the moved `CardDetails`, a `NotFoundError` when the card doesn't exist or an
+    /// error if the query fails.
+    pub async fn move_card(
+        &self,
+        card_id: &str,
+        player_id: Option<&str>,
+        claim_id: Option<&str>,
+    ) -> Result<CardDetails, Box<dyn ApplicationError>> {
+        let location = match (player_id, claim_id) {
+            (_, Some(_)) => CardLocation::Claim,
+            (Some(_), None) => CardLocation::Hand,
+            (None, None) => CardLocation::Deck,
+        };
+        let query = "UPDATE cards SET player_id = ?, claim_id = ?, location = ? WHERE id = ? RETURNING id, card_type, game_id, player_id, claim_id, location;";
+        let params = vec![
+            player_id.map_or(JsValue::NULL, JsValue::from),
+            claim_id.map_or(JsValue::NULL, JsValue::from),
+            JsValue::from(location.as_str()),
+            JsValue::from(card_id),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&params) {
+            Ok(modified_data) => modified_data.first::<CardDetails>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &params)))
+        };
+
+        match query_result {
+            Ok(Some(card)) => Ok(card),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!(
+                "The card with the id ['{}'] couldn't be found and wasn't moved!",
+                card_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &params))),
+        }
+    }
+
+    /// Moves a random card from the deck of a game into the hand of a player.
+    ///
+    /// Picking the card and assigning it happens in a single statement, so two players drawing at
+    /// the same time never get the same card.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the game whose deck is drawn from.
+    /// - `player_id` -> Identifier of the player who draws the card.
+    ///
+    /// # Returns the drawn `Card`, `None` when the deck is empty, or an error if the query fails.
+    pub async fn draw_card(
+        &self,
+        game_id: &str,
+        player_id: &str,
+    ) -> Result<Option<Card>, Box<dyn ApplicationError>> {
+        let query = "UPDATE cards SET player_id = ?, location = 'Hand' WHERE id = (
+                SELECT id FROM cards WHERE game_id = ? AND location = 'Deck' ORDER BY RANDOM() LIMIT 1
+            ) RETURNING *;";
+        let params = vec![JsValue::from(player_id), JsValue::from(game_id)];
+
+        let query_result = match self.db.prepare(query).bind(&params) {
+            Ok(modified_data) => modified_data.first::<Card>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &params)))
+        };
+
+        match query_result {
+            Ok(drawn_card) => Ok(drawn_card),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &params))),
+        }
+    }
+
+    /// Moves a single card to its next location during a round.
+    ///
+    /// Only the moves allowed by `CardLocation::can_move_to` are accepted. The card is updated
+    /// only if it's still at the location it was read from, so two requests can't move the same
+    /// card at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// - `card_id` -> Identifier of the `Card` object.
+    /// - `next` -> The location the card is moved to.
+    /// - `holder_id` -> Identifier of the player for a move into a hand or of the claim for a move
+    /// into a claim. Cards moved onto the pile stay linked to their claim.
+    ///
+    /// # Returns the moved `CardDetails`, a `NotFoundError` when the card doesn't exist, a
+    /// `ValidationError` when the move isn't allowed or the holder is missing, a `ConflictError`
+    /// when the card was moved in the meantime or an error if the query fails.
+    pub async fn transition_card(
+        &self,
+        card_id: &str,
+        next: CardLocation,
+        holder_id: Option<&str>,
+    ) -> Result<CardDetails, Box<dyn ApplicationError>> {
+        let card = self.get_card_details(card_id).await?;
+        if !card.location.can_move_to(&next) {
+            return Err(Box::new(ValidationError::new(format!(
+                "The card with the id ['{}'] can't be moved from the {} to the {}!",
+                card_id, card.location, next
+            ))));
+        }
+
+        let (query, params) = match (&next, holder_id) {
+            (CardLocation::Hand, Some(player_id)) => (
+                "UPDATE cards SET player_id = ?, claim_id = NULL, location = ? WHERE id = ? AND location = ? RETURNING id, card_type, game_id, player_id, claim_id, location;",
+                vec![JsValue::from(player_id), JsValue::from(next.as_str()), JsValue::from(card_id), JsValue::from(card.location.as_str())],
+            ),
+            (CardLocation::Claim, Some(claim_id)) => (
+                "UPDATE cards SET player_id = NULL, claim_id = ?, location = ? WHERE id = ? AND location = ? RETURNING id, card_type, game_id, player_id, claim_id, location;",
+                vec![JsValue::from(claim_id), JsValue::from(next.as_str()), JsValue::from(card_id), JsValue::from(card.location.as_str())],
+            ),
+            (CardLocation::Pile, _) => (
+                "UPDATE cards SET player_id = NULL, location = ? WHERE id = ? AND location = ? RETURNING id, card_type, game_id, player_id, claim_id, location;",
+                vec![JsValue::from(next.as_str()), JsValue::from(card_id), JsValue::from(card.location.as_str())],
+            ),
+            _ => return Err(Box::new(ValidationError::new(format!(
+                "The card with the id ['{}'] can't be moved to the {} without a player or claim holding it!",
+                card_id, next
+            )))),
+        };
+
+        let query_result = match self.db.prepare(query).bind(&params) {
+            Ok(modified_data) => modified_data.first::<CardDetails>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &params)))
+        };
+
+        match query_result {
+            Ok(Some(moved_card)) => Ok(moved_card),
+            Ok(None) => Err(Box::new(ConflictError::new(format!(
+                "The card with the id ['{}'] was moved by another request and isn't in the {} anymore!",
+                card_id, card.location
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &params))),
+        }
+    }
+
+    /// Moves the cards of all claims of a game onto the pile.
+    ///
+    /// Called before a new claim is placed or the stack is picked up, so only the cards of the
+    /// latest claim are ever in the `Claim` location.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` whose claims are settled.
+    ///
+    /// # Returns `Ok(())` if the cards were updated, or an error if the query fails.
+    pub async fn settle_claims(&self, game_id: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE cards SET location = 'Pile' WHERE game_id = ? AND location = 'Claim';")
+            .bind(&[JsValue::from(game_id)])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
     /// Deletes a `Card` from the database by its ID.
     ///
     /// # Arguments
@@ -167,24 +407,27 @@ impl CardRepository {
         }
     }
 
-    /// Creates a new `Card` in the database.
+    /// Creates a new `Card` in the hand of a player.
     ///
     /// # Arguments
     ///
     /// - `card` -> The `Card` struct to be inserted into the database.
     /// - `player_id` -> Identifier of the `Player` object to which the card belongs.
+    /// - `game_id` -> Identifier of the `Game` object the card is part of.
     ///
     /// # Returns a `Card` instance if the insertion is successful, or an error if it fails.
     pub async fn create_card(
         &self,
         card: Card,
         player_id: String,
+        game_id: &str,
     ) -> Result<Card, Box<dyn ApplicationError>> {
-        let query = "INSERT INTO cards (id, card_type, player_id) VALUES (1?, 2?, 3?) RETURN *;";
+        let query = "INSERT INTO cards (id, card_type, player_id, game_id, location) VALUES (?1, ?2, ?3, ?4, 'Hand') RETURNING *;";
         let params = vec![
             JsValue::from(card.id.clone()),
             JsValue::from(card.card_type.index()),
             JsValue::from(player_id),
+            JsValue::from(game_id),
         ];
 
         let query_result = match self
@@ -217,6 +460,55 @@ impl CardRepository {
         }
     }
 
+    /// Stores the deck of a game, all cards are put into the `Deck` without an owner.
+    ///
+    /// The cards are inserted in a single statement, so a game never ends up with half a deck.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` object the deck belongs to.
+    /// - `deck` -> The cards of the deck, see `game_logic::new_deck`.
+    ///
+    /// # Returns `Ok(())` if the cards were stored, or an error if the query fails.
+    pub async fn create_deck(&self, game_id: &str, deck: &[Card]) -> Result<(), Box<dyn ApplicationError>> {
+        if deck.is_empty() {
+            return Ok(());
+        }
+
+        let query = format!(
+            "INSERT INTO cards (id, card_type, game_id, location) VALUES {};",
+            vec!["(?, ?, ?, 'Deck')"; deck.len()].join(", ")
+        );
+        let params: Vec<JsValue> = deck
+            .iter()
+            .flat_map(|card| {
+                [
+                    JsValue::from(card.id.clone()),
+                    JsValue::from(card.card_type.index()),
+                    JsValue::from(game_id),
+                ]
+            })
+            .collect();
+
+        let query_result = match self.db.prepare(&query).bind(&params) {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(&query, &params)))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &params))),
+        }
+    }
+
     /// Updates an existing `Card` in the database.
     ///
     /// # Arguments
@@ -249,7 +541,7 @@ impl CardRepository {
                     err.to_string(),
                     None,
                     StatusCode::INTERNAL_SERVER_ERROR
-                    )))
+                    ).with_query(&query, &params)))
             };
 
         match query_result {
@@ -261,6 +553,160 @@ impl CardRepository {
                     StatusCode::NOT_FOUND,
                 ))),
             },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &params))),
+        }
+    }
+
+    /// Counts the cards of a game in the central pile and in the deck.
+    ///
+    /// Cards of the latest claim count for the pile as well.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` object.
+    ///
+    /// # Returns the `CardCounts` of the game or an error if the query fails.
+    pub async fn get_card_counts(
+        &self,
+        game_id: &str,
+    ) -> Result<CardCounts, Box<dyn ApplicationError>> {
+        let query = "SELECT COUNT(CASE WHEN location IN ('Claim', 'Pile') THEN 1 END) AS pile_size, COUNT(CASE WHEN location = 'Deck' THEN 1 END) AS deck_remaining FROM cards WHERE game_id = ?;";
+        let params = [JsValue::from(game_id)];
+
+        let query_result = match self.db.prepare(query).bind(&params) {
+            Ok(prepared) => prepared.first::<CardCounts>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &params)))
+        };
+
+        match query_result {
+            // an aggregate without a group always returns a row
+            Ok(counts) => Ok(counts.unwrap_or_default()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &params))),
+        }
+    }
+
+    /// Gets all cards that belong to a game, no matter if they are in a hand, a claim or
+    /// currently unowned.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` object.
+    ///
+    /// # Returns a vector of `Card` instances or an error if the query fails.
+    pub async fn get_all_cards_of_game(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<Card>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT * FROM cards WHERE game_id = ?;")
+            .bind(&[JsValue::from(game_id)])
+        {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(fetched_cards) => match fetched_cards.results::<Card>() {
+                Ok(cards) => Ok(cards),
+                Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves the cards that are placed in a claim of a game but belong to another game.
+    ///
+    /// Used to check the integrity of a game, the list is empty for a consistent game.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` whose claims are checked.
+    ///
+    /// # Returns a vector of `Card` instances or an error if the query fails.
+    pub async fn get_claimed_cards_of_other_games(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<Card>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT cards.* FROM cards JOIN claims ON cards.claim_id = claims.id WHERE claims.game_id = ? AND (cards.game_id IS NULL OR cards.game_id != ?);")
+            .bind(&[JsValue::from(game_id), JsValue::from(game_id)])
+        {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(fetched_cards) => match fetched_cards.results::<Card>() {
+                Ok(cards) => Ok(cards),
+                Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Removes the owner and the claim of all cards of a game in a single statement.
+    ///
+    /// Used before the cards are dealt again, when a round is reset.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` whose cards are collected.
+    ///
+    /// # Returns `Ok(())` if the cards were updated, or an error if the query fails.
+    pub async fn clear_all_owners(&self, game_id: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE cards SET player_id = NULL, claim_id = NULL, location = 'Deck' WHERE game_id = ?;")
+            .bind(&[JsValue::from(game_id)])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
             Err(err) => Err(Box::new(DatabaseQueryError::<Card>::new(
                 err.to_string(),
                 None,
@@ -269,6 +715,53 @@ impl CardRepository {
         }
     }
 
+    /// Hands all cards that are currently placed in claims of a game to a single player.
+    ///
+    /// Used when a player lost a challenge and has to pick up the stack. The latest claim is
+    /// settled onto the pile first and the whole pile is picked up in the same batch.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the `Game` whose stack is picked up.
+    /// - `player_id` -> Identifier of the `Player` who receives the cards.
+    ///
+    /// # Returns `Ok(())` if the cards were updated, or an error if the query fails.
+    pub async fn assign_claimed_cards_to_player(
+        &self,
+        game_id: &str,
+        player_id: &str,
+    ) -> Result<(), Box<dyn ApplicationError>> {
+        let queries = [
+            (
+                "settle claims",
+                "UPDATE cards SET location = 'Pile' WHERE game_id = ? AND location = 'Claim';",
+                vec![JsValue::from(game_id)],
+            ),
+            (
+                "pick up pile",
+                "UPDATE cards SET player_id = ?, claim_id = NULL, location = 'Hand' WHERE game_id = ? AND location = 'Pile';",
+                vec![JsValue::from(player_id), JsValue::from(game_id)],
+            ),
+        ];
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Card>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        match run_labeled_batch::<Card>(&self.db, statements).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(err)),
+        }
+    }
+
     // ----- Helper functions for the 'CardRepository' struct -----
 
     /// Determines the SQL query and bindings to update a card based on the provided
@@ -312,6 +805,13 @@ impl CardRepository {
             params.push(JsValue::from(claim_id));
         }
 
+        // a claim takes precedence over the owner, like in `move_card`
+        if card_data.claim_id.is_some() {
+            query.push_str("location = 'Claim', ");
+        } else if card_data.player_id.is_some() {
+            query.push_str("location = 'Hand', ");
+        }
+
         query.truncate(query.len() - 2); // Remove the last comma and space
         query.push_str(" WHERE id = ? RETURNING *;");
         params.push(JsValue::from(card_data.id.clone()));
@@ -1,15 +1,24 @@
 use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(feature = "ssr")] {
+use std::collections::HashMap;
+
 use axum::http::StatusCode;
+use serde::Deserialize;
 use wasm_bindgen::JsValue;
-use worker::D1Database;
+use crate::backend::utils::query_budget::CountedDatabase;
 
 use crate::backend::{
-    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError},
-    types::chat::ChatMessage,
+    errors::{application_error::ApplicationError, conflict_error::ConflictError, database_query_error::DatabaseQueryError},
+    types::chat::{ChatMessage, ReactionCount},
 };
 
+// constants
+
+/// Number of messages that are loaded when a client opens a chat and doesn't ask for a specific
+/// amount.
+pub const DEFAULT_NUMBER_OF_RECENT_MESSAGES: usize = 20;
+
 /// A database repository for interacting with the `chat_messages` table.
 ///
 /// Contains the utility functions for the `ChatMessage` struct.
@@ -20,7 +29,7 @@ pub struct ChatMessageRepository {
     ///
     /// # Type
     /// - `&'a D1Database` -> A reference to the D1Database instance.
-    db: D1Database,
+    db: CountedDatabase,
 }
 
 impl ChatMessageRepository {
@@ -29,8 +38,8 @@ impl ChatMessageRepository {
     /// # Arguments
     ///
     /// - `db` -> Database service to execute queries.
-    pub fn new(db: D1Database) -> Self {
-        ChatMessageRepository { db }
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        ChatMessageRepository { db: db.into() }
     }
 
     /// Deletes all messages in a specific chat by its ID.
@@ -74,6 +83,50 @@ impl ChatMessageRepository {
         }
     }
 
+    /// Deletes the oldest messages of a chat.
+    ///
+    /// Used to keep a chat under its `max_chat_messages` cap.
+    ///
+    /// # Arguments
+    ///
+    /// - `chat_id` - The ID of the chat.
+    /// - `number_of_messages` - How many of the oldest messages are deleted.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())` if the operation is successful.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the operation.
+    pub async fn delete_oldest_messages(
+        &self,
+        chat_id: &str,
+        number_of_messages: usize,
+    ) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare(
+                "DELETE FROM chat_messages WHERE id IN
+                    (SELECT id FROM chat_messages WHERE chat_id = ? ORDER BY sent_at ASC LIMIT ?);",
+            )
+            .bind(&[JsValue::from(chat_id), JsValue::from(number_of_messages)])
+        {
+            Ok(removed_data) => removed_data.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
     /// Deletes all messages from the `chat_messages` table.
     ///
     /// # Returns
@@ -116,20 +169,22 @@ impl ChatMessageRepository {
     ///
     /// - `message_id` -> Identifier of the message to be deleted.
     ///
+    /// Deleting a message that doesn't exist isn't an error, the caller decides how to answer.
+    ///
     /// # Returns
     ///
-    /// - `Ok(ChatMessage)` containing the deleted message if the operation is successful.
+    /// - `Ok(bool)` whether a message was actually deleted.
     /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the
     pub async fn delete_message_by_id(
         &self,
         message_id: &str,
-    ) -> Result<ChatMessage, Box<dyn ApplicationError>> {
+    ) -> Result<bool, Box<dyn ApplicationError>> {
         let query_result = match self
             .db
-            .prepare("DELETE FROM chat_messages WHERE id = ? RETURNING *;")
+            .prepare("DELETE FROM chat_messages WHERE id = ? RETURNING id;")
             .bind(&[JsValue::from(message_id)])
         {
-            Ok(prepared) => prepared.first::<ChatMessage>(None).await,
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
             Err(err) => {
                 return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
                     err.to_string(),
@@ -140,14 +195,7 @@ impl ChatMessageRepository {
         };
 
         match query_result {
-            Ok(returned_data) => match returned_data {
-                Some(message) => Ok(message),
-                None => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
-                    "Message not found".to_string(),
-                    None,
-                    StatusCode::NOT_FOUND,
-                ))),
-            },
+            Ok(deleted_id) => Ok(deleted_id.is_some()),
             Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
                 err.to_string(),
                 None,
@@ -180,14 +228,14 @@ impl ChatMessageRepository {
         &self,
         chat_id: &str,
     ) -> Result<Vec<ChatMessage>, Box<dyn ApplicationError>> {
-        let query = "SELECT * FROM chat_messages WHERE chat_id = ? ORDER BY created_at ASC;";
+        let query = "SELECT * FROM chat_messages WHERE chat_id = ? ORDER BY sent_at ASC;";
         let params = vec![JsValue::from(chat_id)];
 
         let query_result = self.db.prepare(query).bind(&params).unwrap().all().await;
 
         match query_result {
             Ok(fetched_messages) => {
-                let messages = match fetched_messages.results::<ChatMessage>() {
+                let mut messages = match fetched_messages.results::<ChatMessage>() {
                     Ok(msgs) => msgs,
                     Err(err) => {
                         return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
@@ -197,6 +245,7 @@ impl ChatMessageRepository {
                         )));
                     }
                 };
+                self.attach_reactions(chat_id, &mut messages).await?;
                 Ok(messages)
             }
             Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
@@ -207,20 +256,117 @@ impl ChatMessageRepository {
         }
     }
 
+    /// Retrieves only the last `n` messages of a chat.
+    ///
+    /// The newest messages are selected first and then sorted again from old to new, so the
+    /// result can be displayed directly. Older messages can be loaded by skipping the newest
+    /// ones.
+    ///
+    /// # Arguments
+    ///
+    /// - `chat_id` -> Identifier of the chat whose messages are to be retrieved.
+    /// - `n` -> Maximum number of messages.
+    /// - `skip` -> Number of the newest messages that are left out.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Vec<ChatMessage>)` containing up to `n` messages, the oldest first.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the operation.
+    pub async fn get_recent_messages(
+        &self,
+        chat_id: &str,
+        n: usize,
+        skip: usize,
+    ) -> Result<Vec<ChatMessage>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT * FROM chat_messages WHERE chat_id = ? ORDER BY sent_at DESC LIMIT ? OFFSET ?;")
+            .bind(&[JsValue::from(chat_id), JsValue::from(n), JsValue::from(skip)])
+        {
+            Ok(prepared) => prepared.all().await,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )));
+            }
+        };
+
+        match query_result {
+            Ok(fetched_messages) => match fetched_messages.results::<ChatMessage>() {
+                Ok(mut messages) => {
+                    // newest first -> oldest first
+                    messages.reverse();
+                    self.attach_reactions(chat_id, &mut messages).await?;
+                    Ok(messages)
+                }
+                Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Counts the messages of a chat without loading them.
+    ///
+    /// # Arguments
+    ///
+    /// - `chat_id` -> Identifier of the chat whose messages are counted.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(usize)` containing the number of messages.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the operation.
+    pub async fn count_messages(&self, chat_id: &str) -> Result<usize, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT COUNT(*) AS number_of_messages FROM chat_messages WHERE chat_id = ?;")
+            .bind(&[JsValue::from(chat_id)])
+        {
+            Ok(prepared) => prepared.first::<usize>(Some("number_of_messages")).await,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )));
+            }
+        };
+
+        match query_result {
+            Ok(number_of_messages) => Ok(number_of_messages.unwrap_or(0)),
+            Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
     /// Retrieves a message from the `chat_messages` table by its ID.
     ///
     /// # Arguments
     ///
     /// - `message_id` -> Identifier of the message to be retrieved.
+    /// - `chat_id` -> Optional chat the message has to be part of, so a message of another chat
+    ///   can't be read by guessing its id.
     ///
     /// # Returns
     ///
     /// - `Ok(ChatMessage)` containing the message if found.
-    /// - `Err(DatabaseQueryError<ChatMessage>)` if the message is not found or if an error occurs during the operation.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if the message is not found (in the chat) or if an error occurs during the operation.
     ///
     /// # Example
     /// ```rust
-    /// let result = chat_message_repository.get_message_by_id("message123").await;
+    /// let result = chat_message_repository.get_message_by_id("message123", Some("chat123")).await;
     /// match result {
     ///    Ok(message) => println!("Retrieved message: {:?}", message),
     ///    Err(e) => eprintln!("Error retrieving message: {}", e),
@@ -229,12 +375,18 @@ impl ChatMessageRepository {
     pub async fn get_message_by_id(
         &self,
         message_id: &str,
+        chat_id: Option<&str>,
     ) -> Result<ChatMessage, Box<dyn ApplicationError>> {
-        let query_result = match self
-            .db
-            .prepare("SELECT * FROM chat_messages WHERE id = ?;")
-            .bind(&[JsValue::from(message_id)])
-        {
+        let mut query = "SELECT * FROM chat_messages WHERE id = ?".to_string();
+        let mut bindings = vec![JsValue::from(message_id)];
+
+        if let Some(chat_id) = chat_id {
+            query.push_str(" AND chat_id = ?");
+            bindings.push(JsValue::from(chat_id));
+        }
+        query.push(';');
+
+        let query_result = match self.db.prepare(&query).bind(&bindings) {
             Ok(prepared) => prepared.first::<ChatMessage>(None).await,
             Err(err) => {
                 return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
@@ -247,12 +399,22 @@ impl ChatMessageRepository {
 
         match query_result {
             Ok(fetched_message) => match fetched_message {
-                Some(message) => Ok(message),
+                Some(mut message) => {
+                    let chat_id = message.chat_id.clone();
+                    self.attach_reactions(&chat_id, std::slice::from_mut(&mut message)).await?;
+                    Ok(message)
+                }
                 None => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
-                    format!(
-                        "The chat message with the id ['{}'] couldn't be found!",
-                        message_id
-                    ),
+                    match chat_id {
+                        Some(chat_id) => format!(
+                            "The chat message with the id ['{}'] couldn't be found in the chat ['{}']!",
+                            message_id, chat_id
+                        ),
+                        None => format!(
+                            "The chat message with the id ['{}'] couldn't be found!",
+                            message_id
+                        ),
+                    },
                     None,
                     StatusCode::NOT_FOUND,
                 ))),
@@ -265,27 +427,77 @@ impl ChatMessageRepository {
         }
     }
 
+    /// Looks up a message that was already stored with an idempotency key.
+    ///
+    /// # Arguments
+    ///
+    /// - `chat_id` -> Identifier of the chat the message was sent to.
+    /// - `idempotency_key` -> Key the client sent in the `Idempotency-Key` header.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(ChatMessage))` if a message with the key was already stored.
+    /// - `Ok(None)` if the key wasn't used yet.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the operation.
+    pub async fn get_message_by_idempotency_key(
+        &self,
+        chat_id: &str,
+        idempotency_key: &str,
+    ) -> Result<Option<ChatMessage>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT * FROM chat_messages WHERE chat_id = ? AND idempotency_key = ?;")
+            .bind(&[JsValue::from(chat_id), JsValue::from(idempotency_key)])
+        {
+            Ok(prepared) => prepared.first::<ChatMessage>(None).await,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )));
+            }
+        };
+
+        match query_result {
+            Ok(fetched_message) => Ok(fetched_message),
+            Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
     /// Uses all necessary data and adds a new 'ChatMessage' entity to the database.
     ///
     /// # Arguments
     ///
     /// * `message` - A `ChatMessage` instance that holds all important data.
+    /// * `idempotency_key` - Optional key of the client to detect resent messages.
     ///
     /// # Returns
     ///
     /// - The ealier created `ChatMessage` object in the database, when everything went well.
+    /// - A `ConflictError` when a message with the same id is already stored.
     /// - A `DatabaseQueryError<ChatMessage>` error object in the case something happens.
     pub async fn save_message(
         &self,
         message: &ChatMessage,
+        idempotency_key: Option<&str>,
     ) -> Result<ChatMessage, Box<dyn ApplicationError>> {
-        let query_result = match self.db.prepare("INSERT INTO chat_messages (id, player_id, content, sent_at, chat_id) VALUES (1?, 2?, 3?, 4?, 5?) RETURNING *;")
+        let query_result = match self.db.prepare("INSERT INTO chat_messages (id, player_id, content, sent_at, chat_id, idempotency_key) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(id) DO NOTHING RETURNING *;")
             .bind(&[
                 JsValue::from(&message.id),
                 JsValue::from(&message.player_id),
                 JsValue::from(&message.content),
                 JsValue::from(&message.sent_at),
                 JsValue::from(&message.chat_id),
+                match idempotency_key {
+                    Some(key) => JsValue::from(key),
+                    None => JsValue::NULL,
+                },
             ]) {
             Ok(prepared) => prepared.first::<ChatMessage>(None).await,
             Err(err) => {
@@ -300,15 +512,65 @@ impl ChatMessageRepository {
         match query_result {
             Ok(returned_message) => match returned_message {
                 Some(message) => Ok(message),
-                None => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
-                    format!(
-                        "Failed to add the chat message with ID ['{}'] to the database!",
-                        message.id
-                    ),
+                None => Err(Box::new(ConflictError::new(format!(
+                    "The id ['{}'] is already used by another chat message!",
+                    message.id
+                )))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Adds the reaction of a player to a message.
+    ///
+    /// A player can react with several emojis, but only once with the same emoji. Reacting a
+    /// second time with the same emoji doesn't change anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - Identifier of the message.
+    /// * `player_id` - Identifier of the player who reacts.
+    /// * `emoji` - The emoji the player reacts with.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(true)` if the reaction was added, `Ok(false)` if the player already reacted with the
+    ///   emoji.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the operation.
+    pub async fn add_reaction(
+        &self,
+        message_id: &str,
+        player_id: &str,
+        emoji: &str,
+    ) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare(
+                "INSERT INTO message_reactions (message_id, player_id, emoji, created_at) VALUES (?, ?, ?, ?)
+                    ON CONFLICT (message_id, player_id, emoji) DO NOTHING RETURNING message_id;",
+            )
+            .bind(&[
+                JsValue::from(message_id),
+                JsValue::from(player_id),
+                JsValue::from(emoji),
+                JsValue::from(chrono::Utc::now().to_string()),
+            ]) {
+            Ok(prepared) => prepared.first::<String>(Some("message_id")).await,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
                     None,
                     StatusCode::INTERNAL_SERVER_ERROR,
-                ))),
-            },
+                )));
+            }
+        };
+
+        match query_result {
+            Ok(added_message_id) => Ok(added_message_id.is_some()),
             Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
                 err.to_string(),
                 None,
@@ -316,6 +578,125 @@ impl ChatMessageRepository {
             ))),
         }
     }
+
+    /// Removes the reaction of a player to a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - Identifier of the message.
+    /// * `player_id` - Identifier of the player who reacted.
+    /// * `emoji` - The emoji of the reaction.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(true)` if the reaction was removed, `Ok(false)` if the player didn't react with the
+    ///   emoji.
+    /// - `Err(DatabaseQueryError<ChatMessage>)` if an error occurs during the operation.
+    pub async fn remove_reaction(
+        &self,
+        message_id: &str,
+        player_id: &str,
+        emoji: &str,
+    ) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("DELETE FROM message_reactions WHERE message_id = ? AND player_id = ? AND emoji = ? RETURNING message_id;")
+            .bind(&[
+                JsValue::from(message_id),
+                JsValue::from(player_id),
+                JsValue::from(emoji),
+            ]) {
+            Ok(prepared) => prepared.first::<String>(Some("message_id")).await,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )));
+            }
+        };
+
+        match query_result {
+            Ok(removed_message_id) => Ok(removed_message_id.is_some()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Counts the reactions to the messages of a chat and sets them on the given messages.
+    ///
+    /// The reactions of all messages of the chat are counted in one query. The emojis of a message
+    /// are ordered by the time they were first used.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_id` - Identifier of the chat the messages belong to.
+    /// * `messages` - The messages whose `reactions` are set.
+    async fn attach_reactions(
+        &self,
+        chat_id: &str,
+        messages: &mut [ChatMessage],
+    ) -> Result<(), Box<dyn ApplicationError>> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let query_result = match self
+            .db
+            .prepare(
+                "SELECT message_reactions.message_id, message_reactions.emoji, COUNT(*) AS count
+                    FROM message_reactions JOIN chat_messages ON chat_messages.id = message_reactions.message_id
+                    WHERE chat_messages.chat_id = ?
+                    GROUP BY message_reactions.message_id, message_reactions.emoji
+                    ORDER BY MIN(message_reactions.created_at) ASC;",
+            )
+            .bind(&[JsValue::from(chat_id)])
+        {
+            Ok(prepared) => prepared.all().await,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )));
+            }
+        };
+
+        let rows = match query_result.and_then(|fetched_rows| fetched_rows.results::<ReactionCountRow>()) {
+            Ok(rows) => rows,
+            Err(err) => {
+                return Err(Box::new(DatabaseQueryError::<ChatMessage>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )));
+            }
+        };
+
+        let mut reactions: HashMap<String, Vec<ReactionCount>> = HashMap::new();
+        for row in rows {
+            reactions.entry(row.message_id).or_default().push(ReactionCount {
+                emoji: row.emoji,
+                count: row.count,
+            });
+        }
+        for message in messages.iter_mut() {
+            message.reactions = reactions.remove(&message.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+}
+
+/// Row type for queries that count the reactions to messages.
+#[derive(Deserialize)]
+struct ReactionCountRow {
+    message_id: String,
+    emoji: String,
+    count: usize,
 }
 
     }
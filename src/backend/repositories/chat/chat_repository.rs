@@ -1,12 +1,15 @@
 use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(feature = "ssr")] {
+use std::collections::HashMap;
+
 use axum::http::StatusCode;
+use serde::Deserialize;
 use wasm_bindgen::JsValue;
-use worker::D1Database;
+use crate::backend::utils::query_budget::CountedDatabase;
 
 use crate::backend::{
-    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError, process_error::ProcessError}, repositories::chat::chat_message_repository::ChatMessageRepository, types::chat::{Chat, ChatMessage}
+    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError, not_found_error::NotFoundError, process_error::ProcessError}, repositories::chat::chat_message_repository::ChatMessageRepository, types::chat::{Chat, ChatMessage}
 };
 
 /// A database repository for interacting with the `chats` table.
@@ -20,7 +23,7 @@ pub struct ChatRepository {
     ///
     /// # Type
     /// - `D1Database` -> D1Database instance to interact with the `chats` table.
-    db: D1Database,
+    db: CountedDatabase,
 }
 
 impl ChatRepository {
@@ -29,12 +32,15 @@ impl ChatRepository {
     /// # Returns
     ///
     /// A new instantiated `ChatRepository` object.
-    pub fn new(db: D1Database) -> Self {
-        ChatRepository { db }
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        ChatRepository { db: db.into() }
     }
 
     /// Creates a new instance of a `Chat` struct in the database.
     ///
+    /// A game has only one chat, so when the game already has a chat, e.g. because the creation
+    /// was retried, nothing is inserted and the existing chat is returned instead.
+    ///
     /// # Arguments
     ///
     /// - **chat** -> The `Chat` object that holds necessary data to create the entry in the
@@ -44,36 +50,42 @@ impl ChatRepository {
     ///
     /// => Returned data from the database query as a `Chat` object WHEN the query is successful.
     /// => Returns an error as a `DatabaseQueryError<Chat>` which implements the `ApplicationError` trait WHEN any issue occurs.
-    pub async fn create_chat(&self, chat: Chat) -> Result<Chat, impl ApplicationError> {
-        let insertion_result = match self.db.prepare("INSERT INTO chats (id, number_of_messages, game_id) VALUES (1?, 2? ,3?) RETURNING *;").bind(&[
+    pub async fn create_chat(&self, chat: Chat) -> Result<Chat, Box<dyn ApplicationError>> {
+        // the no-op update makes the statement return the existing row on a conflict
+        let insertion_result = match self.db.prepare("INSERT INTO chats (id, number_of_messages, game_id, max_chat_messages) VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(game_id) DO UPDATE SET game_id = excluded.game_id RETURNING *;").bind(&[
             JsValue::from(chat.id.clone()),
             JsValue::from(chat.number_of_messages),
             JsValue::from(chat.game_id),
+            match chat.max_chat_messages {
+                Some(max_chat_messages) => JsValue::from(max_chat_messages),
+                None => JsValue::NULL,
+            },
         ]) {
             Ok(query_context) => query_context.first::<Chat>(None).await,
-            Err(err) => return Err(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR))
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
         };
 
         match insertion_result {
             Ok(fetched_chat) => match fetched_chat {
                 Some(chat) => Ok(chat),
                 None => {
-                    return Err(DatabaseQueryError::new(
+                    return Err(Box::new(DatabaseQueryError::<Chat>::new(
                         format!(
                             "Attempt to save the chat object with id ['{}'] failed!",
                             chat.id
                         ),
                         None,
                         StatusCode::INTERNAL_SERVER_ERROR,
-                    ))
+                    )))
                 }
             },
             Err(error) => {
-                return Err(DatabaseQueryError::new(
+                return Err(Box::new(DatabaseQueryError::<Chat>::new(
                     error.to_string(),
                     None,
                     StatusCode::INTERNAL_SERVER_ERROR,
-                ))
+                )))
             }
         }
     }
@@ -92,12 +104,12 @@ impl ChatRepository {
     /// # Returns
     ///
     /// => Ok(()) WHEN the removal of the `Chat` object was successful.
-    /// => Err(impl ApplicationError) WHEN any issue is being faced.
+    /// => Err(Box<dyn ApplicationError>) WHEN any issue is being faced.
     pub async fn delete_chat(
         &self,
         chat_id: Option<&str>,
         game_id: Option<&str>,
-    ) -> Result<(), impl ApplicationError> {
+    ) -> Result<(), Box<dyn ApplicationError>> {
         // check if the a game id was passed and then if a chat id is available
         let mut query_string = "DELETE FROM chats WHERE ".to_string();
         let mut query_bindings: Vec<JsValue> = vec![];
@@ -109,22 +121,22 @@ impl ChatRepository {
             query_string.push_str("id = ?;");
             query_bindings.push(JsValue::from(received_chat_id));
         } else {
-            return Err(DatabaseQueryError::<Chat>::new(
+            return Err(Box::new(DatabaseQueryError::<Chat>::new(
                 "An invalid function input was passed to the 'delete_chat' method! Either pass the 'chat_id' or 'game_id' argument after which a 'Chat' entry will be deleted!".to_string(),
                 None,
                 StatusCode::BAD_REQUEST
-            ));
+            )));
         }
 
         // execute the required query
         let deletion_result = match self.db.prepare(query_string).bind(&query_bindings) {
             Ok(prep_query) => prep_query.run().await,
-            Err(error) => return Err(DatabaseQueryError::new(error.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR))
+            Err(error) => return Err(Box::new(DatabaseQueryError::<Chat>::new(error.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
         };
 
         match deletion_result {
             Ok(_) => Ok(()),
-            Err(error) => Err(DatabaseQueryError::new(error.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR))
+            Err(error) => Err(Box::new(DatabaseQueryError::<Chat>::new(error.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
         }
     }
 
@@ -140,51 +152,62 @@ impl ChatRepository {
     /// database.
     /// - `chat_message_repo` => The database repository which provides functionality to interact
     /// with the `chat_messages` table.
+    /// - `idempotency_key` => Optional key of the client; when a message with the same key was
+    /// already stored in the chat, that message is returned and nothing is inserted.
     ///
     /// # Returns
     ///
     /// 1.) Ok(ChatMessage), WHEN all queries went well and the final operation returned the same
     ///   stored `ChatMessage` object.
+    /// 2.) Err(Box<dyn ApplicationError>), WHEN a query failed. Is a `ConflictError` when the id of
+    ///   the message is already used, then the number of messages stays unchanged.
     pub async fn add_new_message_to_chat(
         &self,
         chat_id: &str,
         chat_message: ChatMessage,
         chat_message_repo: &ChatMessageRepository,
+        idempotency_key: Option<&str>,
     ) -> Result<ChatMessage, Box<dyn ApplicationError>> {
-        // get the 'number_of_messages' and increment by 1
-        let updated_num_of_mess = match self.get_number_of_messages_of_chat(None, Some(chat_id)).await {
-            Ok(number_of_messages) => number_of_messages + 1,
-            Err(err) => {
-                return Err(err);
-            }
-        };
-
-        // update the chat instance with the new number_of_messages
-        let modification_result = match self.db.prepare("UPDATE chats SET number_of_messages = 1? WHERE id = 2? RETURNING *;").bind(&[
-            JsValue::from(updated_num_of_mess), JsValue::from(chat_id)
-        ]) {
-            Ok(returned_data) => returned_data.first::<Chat>(None).await,
-            Err(err) => {
-                return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)));
+        // a resent message must neither be stored nor counted twice
+        if let Some(key) = idempotency_key {
+            match chat_message_repo.get_message_by_idempotency_key(chat_id, key).await {
+                Ok(Some(stored_message)) => return Ok(stored_message),
+                Ok(None) => (),
+                Err(err) => return Err(err),
             }
-        };
+        }
 
-        // just check if an error occured in the result data progression
-        match modification_result {
-            Ok(_) => (),
+        // increment the 'number_of_messages' in the database itself, so concurrent messages
+        // can't overwrite each others count
+        let updated_num_of_mess = match self.increment_number_of_messages(chat_id).await {
+            Ok(number_of_messages) => number_of_messages,
             Err(err) => {
-                return Err(Box::new(ProcessError::<Chat>::new(err.to_string(), "ChatRepository::add_new_message_to_chat".to_string(), None)));
+                return Err(err);
             }
         };
 
         // create new 'ChatMessage' instance
-        let chat_message_insertion_result = match chat_message_repo.save_message(&chat_message).await {
+        let chat_message_insertion_result = match chat_message_repo.save_message(&chat_message, idempotency_key).await {
             Ok(message) => message,
             Err(err) => {
+                // the message wasn't stored, e.g. because its id is already used
+                self.decrement_number_of_messages(chat_id).await?;
                 return Err(err)
             }
         };
 
+        // remove the oldest messages when the chat has a cap and it was exceeded
+        let max_chat_messages = match self.get_max_chat_messages(chat_id).await {
+            Ok(max_chat_messages) => max_chat_messages,
+            Err(err) => return Err(err)
+        };
+        if let Some(max) = max_chat_messages {
+            if updated_num_of_mess > max {
+                chat_message_repo.delete_oldest_messages(chat_id, updated_num_of_mess - max).await?;
+                self.update_number_of_messages_of_chat(max, Some(chat_id), None).await?;
+            }
+        }
+
         Ok(chat_message_insertion_result)
     }
 
@@ -199,41 +222,82 @@ impl ChatRepository {
     ///
     /// # Returns
     ///
-    /// 1.) `Ok(ChatMessage)`, WHEN the `Chat` has been updated and the `ChatMessage` was deleted.
+    /// 1.) `Ok(bool)`, whether the `ChatMessage` was deleted; the `Chat` is only updated then.
     /// 2.) `Err(Box<dyn ApplicationError>)`, WHEN in the process of operation a problem occurs.
     pub async fn remove_message_from_chat(
         &self,
         chat_id: &str,
         message_id: &str,
         chat_message_repo: &ChatMessageRepository
-    ) -> Result<ChatMessage, Box<dyn ApplicationError>> {
+    ) -> Result<bool, Box<dyn ApplicationError>> {
+        // remove the 'ChatMessage' from the 'Chat' queue
+        let was_deleted = chat_message_repo.delete_message_by_id(message_id).await?;
+
         // update the 'number_of_messages' -> decrement by one
-        let nr_of_mes_fetch_result = match self.get_number_of_messages_of_chat(None, Some(chat_id)).await {
-            Ok(res) => {
-                if res == 0 { return Err(Box::new(ProcessError::<ChatMessage>::new(format!("The number of messages of the chat with the id ['{}'] can't be negative!", chat_id), "ChatRepository::remove_message_from_chat".to_string(), None)));
-                }
-                res - 1
-            },
-            Err(err) => return Err(err)
-        };
+        if was_deleted {
+            self.decrement_number_of_messages(chat_id).await?;
+        }
 
-        // update the 'number_of_messages'
-        let modification_result = match self.update_number_of_messages_of_chat(nr_of_mes_fetch_result, Some(chat_id), None).await {
-            Ok(new_nr) => new_nr,
-            Err(err) => return Err(err)
+        Ok(was_deleted)
+    }
+
+    /// Increments the `number_of_messages` column of a `Chat` by one.
+    ///
+    /// The new value is calculated by the database, so no update gets lost when several messages
+    /// are sent at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// -> `chat_id` => The identifier of the `Chat` object.
+    ///
+    /// # Returns
+    ///
+    /// 1.) Ok(usize), WHEN the counter was incremented, contains the new value.
+    /// 2.) Err(Box<dyn ApplicationError>), WHEN the chat doesn't exist or the query failed.
+    pub async fn increment_number_of_messages(&self, chat_id: &str) -> Result<usize, Box<dyn ApplicationError>> {
+        let query = "UPDATE chats SET number_of_messages = number_of_messages + 1 WHERE id = ?1 RETURNING number_of_messages;";
+        let bindings = [JsValue::from(chat_id)];
+
+        let modification_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(statement) => statement.first::<usize>(Some("number_of_messages")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &bindings)))
         };
 
-        if modification_result != nr_of_mes_fetch_result {
-            return Err(Box::new(ProcessError::<ChatMessage>::new("The 'number_of_messages' property hasn't changed after a successful operation!".to_string(), "ChatRepository::remove_message_from_chat".to_string(), None)))
+        match modification_result {
+            Ok(Some(new_number)) => Ok(new_number),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!("The 'Chat' object with the id ['{}'] couldn't be found, therefore the 'number_of_messages' couldn't be incremented!", chat_id)))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &bindings)))
         }
+    }
 
-        // remove the 'ChatMessage' from the 'Chat' queue
-        let removal_result = match chat_message_repo.delete_message_by_id(message_id).await {
-            Ok(deleted_message) => deleted_message,
-            Err(err) => return Err(err)
+    /// Decrements the `number_of_messages` column of a `Chat` by one.
+    ///
+    /// Like `increment_number_of_messages` the new value is calculated by the database. The
+    /// counter never drops below zero.
+    ///
+    /// # Arguments
+    ///
+    /// -> `chat_id` => The identifier of the `Chat` object.
+    ///
+    /// # Returns
+    ///
+    /// 1.) Ok(usize), WHEN the counter was decremented, contains the new value.
+    /// 2.) Err(Box<dyn ApplicationError>), WHEN the chat doesn't exist, has no messages left or
+    ///   the query failed.
+    pub async fn decrement_number_of_messages(&self, chat_id: &str) -> Result<usize, Box<dyn ApplicationError>> {
+        let query = "UPDATE chats SET number_of_messages = number_of_messages - 1 WHERE id = ?1 AND number_of_messages > 0 RETURNING number_of_messages;";
+        let bindings = [JsValue::from(chat_id)];
+
+        let modification_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(statement) => statement.first::<usize>(Some("number_of_messages")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &bindings)))
         };
 
-        Ok(removal_result)
+        match modification_result {
+            Ok(Some(new_number)) => Ok(new_number),
+            Ok(None) => Err(Box::new(ProcessError::<ChatMessage>::new(format!("The chat with the id ['{}'] couldn't be found or the number of its messages can't be negative!", chat_id), "ChatRepository::decrement_number_of_messages".to_string(), None))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &bindings)))
+        }
     }
 
     /// A ***modification*** method to update the `number_of_messages` column of a `Chat` entry in
@@ -249,18 +313,19 @@ impl ChatRepository {
     /// # Returns
     ///
     /// 1.) Ok(usize), WHEN the operation was sucessful.
-    /// 2.) Err(Box<dyn ApplicationError>), WHEN any kind of issue occurs.
+    /// 2.) Err(Box<dyn ApplicationError>), WHEN any kind of issue occurs. Is a `NotFoundError`
+    ///   when no `Chat` matched the arguments.
     pub async fn update_number_of_messages_of_chat(&self, updated_number_of_messages: usize, chat_id: Option<&str>, game_id: Option<&str>) -> Result<usize, Box<dyn ApplicationError>> {
         // temporary variables
-        let mut query_string = "UPDATE chats SET number_of_messages = 1? WHERE".to_string();
+        let mut query_string = "UPDATE chats SET number_of_messages = ?1 WHERE".to_string();
         let mut query_bindings: Vec<JsValue> = vec![JsValue::from(updated_number_of_messages)];
 
         // filter the selection arguments
         if let Some(recv_game_id) = game_id {
-            query_string.push_str(" game_id = 2? ");
+            query_string.push_str(" game_id = ?2 ");
             query_bindings.push(JsValue::from(recv_game_id));
         } else if let Some(recv_chat_id) = chat_id {
-            query_string.push_str(" id = 2? ");
+            query_string.push_str(" id = ?2 ");
             query_bindings.push(JsValue::from(recv_chat_id));
         } else {
             return Err(Box::new(ProcessError::<ChatMessage>::new("An invalid data input was passed to the 'update_number_of_messages_of_chat' function! At least pass either 'chat_id' or 'game_id'!".to_string(), "ChatRepository::update_number_of_messages_of_chat".to_string(), None)));
@@ -270,7 +335,7 @@ impl ChatRepository {
 
         // execute query
         let modification_result = match self.db.prepare(query_string).bind(&query_bindings) {
-            Ok(result) => result.first::<usize>(None).await,
+            Ok(result) => result.first::<usize>(Some("number_of_messages")).await,
             Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
 
         };
@@ -279,18 +344,41 @@ impl ChatRepository {
         match modification_result {
             Ok(successful_result) => match successful_result {
                 Some(new_number) => Ok(new_number),
-                None => return Err(Box::new(ProcessError::<Chat>::new(match chat_id {
+                None => return Err(Box::new(NotFoundError::new(match chat_id {
                     Some(id) => format!("The 'Chat' object with the id ['{}'] couldn't be found, therefore the 'number_of_messages' couldn't be updated!", id),
                     None => match game_id {
                         Some(id) => format!("The 'Chat' object belonging to the game with the id ['{}'] couldn't be found, therefore the 'number_of_messages' couldn't be updated!", id),
                         None => format!("The 'Chat' instance couldn't be found!")
                     }
-                }, "ChatRepository::update_number_of_messages_of_chat".to_string(), None)))
+                })))
             },
             Err(err) => return Err(Box::new(ProcessError::<Chat>::new(err.to_string(), "ChatRepository::update_number_of_messages_of_chat".to_string(), None)))
         }
     }
 
+    /// Fetches the optional `max_chat_messages` setting of a `Chat`.
+    ///
+    /// # Arguments
+    ///
+    /// - ***`chat_id`*** => The own identifier of the `Chat` instance.
+    ///
+    /// # Returns
+    ///
+    /// => 1.) Ok(Option<usize>), the cap or `None` when the chat isn't trimmed.
+    /// => 2.) Err(Box<dyn ApplicationError>), WHEN the chat couldn't be found or the query failed.
+    pub async fn get_max_chat_messages(&self, chat_id: &str) -> Result<Option<usize>, Box<dyn ApplicationError>> {
+        let fetch_query_result = match self.db.prepare("SELECT max_chat_messages FROM chats WHERE id = ?;").bind(&[JsValue::from(chat_id)]) {
+            Ok(received_data) => received_data.first::<ChatLimit>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
+        };
+
+        match fetch_query_result {
+            Ok(Some(limit)) => Ok(limit.max_chat_messages),
+            Ok(None) => Err(Box::new(DatabaseQueryError::<Chat>::new(format!("The 'Chat' instance with the id ['{}'] couldn't be found in the database!", chat_id), None, StatusCode::NOT_FOUND))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
+        }
+    }
+
     /// Fetches the `number_of_messages` property of a `Chat` struct by either using the `game_id` or `chat_id` argument.
     /// Here by is the `game_id` argument prefered.
     ///
@@ -302,10 +390,12 @@ impl ChatRepository {
     ///
     /// # Returns
     ///
-    /// => 1.) Ok(usize), WHEN the data could be fetched.
-    /// => 2.) Err(Box<impl ApplicationError>), WHEN any kind of error occurs.
+    /// => 1.) Ok(usize), WHEN the data could be fetched. A `NULL` in the column counts as 0
+    /// messages.
+    /// => 2.) Err(Box<impl ApplicationError>), WHEN any kind of error occurs. Is a
+    /// `NotFoundError` when the chat doesn't exist.
     pub async fn get_number_of_messages_of_chat(&self, game_id: Option<&str>, chat_id: Option<&str>) -> Result<usize, Box<dyn ApplicationError>> {
-        let mut query_string = "SELECT number_of_messages FROM chats WHERE ".to_string();
+        let mut query_string = "SELECT COALESCE(number_of_messages, 0) AS number_of_messages FROM chats WHERE ".to_string();
         let mut query_bindings: Vec<JsValue> = vec![];
 
         // filtering after the arguments passed to the method
@@ -320,21 +410,22 @@ impl ChatRepository {
         }
 
         let fetch_query_result = match self.db.prepare(query_string).bind(&query_bindings) {
-            Ok(received_data) => received_data.first::<usize>(None).await,
+            Ok(received_data) => received_data.first::<usize>(Some("number_of_messages")).await,
             Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR)))
         };
 
         match fetch_query_result {
             Ok(recv_data) => match recv_data {
                 Some(number_of_messages) => Ok(number_of_messages),
+                // no row at all, a NULL count was already turned into 0 by the query
                 None => {
-                    return Err(Box::new(DatabaseQueryError::<Chat>::new(match chat_id {
-                        Some(id) => format!("The 'Chat' instance with the id ['{}'] couldn't be found in the database!", id),
-                        None => match game_id {
-                            Some(id) => format!("A 'Chat' entry with the game id ['{}'] wasn't in the database!", id),
+                    return Err(Box::new(NotFoundError::new(match game_id {
+                        Some(id) => format!("A 'Chat' entry with the game id ['{}'] wasn't in the database!", id),
+                        None => match chat_id {
+                            Some(id) => format!("The 'Chat' instance with the id ['{}'] couldn't be found in the database!", id),
                             None => "No 'Chat' object was found!".to_string()
                         }
-                    }, None, StatusCode::NOT_FOUND)))
+                    })))
                 }
             },
             Err(err) => {
@@ -370,7 +461,7 @@ impl ChatRepository {
             query_string.push_str("WHERE game_id = ?;");
             query_bindings.push(JsValue::from(recv_game_id));
         } else if let Some(recv_chat_id) = chat_id {
-            query_string.push_str("WHERE chat_id = ?;");
+            query_string.push_str("WHERE id = ?;");
             query_bindings.push(JsValue::from(recv_chat_id));
         }
 
@@ -402,6 +493,91 @@ impl ChatRepository {
             }
         }
     }
+
+    /// Stores the read receipt of a player, marking every message up to and including `message`
+    /// as read.
+    ///
+    /// A receipt never moves backwards, marking an older message as read keeps the newer receipt.
+    ///
+    /// # Arguments
+    ///
+    /// - ***`chat_id`*** => Identifier of the `Chat` the message belongs to.
+    /// - ***`player_id`*** => Identifier of the player who read the messages.
+    /// - ***`message`*** => The last message the player has read.
+    ///
+    /// # Returns
+    ///
+    /// => 1.) Ok(()), WHEN the receipt was stored or a newer one already exists.
+    /// => 2.) Err(Box<dyn ApplicationError>), WHEN the query failed.
+    pub async fn mark_messages_read(&self, chat_id: &str, player_id: &str, message: &ChatMessage) -> Result<(), Box<dyn ApplicationError>> {
+        let query = "INSERT INTO read_receipts (chat_id, player_id, last_read_message_id, last_read_sent_at) VALUES (?, ?, ?, ?) ON CONFLICT(chat_id, player_id) DO UPDATE SET last_read_message_id = excluded.last_read_message_id, last_read_sent_at = excluded.last_read_sent_at, updated_at = CURRENT_TIMESTAMP WHERE excluded.last_read_sent_at >= read_receipts.last_read_sent_at;";
+        let params = [
+            JsValue::from(chat_id),
+            JsValue::from(player_id),
+            JsValue::from(message.id.as_str()),
+            JsValue::from(message.sent_at.as_str()),
+        ];
+
+        let upsert_result = match self.db.prepare(query).bind(&params) {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &params)))
+        };
+
+        match upsert_result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &params)))
+        }
+    }
+
+    /// Counts the unread messages of every player in the game of a `Chat`.
+    ///
+    /// The messages are joined with the read receipt of every player and counted in a single
+    /// grouped query, so no message has to be loaded. Messages sent by the player itself never
+    /// count as unread. A player without a read receipt hasn't read any message yet.
+    ///
+    /// # Arguments
+    ///
+    /// - ***`chat_id`*** => Identifier of the `Chat`.
+    ///
+    /// # Returns
+    ///
+    /// => 1.) Ok(HashMap<String, usize>), the number of unread messages keyed by the player id.
+    /// => 2.) Err(Box<dyn ApplicationError>), WHEN the query failed.
+    pub async fn get_unread_counts(&self, chat_id: &str) -> Result<HashMap<String, usize>, Box<dyn ApplicationError>> {
+        let query = "SELECT p.id AS player_id, COUNT(m.id) AS unread
+            FROM chats c
+            JOIN players p ON p.game_id = c.game_id
+            LEFT JOIN read_receipts r ON r.chat_id = c.id AND r.player_id = p.id
+            LEFT JOIN chat_messages m ON m.chat_id = c.id AND m.player_id != p.id AND (r.last_read_sent_at IS NULL OR m.sent_at > r.last_read_sent_at)
+            WHERE c.id = ?
+            GROUP BY p.id;";
+        let params = [JsValue::from(chat_id)];
+
+        let fetch_result = match self.db.prepare(query).bind(&params) {
+            Ok(prepared) => prepared.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &params)))
+        };
+
+        let rows = match fetch_result.and_then(|result| result.results::<UnreadCount>()) {
+            Ok(rows) => rows,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Chat>::new(err.to_string(), None, StatusCode::INTERNAL_SERVER_ERROR).with_query(query, &params)))
+        };
+
+        Ok(rows.into_iter().map(|row| (row.player_id, row.unread)).collect())
+    }
+}
+
+/// Row type for queries that only select the `max_chat_messages` column of the `chats` table.
+#[derive(Deserialize)]
+struct ChatLimit {
+    max_chat_messages: Option<usize>,
+}
+
+/// Row type for queries that count the unread messages of a player.
+#[derive(Deserialize)]
+struct UnreadCount {
+    player_id: String,
+    unread: usize,
 }
     }
 }
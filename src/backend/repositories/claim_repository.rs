@@ -5,12 +5,13 @@ cfg_if! {
 
 use axum::{http::StatusCode, Json};
 use wasm_bindgen::JsValue;
-use worker::D1Database;
+use crate::backend::utils::query_budget::CountedDatabase;
 
 use crate::backend::{
+    enums::{card_location::CardLocation, claim_outcome::ClaimOutcome},
     errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError},
     repositories::card_repository::CardRepository,
-    types::{card::UpdateCardDTO, claim::Claim},
+    types::claim::Claim,
 };
 
 /// A database repository for interacting with the `claims` table.
@@ -19,7 +20,7 @@ use crate::backend::{
 ///
 /// It will be accessable in the context element in the handler functions.
 pub struct ClaimsRepository {
-    db: D1Database,
+    db: CountedDatabase,
 }
 
 // ----- Implementation of the 'ClaimsRepository' struct -----
@@ -30,8 +31,8 @@ impl ClaimsRepository {
     /// # Arguments
     ///
     /// - `db` -> Database service pointer to execute queries.
-    pub fn new(db: D1Database) -> Self {
-        ClaimsRepository { db }
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        ClaimsRepository { db: db.into() }
     }
 
     /// Gets a `Claim` struct from the database by using its ID.
@@ -64,6 +65,7 @@ impl ClaimsRepository {
                     message: format!("The claim with the id {} couldn't be found!", id),
                     received_data: None,
                     status_code: StatusCode::NOT_FOUND,
+                    query_context: None,
                 })),
             },
             Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
@@ -74,7 +76,7 @@ impl ClaimsRepository {
         }
     }
 
-    /// Retrieves all claims from the database, optionally filtered by game ID or player ID.
+    /// Retrieves all claims from the database, optionally filtered by game ID and / or player ID.
     ///
     /// # Arguments
     ///
@@ -82,7 +84,8 @@ impl ClaimsRepository {
     /// claims.
     /// - `game_id` -> Optional game ID to filter claims by game.
     /// - `player_id` -> Optional player ID to filter claims by player.
-    /// If both are `None`, all claims will be returned.
+    /// If both are given, only the claims of the player in that game are returned. If both are
+    /// `None`, all claims will be returned.
     ///
     /// # Returns a vector of `Claim` instances or an error if the query fails.
     pub async fn get_all_claims(
@@ -94,14 +97,22 @@ impl ClaimsRepository {
         let mut query = "SELECT * FROM claims".to_string();
         let mut params: Vec<JsValue> = Vec::new();
 
+        let mut conditions: Vec<&str> = Vec::new();
+
         if let Some(game_id) = game_id {
-            query.push_str(" WHERE game_id = ?");
+            conditions.push("game_id = ?");
             params.push(JsValue::from(game_id));
-        } else if let Some(player_id) = player_id {
-            query.push_str(" WHERE created_by = ?");
+        }
+        if let Some(player_id) = player_id {
+            conditions.push("created_by = ?");
             params.push(JsValue::from(player_id));
         }
 
+        if !conditions.is_empty() {
+            query.push_str(" WHERE ");
+            query.push_str(&conditions.join(" AND "));
+        }
+
         query.push_str(";");
 
         let query_result = match self.db.prepare(&query).bind(&params){
@@ -110,7 +121,7 @@ impl ClaimsRepository {
                 err.to_string(),
                 None,
                 StatusCode::INTERNAL_SERVER_ERROR
-            )))
+            ).with_query(&query, &params)))
         };
 
         match query_result {
@@ -122,7 +133,7 @@ impl ClaimsRepository {
                             err.to_string(),
                             None,
                             StatusCode::INTERNAL_SERVER_ERROR,
-                        )));
+                        ).with_query(&query, &params)));
                     }
                 };
 
@@ -144,6 +155,72 @@ impl ClaimsRepository {
 
                 Ok(extracted_claims)
             }
+            Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &params))),
+        }
+    }
+
+    /// Checks whether a claim was placed in a game.
+    ///
+    /// # Arguments
+    ///
+    /// - `claim_id` -> Identifier of the claim.
+    /// - `game_id` -> Identifier of the game.
+    ///
+    /// # Returns `true` when the claim belongs to the game or an error if the query fails.
+    pub async fn is_claim_in_game(&self, claim_id: &str, game_id: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT COUNT(*) AS number_of_claims FROM claims WHERE id = ? AND game_id = ?;")
+            .bind(&[JsValue::from(claim_id), JsValue::from(game_id)])
+        {
+            Ok(fetched_data) => fetched_data.first::<usize>(Some("number_of_claims")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(number_of_claims) => Ok(number_of_claims.unwrap_or(0) > 0),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Returns the id of the claim that was placed last in a game.
+    ///
+    /// Claims have no timestamp, so the order in which the rows were inserted is used.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the game.
+    ///
+    /// # Returns the id of the latest claim, `None` when the stack is empty, or an error if the
+    /// query fails.
+    pub async fn get_latest_claim_id(&self, game_id: &str) -> Result<Option<String>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT id FROM claims WHERE game_id = ? ORDER BY rowid DESC LIMIT 1;")
+            .bind(&[JsValue::from(game_id)])
+        {
+            Ok(fetched_data) => fetched_data.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(claim_id) => Ok(claim_id),
             Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
                 err.to_string(),
                 None,
@@ -157,21 +234,37 @@ impl ClaimsRepository {
     /// # Arguments
     ///
     /// - `claim` -> The `Claim` struct to be inserted into the database.
+    /// - `game_id` -> Identifier of the game the claim was placed in.
     /// - `card_repository` -> Reference to the `CardRepository` to handle cards associated with
     /// the claim.
     ///
-    /// # Returns a `Claim` instance if the insertion is successful, or an error if it fails.
+    /// # Returns a `Claim` instance if the insertion is successful, a `ValidationError` when the
+    /// claim announces no cards, a `ForbiddenError` when a card isn't in the hand of the player
+    /// who places the claim in this game, or an error if it fails.
     pub async fn create_claim(
         &self,
         claim: Claim,
+        game_id: &str,
         card_repository: &CardRepository,
     ) -> Result<Claim, Box<dyn ApplicationError>> {
+        claim.validate_number_of_cards()?;
+
+        // the card ids are sent by the client, only the own cards of the game can be placed
+        for card in &claim.cards {
+            card_repository
+                .get_card_details(&card.id)
+                .await?
+                .check_held_by(game_id, &claim.created_by)?;
+        }
+
         let query =
-            "INSERT INTO claims (id, created_by, number_of_cards, cards) VALUES (?, ?, ?, ?);";
+            "INSERT INTO claims (id, created_by, announced_rank, number_of_cards, game_id) VALUES (?, ?, ?, ?, ?);";
         let params = vec![
             JsValue::from(claim.id.clone()),
             JsValue::from(claim.created_by.clone()),
+            JsValue::from(claim.announced_rank.as_str()),
             JsValue::from(claim.number_of_cards as i32),
+            JsValue::from(game_id),
         ];
 
         let query_result = match self.db.prepare(query).bind(&params) {
@@ -183,28 +276,64 @@ impl ClaimsRepository {
             )))
         };
 
+        if let Err(err) = query_result {
+            return Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                Some(Json(claim)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )));
+        }
+
+        // the previous claim can't be challenged anymore, its cards belong to the pile now
+        card_repository.settle_claims(game_id).await?;
+
         // cards need to be stored separatly
         for card in &claim.cards {
-            let res = card_repository
-                .update_card(
-                    match UpdateCardDTO::new(card.id.clone(), None, None, Some(claim.id.clone())) {
-                        Ok(update_card) => update_card,
-                        Err(err) => {
-                            return Err(Box::new(err));
-                        }
-                    },
-                )
-                .await;
-            if let Err(err) = res {
-                return Err(err);
-            }
+            card_repository
+                .transition_card(&card.id, CardLocation::Claim, Some(&claim.id))
+                .await?;
         }
 
+        Ok(claim)
+    }
+
+    /// Stores the outcome of a challenged claim.
+    ///
+    /// # Arguments
+    ///
+    /// - `claim_id` -> Identifier of the challenged `Claim`.
+    /// - `outcome` -> Result of the challenge.
+    /// - `challenged_by` -> Identifier of the player who challenged the claim.
+    ///
+    /// # Returns `Ok(())` if the claim was updated, or an error if the query fails.
+    pub async fn set_outcome(
+        &self,
+        claim_id: &str,
+        outcome: &ClaimOutcome,
+        challenged_by: &str,
+    ) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE claims SET outcome = ?, challenged_by = ? WHERE id = ?;")
+            .bind(&[
+                JsValue::from(outcome.as_str()),
+                JsValue::from(challenged_by),
+                JsValue::from(claim_id),
+            ])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
         match query_result {
-            Ok(_) => Ok(claim),
+            Ok(_) => Ok(()),
             Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
                 err.to_string(),
-                Some(Json(claim)),
+                None,
                 StatusCode::INTERNAL_SERVER_ERROR,
             ))),
         }
@@ -212,18 +341,20 @@ impl ClaimsRepository {
 
     /// Deletes a claim from the database by its ID.
     ///
+    /// Deleting a claim that doesn't exist isn't an error, the caller decides how to answer.
+    ///
     /// # Arguments
     ///
     /// - `id` -> Identifier of the `Claim` object to be deleted.
     ///
-    /// # Returns `Ok(())` if the deletion is successful, or an error if it fails.
-    pub async fn delete_claim(&self, claim_id: String) -> Result<(), Box<dyn ApplicationError>> {
+    /// # Returns whether a claim was actually deleted, or an error if the query fails.
+    pub async fn delete_claim(&self, claim_id: String) -> Result<bool, Box<dyn ApplicationError>> {
         let query_result = match self
             .db
-            .prepare("DELETE FROM claims WHERE id = ?;")
+            .prepare("DELETE FROM claims WHERE id = ? RETURNING id;")
             .bind(&[JsValue::from(claim_id)])
         {
-            Ok(removed_data) => removed_data.run().await,
+            Ok(removed_data) => removed_data.first::<String>(Some("id")).await,
             Err(err) => return Err(Box::new(DatabaseQueryError::<Claim>::new(
                 err.to_string(),
                 None,
@@ -232,7 +363,7 @@ impl ClaimsRepository {
         };
 
         match query_result {
-            Ok(_) => Ok(()),
+            Ok(deleted_id) => Ok(deleted_id.is_some()),
             Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
                 err.to_string(),
                 None,
@@ -241,6 +372,48 @@ impl ClaimsRepository {
         }
     }
 
+    /// Deletes several claims by their IDs in a single query.
+    ///
+    /// # Arguments
+    ///
+    /// - `ids` -> Identifiers of the `Claim` objects to be deleted.
+    ///
+    /// # Returns the number of deleted claims, or an error if the deletion fails.
+    pub async fn delete_claims(&self, ids: &[String]) -> Result<usize, Box<dyn ApplicationError>> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let query = format!("DELETE FROM claims WHERE id IN ({});", placeholders);
+        let params: Vec<JsValue> = ids.iter().map(JsValue::from).collect();
+
+        let query_result = match self.db.prepare(&query).bind(&params) {
+            Ok(removed_data) => removed_data.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(&query, &params)))
+        };
+
+        match query_result {
+            Ok(result) => match result.meta() {
+                Ok(meta) => Ok(meta.and_then(|meta| meta.changes).unwrap_or(0)),
+                Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Claim>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &params))),
+        }
+    }
+
     /// Deletes all claims associated with a specific game ID from the database.
     ///
     /// # Arguments
@@ -0,0 +1,80 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+
+use axum::http::StatusCode;
+use wasm_bindgen::JsValue;
+use crate::backend::utils::query_budget::CountedDatabase;
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError},
+    types::game_event::GameEvent,
+};
+
+/// A database repository for interacting with the `game_events` table.
+///
+/// The events are only read here, they are recorded by triggers of the database whenever a game,
+/// its players, claims or score events change.
+///
+/// # Properties
+///
+/// `db`: A `CountedDatabase` that provides access to the D1 database.
+pub struct GameEventRepository {
+    /// The D1 database instance used for accessing the game events.
+    db: CountedDatabase,
+}
+
+impl GameEventRepository {
+    /// Creates a new `GameEventRepository` instance with the provided D1 database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - An instance of `D1Database` to be used for database operations.
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        GameEventRepository { db: db.into() }
+    }
+
+    /// Gets the next events of a game after a known one, in the order they happened.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `after` - Sequence of the last known event, 0 to start with the first one.
+    /// * `limit` - Maximum number of returned events.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing up to `limit` events, or an error on failure.
+    pub async fn get_events_after(&self, game_id: &str, after: usize, limit: usize) -> Result<Vec<GameEvent>, Box<dyn ApplicationError>> {
+        let query = "SELECT * FROM game_events WHERE game_id = ? AND sequence > ? ORDER BY sequence ASC LIMIT ?;";
+        let bindings = [JsValue::from(game_id), JsValue::from(after), JsValue::from(limit)];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<GameEvent>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(fetched_events) => match fetched_events.results::<GameEvent>() {
+                Ok(events) => Ok(events),
+                Err(err) => Err(Box::new(DatabaseQueryError::<GameEvent>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ).with_query(query, &bindings))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<GameEvent>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+}
+    }
+}
@@ -4,18 +4,29 @@ cfg_if! {
 
 
 use crate::backend::{
-    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError},
+    enums::{card_types::CardType, game_phase::GamePhase, game_state::GameState, player_color::PlayerColor},
+    errors::{application_error::ApplicationError, conflict_error::ConflictError, database_query_error::DatabaseQueryError, not_found_error::NotFoundError, validation_error::ValidationError},
+    logic::game_logic::next_host,
     repositories::{card_repository::CardRepository, chat::{chat_message_repository::ChatMessageRepository, chat_repository::ChatRepository}, claim_repository::ClaimsRepository, player_repository::PlayerRepository},
     types::{
+        change_set::ChangeSet,
         chat::{Chat, ChatMessage},
         claim::Claim,
-        game::{Game, UpdateGameDTO},
+        game::{Game, GameSummary, UpdateGameDTO},
+        game_operation::GameOperation,
+        game_settings::GameSettings,
         player::Player,
     },
+    utils::{
+        database_service::{run_labeled_batch, LabeledStatement},
+        id_generator::generate_id,
+    },
 };
 use axum::{http::StatusCode, Json};
+use futures::join;
+use serde::Deserialize;
 use wasm_bindgen::JsValue;
-use worker::D1Database;
+use crate::backend::utils::query_budget::CountedDatabase;
 
 /// Represents a repository for managing game data in the D1 database.
 ///
@@ -24,10 +35,10 @@ use worker::D1Database;
 ///
 /// # Properties
 ///
-/// `db`: An instance of `D1Database` that provides access to the D1 database.
+/// `db`: A `CountedDatabase` that provides access to the D1 database.
 pub struct GameRepository {
     /// The D1 database instance used for accessing game data.
-    db: D1Database,
+    db: CountedDatabase,
 }
 
 impl GameRepository {
@@ -40,8 +51,8 @@ impl GameRepository {
     /// # Returns
     ///
     /// A new `GameRepository` instance.
-    pub fn new(db: D1Database) -> Self {
-        GameRepository { db }
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        GameRepository { db: db.into() }
     }
 
     // pub fn db(&self) -> &D1Database {
@@ -61,8 +72,8 @@ impl GameRepository {
         let added_game = match self
             .db
             .prepare(
-                "INSERT INTO games (id, started_at, round_number, state, which_players_turn, card_to_play)
-                    VALUES (1?, 2?, 3?, 4?, 5?, 6?) RETURNING *;",
+                "INSERT INTO games (id, started_at, round_number, state, which_player_turn, card_to_play, title, settings, deck_seed, join_code_hash)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) RETURNING *;",
             )
             .bind(&[
                 JsValue::from(game.id.clone()),
@@ -71,6 +82,16 @@ impl GameRepository {
                 JsValue::from(game.state.index()),
                 JsValue::from(game.which_player_turn.clone()),
                 JsValue::from(game.card_to_play.index()),
+                JsValue::from(game.title.clone()),
+                JsValue::from(game.settings.to_column()),
+                match game.deck_seed {
+                    Some(deck_seed) => JsValue::from(deck_seed),
+                    None => JsValue::NULL,
+                },
+                match &game.join_code_hash {
+                    Some(join_code_hash) => JsValue::from(join_code_hash),
+                    None => JsValue::NULL,
+                },
             ])
             {
                 Ok(inserted_data) => inserted_data.first::<Game>(None).await,
@@ -81,167 +102,1168 @@ impl GameRepository {
                 )))
             };
 
-        match added_game {
-            Ok(game_data) => match game_data {
-                Some(created_game) => Ok(created_game),
-                None => Err(Box::new(DatabaseQueryError::<Game>::new(
-                    "Failed to add game to the database".to_string(),
-                    Some(Json(game.clone())),
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                ))),
-            },
-            Err(err) => Err(Box::new(DatabaseQueryError::new(
+        match added_game {
+            Ok(game_data) => match game_data {
+                Some(created_game) => Ok(created_game),
+                None => Err(Box::new(DatabaseQueryError::<Game>::new(
+                    "Failed to add game to the database".to_string(),
+                    Some(Json(game.clone())),
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::new(
+                err.to_string(),
+                Some(Json(game.clone())),
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Updates an existing game in the D1 database.
+    ///
+    /// # Arguments
+    ///
+    /// - `game` - A reference to the `Game` instance containing updated information.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated `Game` and a `ChangeSet` with the fields that actually
+    /// changed. Invalid state or phase transitions are rejected with a `ConflictError` and an
+    /// update without any field with a `ValidationError`.
+    pub async fn update_game(
+        &self,
+        game_data: UpdateGameDTO,
+        player_repo: &PlayerRepository,
+        claims_repo: &ClaimsRepository,
+        card_repo: &CardRepository,
+        chat_repo: &ChatRepository,
+        chat_message_repo: &ChatMessageRepository
+    ) -> Result<(Game, ChangeSet), Box<dyn ApplicationError>> {
+        if game_data.is_empty() {
+            return Err(Box::new(ValidationError::new(format!(
+                "The update of the game ['{}'] doesn't contain any field to change!",
+                game_data.id
+            ))));
+        }
+
+        // compare with the stored game before it gets overwritten
+        let current_game = self.get_game_by_id(&game_data.id, chat_repo, player_repo, claims_repo, chat_message_repo, card_repo).await?;
+        let changes = game_data.changes_against(&current_game);
+        if let Err(message) = game_data.validate_transitions(&current_game) {
+            return Err(Box::new(ConflictError::new(message)));
+        }
+        if let Err(message) = game_data.validate_round_number(&current_game) {
+            return Err(Box::new(ValidationError::new(message)));
+        }
+        if let Err(message) = game_data.validate_turn(&current_game) {
+            return Err(Box::new(ValidationError::new(message)));
+        }
+
+        // only players, claims or the chat may be updated, then the columns of the game stay
+        let mut updated_game = match self.get_update_query_string_and_bindings(&game_data) {
+            Some((query, bindings)) => {
+                let query_result = match self
+                    .db
+                    .prepare(&query)
+                    .bind(&bindings)
+                    {
+                        Ok(modified_data) => modified_data.first::<Game>(None).await,
+                        Err(err) => return Err(Box::new(DatabaseQueryError::<UpdateGameDTO>::new(
+                            err.to_string(),
+                            Some(Json(game_data.clone())),
+                            StatusCode::INTERNAL_SERVER_ERROR
+                        ).with_query(&query, &bindings)))
+                    };
+
+                match query_result {
+                    Ok(Some(updated_game)) => updated_game,
+                    Ok(None) => return Err(Box::new(DatabaseQueryError::<UpdateGameDTO>::new(
+                        "Failed to update game in the database".to_string(),
+                        Some(Json(game_data.clone())),
+                        axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ))),
+                    Err(err) => return Err(Box::new(DatabaseQueryError::<UpdateGameDTO>::new(
+                        err.to_string(),
+                        Some(Json(game_data.clone())),
+                        axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ).with_query(&query, &bindings))),
+                }
+            }
+            None => Game::from_ref(&current_game),
+        };
+
+        // players, claims and chat are only modified when they were provided
+        updated_game.players = match &game_data.players {
+            Some(_) => match self.update_players_in_game(&game_data, player_repo, card_repo).await {
+                Ok(players) => players,
+                Err(err) => return Err(err)
+            },
+            None => current_game.players,
+        };
+
+        updated_game.claims = match &game_data.claims {
+            Some(_) => match self.update_claims_of_game(&game_data, claims_repo, card_repo).await {
+                Ok(claims) => claims,
+                Err(err) => return Err(err)
+            },
+            None => current_game.claims,
+        };
+
+        updated_game.chat = match &game_data.chat {
+            Some(_) => match self.update_chat_of_game(&game_data, chat_repo, chat_message_repo).await {
+                Ok(chat) => chat,
+                Err(err) => return Err(err)
+            },
+            None => current_game.chat,
+        };
+
+        Ok((updated_game, changes))
+    }
+
+    /// Retrieves a game by its ID from the D1 database.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - A string slice representing the ID of the game to be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an `Game` struct object if the game is found, or a `DatabaseQueryError` if
+    /// an error occurs.
+    pub async fn get_game_by_id(
+        &self,
+        game_id: &str,
+        chat_repo: &ChatRepository,
+        player_repo: &PlayerRepository,
+        claim_repo: &ClaimsRepository,
+        chat_message_repo: &ChatMessageRepository,
+        card_repo: &CardRepository
+    ) -> Result<Game, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT * FROM games WHERE id = ?;")
+            .bind(&[JsValue::from(game_id)])
+            {
+                Ok(fetched_data) => fetched_data.first::<Game>(None).await,
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                )))
+            };
+
+        match query_result {
+            Ok(game) => match game {
+                Some(mut game) => {
+                    // the children don't depend on each other, so their queries run concurrently;
+                    // the worker is single-threaded, but the database round trips overlap
+                    let (chat, players, claims, card_counts) = join!(
+                        chat_repo.get_chat(None, Some(game_id), chat_message_repo),
+                        player_repo.get_all_players(Some(game_id), card_repo),
+                        claim_repo.get_all_claims(Some(game_id), None, card_repo),
+                        card_repo.get_card_counts(game_id),
+                    );
+
+                    // errors are reported in the same order as if the queries ran one by one
+                    game.chat = chat?;
+                    game.players = players?;
+                    game.claims = claims?;
+                    game.card_counts = card_counts?;
+                    Ok(game)
+                },
+                None => Err(Box::new(DatabaseQueryError::<Game>::new(
+                    "Game not found".to_string(),
+                    None,
+                    axum::http::StatusCode::NOT_FOUND,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves the `GameSummary`s of several games in a single query, without loading their
+    /// players, claims and chat.
+    ///
+    /// Unknown IDs are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_ids` - Identifiers of the games to be summarized.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the summaries in the order of `game_ids`, or a `DatabaseQueryError`
+    /// on failure.
+    pub async fn get_summaries(&self, game_ids: &[String]) -> Result<Vec<GameSummary>, Box<dyn ApplicationError>> {
+        if game_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let placeholders = vec!["?"; game_ids.len()].join(", ");
+        let query = format!(
+            "SELECT id, title, state, (SELECT COUNT(*) FROM claims WHERE claims.game_id = games.id) AS number_of_claims
+                FROM games WHERE id IN ({});",
+            placeholders
+        );
+        let bindings: Vec<JsValue> = game_ids.iter().map(JsValue::from).collect();
+
+        let query_result = match self.db.prepare(&query).bind(&bindings) {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(&query, &bindings)))
+        };
+
+        let rows = match query_result {
+            Ok(fetched_games) => match fetched_games.results::<StoredGameSummary>() {
+                Ok(rows) => rows,
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &bindings))),
+        };
+
+        let mut summaries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let state = match GameState::from_usize(row.state) {
+                Some(state) => state,
+                None => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    format!("The game with the id ['{}'] has an unknown state index {}!", row.id, row.state),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            };
+            summaries.push(GameSummary {
+                game_id: row.id,
+                title: row.title,
+                state,
+                number_of_claims: row.number_of_claims,
+            });
+        }
+        summaries.sort_by_key(|summary| game_ids.iter().position(|id| *id == summary.game_id));
+
+        Ok(summaries)
+    }
+
+    /// Retrieves only the `state` column of a game without loading its players, claims and chat.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - A string slice representing the ID of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `GameState` of the game, or a `DatabaseQueryError` when the game
+    /// couldn't be found or the stored state is unknown.
+    pub async fn get_game_state(&self, game_id: &str) -> Result<GameState, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT state FROM games WHERE id = ?;")
+            .bind(&[JsValue::from(game_id)])
+            {
+                Ok(fetched_data) => fetched_data.first::<usize>(Some("state")).await,
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                )))
+            };
+
+        match query_result {
+            Ok(Some(state_index)) => match GameState::from_usize(state_index) {
+                Some(state) => Ok(state),
+                None => Err(Box::new(DatabaseQueryError::<Game>::new(
+                    format!("The game with the id ['{}'] has an unknown state index {}!", game_id, state_index),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Ok(None) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                "Game not found".to_string(),
+                None,
+                StatusCode::NOT_FOUND,
+            ))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves all public games from the D1 database, e.g. for the lobby.
+    ///
+    /// Private games, which can only be joined with a join code, and archived games are left out.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a vector of `Game` instances, which is empty when there are no public
+    /// games, or a `DatabaseQueryError` if an error occurs.
+    pub async fn get_all_games(&self, player_repo: &PlayerRepository, card_repo: &CardRepository, claims_repo: &ClaimsRepository, chat_repo: &ChatRepository, chat_message_repo: &ChatMessageRepository) -> Result<Vec<Game>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT * FROM games WHERE join_code_hash IS NULL AND archived_at IS NULL;")
+            .bind(&[])
+        {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(collected_games) => {
+                let mut output: Vec<Game> = match collected_games.results::<Game>() {
+                    Ok(games) => games,
+                    Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                        err.to_string(),
+                        None,
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    )))
+                };
+
+                // Retrieve all other necessary game data (players, claims, chat) here
+                for game in &mut output {
+                    // players
+                    let players = match player_repo.get_all_players(Some(&game.id), card_repo).await {
+                        Ok(players) => players,
+                        Err(err) => return Err(err)
+                    };
+                    // Assign players to the game
+                    game.players = players;
+
+                    // claims
+                    let claims = match claims_repo.get_all_claims(Some(&game.id), None, card_repo).await {
+                        Ok(claims) => claims,
+                        Err(err) => return Err(err)
+                    };
+
+                    // Assign claims to the game
+                    game.claims = claims;
+
+                    // Retrieve chat for the game
+                    let chat = match chat_repo.get_chat(None, Some(&game.id), chat_message_repo).await {
+                        Ok(chat) => chat,
+                        Err(err) => return Err(err)
+                    };
+                    // Assign chat to the game
+                    game.chat = chat;
+                }
+                Ok(output)
+            }
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Deletes a game by its ID from the D1 database.
+    ///
+    /// Deleting a game that doesn't exist isn't an error, the caller decides how to answer.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - A string slice representing the ID of the game to be deleted.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether a game was actually deleted.
+    pub async fn delete_game(&self, game_id: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("DELETE FROM games WHERE id = ? RETURNING id;")
+            .bind(&[JsValue::from(game_id)])
+        {
+            Ok(temporary_data) => temporary_data.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(deleted_id) => Ok(deleted_id.is_some()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves the ids of all games that are still waiting for players and were created before
+    /// the passed point in time.
+    ///
+    /// # Arguments
+    ///
+    /// * `created_before` - Timestamp in the same format as the `started_at` column.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the ids of the abandoned games or a `DatabaseQueryError`.
+    pub async fn get_abandoned_game_ids(&self, created_before: &str) -> Result<Vec<String>, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT id FROM games WHERE state = ? AND started_at < ?;")
+            .bind(&[
+                JsValue::from(GameState::WaitingForPlayers.index()),
+                JsValue::from(created_before),
+            ])
+        {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(fetched_ids) => match fetched_ids.results::<GameId>() {
+                Ok(ids) => Ok(ids.into_iter().map(|game_id| game_id.id).collect()),
+                Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves all running games whose turn deadline has passed together with the player whose
+    /// turn it currently is.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current timestamp in the same format as the `turn_deadline` column.
+    /// * `game_id` - Optional id to only check a single game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing pairs of game id and current player id or a `DatabaseQueryError`.
+    pub async fn get_expired_turns(&self, now: &str, game_id: Option<&str>) -> Result<Vec<(String, String)>, Box<dyn ApplicationError>> {
+        self.get_expired_deadlines("turn_deadline", now, game_id).await
+    }
+
+    /// Retrieves all running games whose challenge window has closed without a challenge
+    /// together with the player whose turn it currently is.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current timestamp in the same format as the `challenge_deadline` column.
+    /// * `game_id` - Optional id to only check a single game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing pairs of game id and current player id or a `DatabaseQueryError`.
+    pub async fn get_expired_challenge_windows(&self, now: &str, game_id: Option<&str>) -> Result<Vec<(String, String)>, Box<dyn ApplicationError>> {
+        self.get_expired_deadlines("challenge_deadline", now, game_id).await
+    }
+
+    /// Selects the running games whose deadline in the given column has passed.
+    ///
+    /// The column name is never provided by a client, only `turn_deadline` or
+    /// `challenge_deadline` are passed to it.
+    async fn get_expired_deadlines(&self, deadline_column: &str, now: &str, game_id: Option<&str>) -> Result<Vec<(String, String)>, Box<dyn ApplicationError>> {
+        let mut query = format!("SELECT id, which_player_turn FROM games WHERE state = ? AND {0} IS NOT NULL AND {0} < ?", deadline_column);
+        let mut bindings = vec![
+            JsValue::from(GameState::InProgress.index()),
+            JsValue::from(now),
+        ];
+
+        if let Some(game_id) = game_id {
+            query.push_str(" AND id = ?");
+            bindings.push(JsValue::from(game_id));
+        }
+        query.push(';');
+
+        let query_result = match self.db.prepare(&query).bind(&bindings) {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(&query, &bindings)))
+        };
+
+        match query_result {
+            Ok(fetched_turns) => match fetched_turns.results::<GameTurn>() {
+                Ok(turns) => Ok(turns
+                    .into_iter()
+                    .map(|turn| (turn.id, turn.which_player_turn))
+                    .collect()),
+                Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ).with_query(&query, &bindings))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &bindings))),
+        }
+    }
+
+    /// Ends a running game, e.g. when only one player is left with cards.
+    ///
+    /// The deadlines are removed, so the turn isn't passed on anymore, and the last round moves
+    /// on to `Scoring`.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the game was running and is ended now.
+    pub async fn end_game(&self, game_id: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query = "UPDATE games SET state = ?, phase = ?, turn_deadline = NULL, challenge_deadline = NULL, current_claim_id = NULL WHERE id = ? AND state = ? RETURNING id;";
+        let bindings = [
+            JsValue::from(GameState::Ended.index()),
+            JsValue::from(GamePhase::Scoring.as_str()),
+            JsValue::from(game_id),
+            JsValue::from(GameState::InProgress.index()),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(ended_id) => Ok(ended_id.is_some()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Takes the turn of a player by opening the challenge window of their claim.
+    ///
+    /// The update only succeeds while it's the turn of the player and no challenge window is
+    /// open, all in a single statement. So when two requests of the same turn arrive at the same
+    /// time, only one of them wins the race.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `player_id` - Identifier of the player who wants to make a move.
+    /// * `challenge_deadline` - End of the challenge window of the placed claim.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the player got the turn.
+    pub async fn take_turn(&self, game_id: &str, player_id: &str, challenge_deadline: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE games SET challenge_deadline = ? WHERE id = ? AND which_player_turn = ? AND challenge_deadline IS NULL RETURNING id;")
+            .bind(&[
+                JsValue::from(challenge_deadline),
+                JsValue::from(game_id),
+                JsValue::from(player_id),
+            ])
+        {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(updated_id) => Ok(updated_id.is_some()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Takes back the claim of the current turn while its challenge window is open.
+    ///
+    /// First the challenge window is closed and the card to play is reset to the announced rank
+    /// of the claim in a single statement, so a challenge arriving at the same time either wins
+    /// or finds the window closed. Then the cards of the claim return to the hand of its creator
+    /// and the claim is removed in one batch. The turn stays with the creator.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `claim` - The claim to undo.
+    /// * `now` - Current timestamp in the same format as the `challenge_deadline` column.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the claim was undone, `false` when it isn't the turn of the
+    /// creator anymore or the challenge window has closed.
+    pub async fn undo_claim(&self, game_id: &str, claim: &Claim, now: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query = "UPDATE games SET challenge_deadline = NULL, current_claim_id = NULL, card_to_play = ?
+            WHERE id = ? AND state = ? AND which_player_turn = ? AND challenge_deadline IS NOT NULL AND challenge_deadline >= ? RETURNING id;";
+        let bindings = vec![
+            JsValue::from(claim.announced_rank.index()),
+            JsValue::from(game_id),
+            JsValue::from(GameState::InProgress.index()),
+            JsValue::from(&claim.created_by),
+            JsValue::from(now),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(Some(_)) => {}
+            Ok(None) => return Ok(false),
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+
+        let queries = [
+            (
+                "return cards of claim",
+                "UPDATE cards SET player_id = ?, claim_id = NULL, location = 'Hand' WHERE claim_id = ?;",
+                vec![JsValue::from(&claim.created_by), JsValue::from(&claim.id)],
+            ),
+            (
+                "remove claim",
+                "DELETE FROM claims WHERE id = ? AND game_id = ?;",
+                vec![JsValue::from(&claim.id), JsValue::from(game_id)],
+            ),
+        ];
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        match run_labeled_batch::<Game>(&self.db, statements).await {
+            Ok(_) => Ok(true),
+            Err(err) => Err(Box::new(err)),
+        }
+    }
+
+    /// Passes the turn of a game on to another player and starts a new turn deadline.
+    ///
+    /// The challenge window of the previous claim is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `player_id` - Identifier of the player whose turn it is now.
+    /// * `turn_deadline` - Point in time until the player has to make a move.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation. Is a `ValidationError` when the
+    /// player isn't part of the game, then the turn stays unchanged.
+    pub async fn set_turn(&self, game_id: &str, player_id: &str, turn_deadline: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE games SET which_player_turn = ?, turn_deadline = ?, challenge_deadline = NULL, current_claim_id = NULL
+                WHERE id = ? AND EXISTS (SELECT 1 FROM players WHERE id = ? AND game_id = games.id) RETURNING id;")
+            .bind(&[
+                JsValue::from(player_id),
+                JsValue::from(turn_deadline),
+                JsValue::from(game_id),
+                JsValue::from(player_id),
+            ])
+        {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(Box::new(ValidationError::new(format!(
+                "The turn can't be passed to the player ['{}'], who isn't part of the game ['{}']!",
+                player_id, game_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Stores the claim that can be challenged now.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `claim_id` - Identifier of the claim that was just placed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation. Is a `ValidationError` when the
+    /// claim isn't part of the game, then the current claim stays unchanged.
+    pub async fn set_current_claim(&self, game_id: &str, claim_id: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query = "UPDATE games SET current_claim_id = ?
+            WHERE id = ? AND EXISTS (SELECT 1 FROM claims WHERE id = ? AND game_id = games.id) RETURNING id;";
+        let bindings = [
+            JsValue::from(claim_id),
+            JsValue::from(game_id),
+            JsValue::from(claim_id),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(Box::new(ValidationError::new(format!(
+                "The claim ['{}'] isn't part of the game ['{}'] and can't be its current claim!",
+                claim_id, game_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Opens or closes the window in which the last claim of a game can be challenged.
+    ///
+    /// Closing the window resolves the claim, so the current claim of the game is cleared as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `challenge_deadline` - Point in time until the claim can be challenged, `None` closes
+    ///   the window.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn set_challenge_deadline(&self, game_id: &str, challenge_deadline: Option<&str>) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE games SET challenge_deadline = ?1, current_claim_id = CASE WHEN ?1 IS NULL THEN NULL ELSE current_claim_id END WHERE id = ?2;")
+            .bind(&[
+                match challenge_deadline {
+                    Some(deadline) => JsValue::from(deadline),
+                    None => JsValue::NULL,
+                },
+                JsValue::from(game_id),
+            ])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Changes the title of a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `title` - The new, already validated title.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn set_title(&self, game_id: &str, title: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE games SET title = ? WHERE id = ?;")
+            .bind(&[JsValue::from(title), JsValue::from(game_id)])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Starts the next round of a game by incrementing its round number.
+    ///
+    /// Unlike `update_game` the round number isn't provided by the client, so it can't skip
+    /// rounds or go backwards. The new round begins with `Dealing`, whatever phase the last
+    /// round was left in, and the state of the last round is cleared: nobody has the turn, no
+    /// claim can be challenged and the `King` has to be played first.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new round number, a `NotFoundError` when the game doesn't exist
+    /// or a `DatabaseQueryError` on failure.
+    pub async fn start_next_round(&self, game_id: &str) -> Result<usize, Box<dyn ApplicationError>> {
+        let query = "UPDATE games SET round_number = round_number + 1, phase = ?, card_to_play = ?, which_player_turn = '',
+            turn_deadline = NULL, challenge_deadline = NULL, current_claim_id = NULL WHERE id = ? RETURNING round_number;";
+        let bindings = [
+            JsValue::from(GamePhase::Dealing.as_str()),
+            JsValue::from(CardType::King.index()),
+            JsValue::from(game_id),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<usize>(Some("round_number")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(Some(round_number)) => Ok(round_number),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!(
+                "The game with the id ['{}'] couldn't be found, therefore the next round couldn't be started!",
+                game_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Moves the current round of a game from one phase to the next one.
+    ///
+    /// The phase is only changed when the round is still in the `current` phase, so of several
+    /// requests that try to move the round on only one succeeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `current` - Phase the round is expected to be in.
+    /// * `next` - Phase the round moves on to, see `GamePhase::can_transition_to`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation. Is a `ConflictError` when the
+    /// transition isn't allowed or the round isn't in the `current` phase anymore.
+    pub async fn set_phase(&self, game_id: &str, current: &GamePhase, next: &GamePhase) -> Result<(), Box<dyn ApplicationError>> {
+        if !current.can_transition_to(next) {
+            return Err(Box::new(ConflictError::new(format!(
+                "A round can't move on from the phase {} to {}!",
+                current, next
+            ))));
+        }
+
+        let query = "UPDATE games SET phase = ? WHERE id = ? AND phase = ? RETURNING id;";
+        let bindings = [
+            JsValue::from(next.as_str()),
+            JsValue::from(game_id),
+            JsValue::from(current.as_str()),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(Box::new(ConflictError::new(format!(
+                "The current round of the game ['{}'] isn't in the phase {} anymore!",
+                game_id, current
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Retrieves only the `round_number` column of a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of the current round, a `NotFoundError` when the game
+    /// doesn't exist or a `DatabaseQueryError` on failure.
+    pub async fn get_round_number(&self, game_id: &str) -> Result<usize, Box<dyn ApplicationError>> {
+        let query = "SELECT round_number FROM games WHERE id = ?;";
+        let bindings = [JsValue::from(game_id)];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<usize>(Some("round_number")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(Some(round_number)) => Ok(round_number),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!(
+                "The game with the id ['{}'] couldn't be found!",
+                game_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Retrieves only the `deck_seed` column of a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the seed of the deck or `None` when the deck is shuffled randomly,
+    /// a `NotFoundError` when the game doesn't exist or a `DatabaseQueryError` on failure.
+    pub async fn get_deck_seed(&self, game_id: &str) -> Result<Option<u32>, Box<dyn ApplicationError>> {
+        let query = "SELECT deck_seed FROM games WHERE id = ?;";
+        let bindings = [JsValue::from(game_id)];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<DeckSeed>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(Some(row)) => Ok(row.deck_seed),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!(
+                "The game with the id ['{}'] couldn't be found!",
+                game_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
-                Some(Json(game.clone())),
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            ))),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
         }
     }
 
-    /// Updates an existing game in the D1 database.
+    /// Retrieves only the `settings` column of a game.
     ///
     /// # Arguments
     ///
-    /// - `game` - A reference to the `Game` instance containing updated information.
+    /// * `game_id` - Identifier of the game.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure of the operation.
-    pub async fn update_game(
-        &self,
-        game_data: UpdateGameDTO,
-        player_repo: &PlayerRepository,
-        claims_repo: &ClaimsRepository,
-        card_repo: &CardRepository,
-        chat_repo: &ChatRepository,
-        chat_message_repo: &ChatMessageRepository
-    ) -> Result<Game, Box<dyn ApplicationError>> {
-        let (query, bindings) = self.get_update_query_string_and_bindings(&game_data);
+    /// A `Result` containing the settings, the standard rules for games without settings, a
+    /// `NotFoundError` when the game doesn't exist or a `DatabaseQueryError` on failure.
+    pub async fn get_settings(&self, game_id: &str) -> Result<GameSettings, Box<dyn ApplicationError>> {
+        let query = "SELECT settings FROM games WHERE id = ?;";
+        let bindings = [JsValue::from(game_id)];
 
-        let query_result = match self
-            .db
-            .prepare(&query)
-            .bind(&bindings)
-            {
-                Ok(modified_data) => modified_data.first::<Game>(None).await,
-                Err(err) => return Err(Box::new(DatabaseQueryError::<UpdateGameDTO>::new(
-                    err.to_string(),
-                    Some(Json(game_data.clone())),
-                    StatusCode::INTERNAL_SERVER_ERROR
-                )))
-            };
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<StoredGameSettings>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
 
         match query_result {
-            Ok(game) => match game {
-                Some(mut updated_game) => {
-                    updated_game.players = match self.update_players_in_game(&game_data, player_repo, card_repo).await {
-                        Ok(players) => players,
-                        Err(err) => return Err(err)
-                    };
+            Ok(Some(row)) => Ok(row.settings),
+            Ok(None) => Err(Box::new(NotFoundError::new(format!(
+                "The game with the id ['{}'] couldn't be found!",
+                game_id
+            )))),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
 
-                    updated_game.claims = match self.update_claims_of_game(&game_data, claims_repo, card_repo).await {
-                        Ok(claims) => claims,
-                        Err(err) => return Err(err)
-                    };
+    /// Starts a game that is still waiting for players, its first round begins with dealing.
+    ///
+    /// The state is only changed when the game is still `WaitingForPlayers`, so of several
+    /// requests that try to start the game at the same time only one succeeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the round number of the started game, or `None` when the game
+    /// doesn't exist or isn't waiting for players anymore.
+    pub async fn start_waiting_game(&self, game_id: &str) -> Result<Option<usize>, Box<dyn ApplicationError>> {
+        let query = "UPDATE games SET state = ?, phase = ? WHERE id = ? AND state = ? RETURNING round_number;";
+        let bindings = [
+            JsValue::from(GameState::InProgress.index()),
+            JsValue::from(GamePhase::Dealing.as_str()),
+            JsValue::from(game_id),
+            JsValue::from(GameState::WaitingForPlayers.index()),
+        ];
 
-                    updated_game.chat = match self.update_chat_of_game(&game_data, chat_repo, chat_message_repo).await {
-                        Ok(chat) => chat,
-                        Err(err) => return Err(err)
-                    };
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<usize>(Some("round_number")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
 
-                    return Ok(updated_game);
-                },
-                None => Err(Box::new(DatabaseQueryError::<UpdateGameDTO>::new(
-                    "Failed to update game in the database".to_string(),
-                    Some(Json(game_data.clone())),
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                ))),
-            },
-            Err(err) => Err(Box::new(DatabaseQueryError::<UpdateGameDTO>::new(
+        match query_result {
+            Ok(round_number) => Ok(round_number),
+            Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
-                Some(Json(game_data.clone())),
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            ))),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
         }
     }
 
-    /// Retrieves a game by its ID from the D1 database.
+    /// Retrieves the hash of the join code of a game.
     ///
     /// # Arguments
     ///
-    /// * `game_id` - A string slice representing the ID of the game to be retrieved.
+    /// * `game_id` - Identifier of the game.
     ///
     /// # Returns
     ///
-    /// A `Result` containing an `Game` struct object if the game is found, or a `DatabaseQueryError` if
-    /// an error occurs.
-    pub async fn get_game_by_id(
-        &self,
-        game_id: &str,
-        chat_repo: &ChatRepository,
-        player_repo: &PlayerRepository,
-        claim_repo: &ClaimsRepository,
-        chat_message_repo: &ChatMessageRepository,
-        card_repo: &CardRepository
-    ) -> Result<Game, Box<dyn ApplicationError>> {
-        let query_result = match self
-            .db
-            .prepare("SELECT * FROM games WHERE id = ?;")
-            .bind(&[JsValue::from(game_id)])
-            {
-                Ok(fetched_data) => fetched_data.first::<Game>(None).await,
-                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
-                    err.to_string(),
-                    None,
-                    StatusCode::INTERNAL_SERVER_ERROR
-                )))
-            };
+    /// A `Result` containing the hash, or `None` when the game is public or doesn't exist.
+    pub async fn get_join_code_hash(&self, game_id: &str) -> Result<Option<String>, Box<dyn ApplicationError>> {
+        let query = "SELECT join_code_hash FROM games WHERE id = ?;";
+        let bindings = [JsValue::from(game_id)];
 
-        match query_result {
-            Ok(game) => match game {
-                Some(mut game) => {
-                    game.chat = match chat_repo.get_chat(None, Some(&game.id), chat_message_repo).await {
-                        Ok(chat) => chat,
-                        Err(err) => return Err(err)
-                    };
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<Option<String>>(Some("join_code_hash")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
 
-                    game.players = match player_repo.get_all_players(Some(&game.id), card_repo).await {
-                        Ok(players) => players,
-                        Err(err) => return Err(err)
-                    };
-                    game.claims = match claim_repo.get_all_claims(Some(&game.id), None, card_repo).await {
-                        Ok(claims) => claims,
-                        Err(err) => return Err(err)
-                    };
-                    Ok(game)
-                },
-                None => Err(Box::new(DatabaseQueryError::<Game>::new(
-                    "Game not found".to_string(),
-                    None,
-                    axum::http::StatusCode::NOT_FOUND,
-                ))),
-            },
+        match query_result {
+            Ok(join_code_hash) => Ok(join_code_hash.flatten()),
             Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
                 None,
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            ))),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
         }
     }
 
-    /// Retrieves all games from the D1 database.
+    /// Retrieves the id of the host of a game.
+    ///
+    /// The host is stored with the game. As long as none is stored or the stored host isn't part
+    /// of the game anymore, the player who joined it first is the host.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
     ///
     /// # Returns
     ///
-    /// A `Result` containing a vector of `Game` instances if successful, or a `DatabaseQueryError`
-    /// if an error occurs.
-    pub async fn get_all_games(&self, player_repo: &PlayerRepository, card_repo: &CardRepository, claims_repo: &ClaimsRepository, chat_repo: &ChatRepository, chat_message_repo: &ChatMessageRepository) -> Result<Vec<Game>, Box<dyn ApplicationError>> {
+    /// A `Result` containing the id of the host or `None` when nobody joined the game yet.
+    pub async fn get_host_id(&self, game_id: &str) -> Result<Option<String>, Box<dyn ApplicationError>> {
         let query_result = match self
             .db
-            .prepare("SELECT * FROM games;")
-            .bind(&[])
+            .prepare("SELECT COALESCE(
+                    (SELECT players.id FROM games JOIN players ON players.id = games.host_id AND players.game_id = games.id WHERE games.id = ?),
+                    (SELECT id FROM players WHERE game_id = ? ORDER BY joined_at ASC LIMIT 1)
+                ) AS id;")
+            .bind(&[JsValue::from(game_id), JsValue::from(game_id)])
         {
-            Ok(fetched_data) => fetched_data.all().await,
+            Ok(fetched_data) => fetched_data.first::<String>(Some("id")).await,
             Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
                 None,
@@ -250,54 +1272,7 @@ impl GameRepository {
         };
 
         match query_result {
-            Ok(collected_games) => {
-                let mut output: Vec<Game> = match collected_games.results::<Game>() {
-                    Ok(games) => games,
-                    Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
-                        err.to_string(),
-                        None,
-                        StatusCode::INTERNAL_SERVER_ERROR
-                    )))
-                };
-
-                if output.is_empty() {
-                    Err(Box::new(DatabaseQueryError::<Game>::new(
-                        "No games found".to_string(),
-                        None,
-                        StatusCode::NOT_FOUND,
-                    )))
-                } else {
-
-                    // Retrieve all other necessary game data (players, claims, chat) here
-                    for game in &mut output {
-                        // players
-                        let players = match player_repo.get_all_players(Some(&game.id), card_repo).await {
-                            Ok(players) => players,
-                            Err(err) => return Err(err)
-                        };
-                        // Assign players to the game
-                        game.players = players;
-
-                        // claims
-                        let claims = match claims_repo.get_all_claims(Some(&game.id), None, card_repo).await {
-                            Ok(claims) => claims,
-                            Err(err) => return Err(err)
-                        };
-
-                        // Assign claims to the game
-                        game.claims = claims;
-
-                        // Retrieve chat for the game
-                        let chat = match chat_repo.get_chat(None, Some(&game.id), chat_message_repo).await {
-                            Ok(chat) => chat,
-                            Err(err) => return Err(err)
-                        };
-                        // Assign chat to the game
-                        game.chat = chat;
-                    }
-                    Ok(output)
-                }
-            }
+            Ok(host_id) => Ok(host_id),
             Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
                 None,
@@ -306,36 +1281,267 @@ impl GameRepository {
         }
     }
 
-    /// Deletes a game by its ID from the D1 database.
+    /// Makes a player of a game its new host.
     ///
     /// # Arguments
     ///
-    /// * `game_id` - A string slice representing the ID of the game to be deleted.
+    /// * `game_id` - Identifier of the game.
+    /// * `player_id` - Identifier of the new host.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure of the operation.
-    pub async fn delete_game(&self, game_id: &str) -> Result<(), Box<dyn ApplicationError>> {
-        let query_result = match self
-            .db
-            .prepare("DELETE FROM games WHERE id = ?;")
-            .bind(&[JsValue::from(game_id)])
-        {
-            Ok(temporary_data) => temporary_data.run().await,
+    /// A `Result` containing whether the host was changed, `false` when the game doesn't exist
+    /// or the player isn't part of it.
+    pub async fn set_host(&self, game_id: &str, player_id: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query = "UPDATE games SET host_id = ? WHERE id = ? AND EXISTS (SELECT 1 FROM players WHERE id = ? AND game_id = ?) RETURNING id;";
+        let bindings = [
+            JsValue::from(player_id),
+            JsValue::from(game_id),
+            JsValue::from(player_id),
+            JsValue::from(game_id),
+        ];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
             Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
                 None,
                 StatusCode::INTERNAL_SERVER_ERROR
-            )))
+            ).with_query(query, &bindings)))
         };
 
         match query_result {
-            Ok(_) => Ok(()),
+            Ok(updated_id) => Ok(updated_id.is_some()),
             Err(err) => Err(Box::new(DatabaseQueryError::<Game>::new(
                 err.to_string(),
                 None,
                 StatusCode::INTERNAL_SERVER_ERROR,
-            ))),
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Deletes a game together with everything that belongs to it: the chat and its messages,
+    /// the cards, the claims, the score events, the event log and the players.
+    ///
+    /// All statements are executed in one batch, so either everything or nothing is removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - A string slice representing the ID of the game to be deleted.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn delete_game_cascading(&self, game_id: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let queries = [
+            ("delete chat messages", "DELETE FROM chat_messages WHERE chat_id IN (SELECT id FROM chats WHERE game_id = ?);"),
+            ("delete chat", "DELETE FROM chats WHERE game_id = ?;"),
+            ("delete cards", "DELETE FROM cards WHERE game_id = ?;"),
+            ("delete claims", "DELETE FROM claims WHERE game_id = ?;"),
+            ("delete score events", "DELETE FROM score_events WHERE game_id = ?;"),
+            ("delete game events", "DELETE FROM game_events WHERE game_id = ?;"),
+            ("delete players", "DELETE FROM players WHERE game_id = ?;"),
+            ("delete game", "DELETE FROM games WHERE id = ?;"),
+        ];
+
+        let mut statements = vec![];
+        for (label, query) in queries {
+            match self.db.prepare(query).bind(&[JsValue::from(game_id)]) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                )))
+            }
+        }
+
+        match run_labeled_batch::<Game>(&self.db, statements).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(err)),
+        }
+    }
+
+    /// Applies explicit operations to a game in one batch, so either all or none of them are
+    /// stored.
+    ///
+    /// The operations have to be validated with `validate_operations` before, here they are only
+    /// translated into statements in their order. When the host is removed, the next player in
+    /// the seating order becomes the host. Like `set_turn`, passing the turn starts a new turn
+    /// deadline and closes the challenge window of the current claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game as it is currently stored, provides the ids of the game and its chat.
+    /// * `operations` - The validated operations in the order they are applied.
+    /// * `turn_deadline` - Deadline of a turn that is passed on with `SetTurn`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn apply_operations(
+        &self,
+        game: &Game,
+        operations: &[GameOperation],
+        turn_deadline: &str,
+    ) -> Result<(), Box<dyn ApplicationError>> {
+        let now = chrono::Utc::now().to_string();
+
+        // colors of the players after the operations so far, added players get a free one
+        let mut taken_colors: Vec<PlayerColor> = game.players.iter().map(|player| player.color.clone()).collect();
+        // players and host after the operations so far, a removed host is replaced by the next player
+        let mut remaining_players: Vec<Player> = game.players.clone();
+        let mut host_id = self.get_host_id(&game.id).await?;
+
+        let mut queries: Vec<(&str, &str, Vec<JsValue>)> = vec![];
+        for operation in operations {
+            match operation {
+                GameOperation::AddPlayer { name } => {
+                    let color = PlayerColor::assign(None, &taken_colors);
+                    taken_colors.push(color.clone());
+                    let player = Player::new(name.trim().to_string(), game.id.clone(), color);
+                    remaining_players.push(player.clone());
+                    queries.push((
+                        "add player",
+                        "INSERT INTO players (id, name, game_id, joined_at, color) VALUES (?, ?, ?, ?, ?);",
+                        vec![
+                            JsValue::from(player.id),
+                            JsValue::from(player.name),
+                            JsValue::from(player.game_id),
+                            JsValue::from(player.joined_at),
+                            JsValue::from(player.color.as_str()),
+                        ],
+                    ));
+                }
+                GameOperation::RemovePlayer { player_id } => {
+                    if let Some(removed) = game.players.iter().find(|player| &player.id == player_id) {
+                        if let Some(position) = taken_colors.iter().position(|color| *color == removed.color) {
+                            taken_colors.remove(position);
+                        }
+                    }
+                    queries.extend(PlayerRepository::removal_queries(player_id));
+                    if host_id.as_deref() == Some(player_id.as_str()) {
+                        host_id = next_host(&remaining_players, player_id).map(|player| player.id.clone());
+                        if let Some(next_host_id) = &host_id {
+                            queries.push((
+                                "transfer host",
+                                "UPDATE games SET host_id = ? WHERE id = ?;",
+                                vec![JsValue::from(next_host_id), JsValue::from(&game.id)],
+                            ));
+                        }
+                    }
+                    remaining_players.retain(|player| &player.id != player_id);
+                    queries.push((
+                        "remove player",
+                        "DELETE FROM players WHERE id = ? AND game_id = ?;",
+                        vec![JsValue::from(player_id), JsValue::from(&game.id)],
+                    ));
+                }
+                GameOperation::AddClaim { created_by, announced_rank, card_ids } => {
+                    let claim_id = generate_id();
+                    queries.push((
+                        "add claim",
+                        "INSERT INTO claims (id, created_by, announced_rank, number_of_cards, game_id) VALUES (?, ?, ?, ?, ?);",
+                        vec![
+                            JsValue::from(&claim_id),
+                            JsValue::from(created_by),
+                            JsValue::from(announced_rank.as_str()),
+                            JsValue::from(card_ids.len()),
+                            JsValue::from(&game.id),
+                        ],
+                    ));
+                    queries.push((
+                        "settle claims",
+                        "UPDATE cards SET location = 'Pile' WHERE game_id = ? AND location = 'Claim';",
+                        vec![JsValue::from(&game.id)],
+                    ));
+                    for card_id in card_ids {
+                        queries.push((
+                            "move card to claim",
+                            "UPDATE cards SET player_id = NULL, claim_id = ?, location = 'Claim'
+                                WHERE id = ? AND player_id = ? AND game_id = ? AND location = 'Hand';",
+                            vec![JsValue::from(&claim_id), JsValue::from(card_id), JsValue::from(created_by), JsValue::from(&game.id)],
+                        ));
+                    }
+                }
+                GameOperation::RemoveClaim { claim_id } => {
+                    queries.push((
+                        "return cards of claim",
+                        "UPDATE cards SET player_id = (SELECT created_by FROM claims WHERE id = ?), claim_id = NULL, location = 'Hand' WHERE claim_id = ?;",
+                        vec![JsValue::from(claim_id), JsValue::from(claim_id)],
+                    ));
+                    queries.push((
+                        "remove claim",
+                        "DELETE FROM claims WHERE id = ? AND game_id = ?;",
+                        vec![JsValue::from(claim_id), JsValue::from(&game.id)],
+                    ));
+                }
+                GameOperation::AddMessage { player_id, content } => {
+                    queries.push((
+                        "add message",
+                        "INSERT INTO chat_messages (id, player_id, content, sent_at, chat_id) VALUES (?, ?, ?, ?, ?);",
+                        vec![
+                            JsValue::from(generate_id()),
+                            JsValue::from(player_id),
+                            JsValue::from(content),
+                            JsValue::from(&now),
+                            JsValue::from(&game.chat.id),
+                        ],
+                    ));
+                    queries.push((
+                        "increment number of messages",
+                        "UPDATE chats SET number_of_messages = number_of_messages + 1 WHERE id = ?;",
+                        vec![JsValue::from(&game.chat.id)],
+                    ));
+                }
+                GameOperation::RemoveMessage { message_id } => {
+                    queries.push((
+                        "remove message",
+                        "DELETE FROM chat_messages WHERE id = ? AND chat_id = ?;",
+                        vec![JsValue::from(message_id), JsValue::from(&game.chat.id)],
+                    ));
+                    queries.push((
+                        "decrement number of messages",
+                        "UPDATE chats SET number_of_messages = number_of_messages - 1 WHERE id = ? AND number_of_messages > 0;",
+                        vec![JsValue::from(&game.chat.id)],
+                    ));
+                }
+                GameOperation::SetTurn { player_id } => {
+                    queries.push((
+                        "set turn",
+                        "UPDATE games SET which_player_turn = ?, turn_deadline = ?, challenge_deadline = NULL, current_claim_id = NULL WHERE id = ?;",
+                        vec![JsValue::from(player_id), JsValue::from(turn_deadline), JsValue::from(&game.id)],
+                    ));
+                }
+                GameOperation::SetState { state } => {
+                    queries.push((
+                        "set state",
+                        "UPDATE games SET state = ? WHERE id = ?;",
+                        vec![JsValue::from(state.index()), JsValue::from(&game.id)],
+                    ));
+                }
+            }
+        }
+
+        if queries.is_empty() {
+            return Ok(());
+        }
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Game>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        match run_labeled_batch::<Game>(&self.db, statements).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Box::new(err)),
         }
     }
 
@@ -348,10 +1554,14 @@ impl GameRepository {
     /// # Arguments
     ///
     /// - `game_data` -> DTO object which holds new data stored in the `games` table
+    ///
+    /// # Returns
+    ///
+    /// -> ***`None`*** when none of the columns of the `games` table is updated
     fn get_update_query_string_and_bindings(
         &self,
         game_data: &UpdateGameDTO,
-    ) -> (String, Vec<JsValue>) {
+    ) -> Option<(String, Vec<JsValue>)> {
         let mut output_query = "UPDATE games SET ".to_string();
         let mut output_bindings = vec![];
 
@@ -361,6 +1571,12 @@ impl GameRepository {
             output_bindings.push(JsValue::from(state.index()));
         }
 
+        // phase of the current round
+        if let Some(phase) = &game_data.phase {
+            output_query.push_str("phase = ?, ");
+            output_bindings.push(JsValue::from(phase.as_str()));
+        }
+
         // round number
         if let Some(round) = game_data.round_number {
             output_query.push_str("round_number = ?, ");
@@ -379,11 +1595,15 @@ impl GameRepository {
             output_bindings.push(JsValue::from(player));
         }
 
+        if output_bindings.is_empty() {
+            return None;
+        }
+
         output_query.truncate(output_query.len() - 2);
         output_query.push_str(" WHERE id = ? RETURNING *;");
         output_bindings.push(JsValue::from(game_data.id.clone()));
 
-        (output_query, output_bindings)
+        Some((output_query, output_bindings))
     }
 
     /// Fetches all curent players of the game stored in the database and then determines which
@@ -409,7 +1629,8 @@ impl GameRepository {
                 return Err(Box::new(DatabaseQueryError::<UpdateGameDTO> {
                     message: "Function was called with invalid data passed to it! A new list of players is mandatory!".to_string(),
                     received_data: None,
-                    status_code: StatusCode::INTERNAL_SERVER_ERROR
+                    status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                    query_context: None,
                 }));
             },
             Some(players) => {
@@ -417,7 +1638,8 @@ impl GameRepository {
                     return Err(Box::new(DatabaseQueryError::<UpdateGameDTO> {
                         message: "An empty list of players was provided! That's an invalid data input!".to_string(),
                         received_data: None,
-                        status_code: StatusCode::BAD_REQUEST
+                        status_code: StatusCode::BAD_REQUEST,
+                        query_context: None,
                     }));
                 }
                 players
@@ -466,6 +1688,10 @@ impl GameRepository {
 
     /// Updates the claims of a game based on the provided `UpdateGameDTO`.
     ///
+    /// An empty list of claims deletes all claims of the game, otherwise the claims are added to
+    /// the game. When the DTO has no claims (`None`) nothing should change, so the method must
+    /// not be called.
+    ///
     /// # Arguments
     ///
     /// - `game_data` -> DTO object containing the list of claims
@@ -474,7 +1700,7 @@ impl GameRepository {
     ///
     /// # Returns
     ///
-    /// - A vector of `Claim` if a new claim was added or when all claims were deleted.
+    /// - A vector of all `Claim`s of the game after the update.
     ///
     /// # Errors
     ///
@@ -482,28 +1708,34 @@ impl GameRepository {
     /// - Returns a `DatabaseQueryError` if there is an error while deleting or adding claims
     async fn update_claims_of_game(&self, game_data: &UpdateGameDTO, claims_repo: &ClaimsRepository, card_repo: &CardRepository) -> Result<Vec<Claim>, Box<dyn ApplicationError>> {
         // first check if the needed data was provided
-        if let None = &game_data.claims {
-            return Err(Box::new(DatabaseQueryError::<UpdateGameDTO> {
+        let new_claims = match &game_data.claims {
+            Some(new_claims) => new_claims,
+            None => return Err(Box::new(DatabaseQueryError::<UpdateGameDTO> {
                 message: "Function was called with invalid data passed to it! A new list of claims is mandatory!".to_string(),
                 received_data: None,
-                status_code: StatusCode::INTERNAL_SERVER_ERROR
-            }));
-        }
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                query_context: None,
+            })),
+        };
 
-        // when the array is empty, all claims in the list of the game will be deleted.
-        if game_data.claims.iter().len() == 0 {
-            // delete all claims of the game
-            match claims_repo.delete_all_claims_of_game(&game_data.id).await {
-                Ok(_) => {},
+        if new_claims.is_empty() {
+            // an explicitly empty list deletes all claims of the game
+            let claim_ids: Vec<String> = match claims_repo.get_all_claims(Some(&game_data.id), None, card_repo).await {
+                Ok(claims) => claims.into_iter().map(|claim| claim.id).collect(),
                 Err(err) => return Err(err)
             };
-        } else {
-            // when there is one element, it will be added to the claims list of a game.
-            // add the claim to the database
-            match claims_repo.create_claim(game_data.claims.clone().unwrap()[1].clone(), card_repo).await {
+            match claims_repo.delete_claims(&claim_ids).await {
                 Ok(_) => {},
                 Err(err) => return Err(err)
             };
+        } else {
+            // every provided claim is added to the claims list of the game
+            for claim in new_claims {
+                match claims_repo.create_claim(claim.clone(), &game_data.id, card_repo).await {
+                    Ok(_) => {},
+                    Err(err) => return Err(err)
+                };
+            }
         }
 
         Ok(match claims_repo.get_all_claims(Some(&game_data.id), None, card_repo).await {
@@ -532,7 +1764,8 @@ impl GameRepository {
             return Err(Box::new(DatabaseQueryError::<Chat> {
                 message: "Function was called with invalid data passed to it! A new chat object is mandatory!".to_string(),
                 received_data: None,
-                status_code: StatusCode::INTERNAL_SERVER_ERROR
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                query_context: None,
             }));
         } else {
             let chat = game_data.chat.as_ref().unwrap();
@@ -588,7 +1821,7 @@ impl GameRepository {
             }
 
             for message in new_messages {
-                match chat_message_repo.save_message(message).await {
+                match chat_message_repo.save_message(message, None).await {
                     Ok(_) => (),
                     Err(err) => return Err(err)
                 }
@@ -598,5 +1831,40 @@ impl GameRepository {
         }
     }
 }
+
+/// Row type for queries that only select the `id` column of the `games` table.
+#[derive(Deserialize)]
+struct GameId {
+    id: String,
+}
+
+/// Row type for queries that select a game together with the player whose turn it is.
+#[derive(Deserialize)]
+struct GameTurn {
+    id: String,
+    which_player_turn: String,
+}
+
+/// Row type for queries that select the columns of a `GameSummary`.
+#[derive(Deserialize)]
+struct StoredGameSummary {
+    id: String,
+    title: String,
+    state: usize,
+    number_of_claims: usize,
+}
+
+/// Row type for queries that only select the `deck_seed` column of the `games` table.
+#[derive(Deserialize)]
+struct DeckSeed {
+    deck_seed: Option<u32>,
+}
+
+/// Row type for queries that only select the `settings` column of the `games` table.
+#[derive(Deserialize)]
+struct StoredGameSettings {
+    #[serde(default, deserialize_with = "GameSettings::deserialize_column")]
+    settings: GameSettings,
+}
     }
 }
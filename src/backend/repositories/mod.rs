@@ -3,5 +3,8 @@
 pub mod card_repository;
 pub mod chat;
 pub mod claim_repository;
+pub mod game_event_repository;
 pub mod game_repository;
 pub mod player_repository;
+pub mod rate_limit_repository;
+pub mod score_event_repository;
@@ -4,13 +4,16 @@ cfg_if! {
 
 
 use axum::{Json, http::StatusCode};
+use serde::Deserialize;
 use wasm_bindgen::JsValue;
-use worker::D1Database;
+use crate::backend::utils::query_budget::CountedDatabase;
 
 use crate::backend::{
+    enums::game_state::GameState,
     errors::{database_query_error::DatabaseQueryError, application_error::ApplicationError},
     repositories::card_repository::CardRepository,
-    types::player::{Player, UpdatePlayerDTO},
+    types::{change_set::ChangeSet, player::{Player, UpdatePlayerDTO}},
+    utils::database_service::{run_labeled_batch, LabeledStatement},
 };
 
 /// Represents a repository for managing player data in the D1 database.
@@ -20,10 +23,12 @@ use crate::backend::{
 ///
 /// # Properties
 ///
-/// `db`: An instance of `D1Database` that provides access to the D1 database.
+/// `db`: A `CountedDatabase` that provides access to the D1 database.
 pub struct PlayerRepository {
     /// The D1 database instance used for accessing player data.
-    db: D1Database,
+    db: CountedDatabase,
+    /// Whether `get_all_players` leaves a hand empty when its cards can't be loaded.
+    degrade_failed_hands: bool,
 }
 
 // ----- Implementation of 'PlayerRepository' -----
@@ -38,8 +43,25 @@ impl PlayerRepository {
     /// # Returns
     ///
     /// A new `PlayerRepository` instance.
-    pub fn new(db: D1Database) -> Self {
-        PlayerRepository { db }
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        PlayerRepository {
+            db: db.into(),
+            degrade_failed_hands: false,
+        }
+    }
+
+    /// Lets `get_all_players` continue when the cards of a single player can't be loaded.
+    ///
+    /// The failure is logged and the player is returned with an empty hand, so one bad row
+    /// doesn't take down a whole game view. Only meant for read-only views, the game logic needs
+    /// the real hands.
+    ///
+    /// # Arguments
+    ///
+    /// - `degrade` -> Whether a failed hand is left empty instead of failing the query.
+    pub fn with_degraded_hands(mut self, degrade: bool) -> Self {
+        self.degrade_failed_hands = degrade;
+        self
     }
 
     /// Adds a new player to the D1 database.
@@ -61,14 +83,16 @@ impl PlayerRepository {
         let added_player = match self
             .db
             .prepare(
-                "INSERT INTO players (id, name, game_id, joined_at)
-                    VALUES (1?, 2?, 3?, 4?) RETURNING *;",
+                "INSERT INTO players (id, name, game_id, joined_at, color, ready_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING *;",
             )
             .bind(&[
                 JsValue::from(player.id.clone()),
                 JsValue::from(player.name.clone()),
                 JsValue::from(player.game_id.clone()),
                 JsValue::from(player.joined_at.clone()),
+                JsValue::from(player.color.as_str()),
+                player.ready_at.as_deref().map_or(JsValue::NULL, JsValue::from),
             ])
         {
             Ok(saved_data) => saved_data.first::<Player>(None).await,
@@ -96,6 +120,183 @@ impl PlayerRepository {
         }
     }
 
+    /// Adds a new player to the D1 database unless a player of the game already joined with the
+    /// same idempotency key.
+    ///
+    /// The check and the insertion happen in one statement, so two identical requests arriving
+    /// at the same time still create only one player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The `Player` instance to be added to the database.
+    /// * `join_key_hash` - Hash of the idempotency key the client sent with the join.
+    ///
+    /// # Returns
+    ///
+    /// The added `Player`, or the player who already joined with the key.
+    ///
+    /// # Errors
+    ///
+    /// If the database query fails, it returns a `DatabaseQueryError` containing the error
+    /// details.
+    pub async fn add_player_once(
+        &self,
+        player: Player,
+        join_key_hash: &str,
+    ) -> Result<Player, Box<dyn ApplicationError>> {
+        let query = "INSERT INTO players (id, name, game_id, joined_at, color, ready_at, join_key_hash)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    ON CONFLICT(game_id, join_key_hash) DO NOTHING RETURNING *;";
+        let params = [
+            JsValue::from(player.id.clone()),
+            JsValue::from(player.name.clone()),
+            JsValue::from(player.game_id.clone()),
+            JsValue::from(player.joined_at.clone()),
+            JsValue::from(player.color.as_str()),
+            player.ready_at.as_deref().map_or(JsValue::NULL, JsValue::from),
+            JsValue::from(join_key_hash),
+        ];
+
+        let added_player = match self.db.prepare(query).bind(&params) {
+            Ok(saved_data) => saved_data.first::<Player>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &params)))
+        };
+
+        match added_player {
+            Ok(Some(result_player)) => return Ok(result_player),
+            Ok(None) => {}
+            Err(e) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                Some(axum::Json(player)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &params))),
+        }
+
+        // the player already joined with the key
+        match self.get_player_by_join_key_hash(&player.game_id, join_key_hash).await? {
+            Some(result_player) => Ok(result_player),
+            None => Err(Box::new(DatabaseQueryError::<Player>::new(
+                "Failed to add player to the database".to_string(),
+                Some(axum::Json(player)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves the player who joined a game with an idempotency key.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `join_key_hash` - Hash of the idempotency key the client sent with the join.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Player` or `None` when nobody joined with the key, or a
+    /// `DatabaseQueryError` on failure.
+    pub async fn get_player_by_join_key_hash(&self, game_id: &str, join_key_hash: &str) -> Result<Option<Player>, Box<dyn ApplicationError>> {
+        let query = "SELECT * FROM players WHERE game_id = ? AND join_key_hash = ?;";
+        let params = [JsValue::from(game_id), JsValue::from(join_key_hash)];
+
+        let existing_player = match self.db.prepare(query).bind(&params) {
+            Ok(fetched_data) => fetched_data.first::<Player>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &params)))
+        };
+
+        match existing_player {
+            Ok(player) => Ok(player),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &params))),
+        }
+    }
+
+    /// Adds a new player to a game that is already in progress and deals them a hand from the
+    /// deck.
+    ///
+    /// The player and their cards are stored in one batch, so when dealing the cards fails the
+    /// player isn't added either.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The `Player` instance to be added to the database.
+    /// * `hand_size` - Number of random cards of the deck the player is dealt.
+    /// * `join_key_hash` - Hash of the idempotency key the client sent with the join, if any.
+    /// * `card_repo` - Repository to load the dealt cards.
+    ///
+    /// # Returns
+    ///
+    /// The added `Player` with the dealt cards.
+    ///
+    /// # Errors
+    ///
+    /// If a query of the batch fails, it returns a `ProcessError` naming the failed operation
+    /// and nothing is stored.
+    pub async fn add_player_with_hand(
+        &self,
+        player: Player,
+        hand_size: usize,
+        join_key_hash: Option<&str>,
+        card_repo: &CardRepository,
+    ) -> Result<Player, Box<dyn ApplicationError>> {
+        let queries = [
+            (
+                "add player",
+                "INSERT INTO players (id, name, game_id, joined_at, color, join_key_hash) VALUES (?, ?, ?, ?, ?, ?);",
+                vec![
+                    JsValue::from(player.id.clone()),
+                    JsValue::from(player.name.clone()),
+                    JsValue::from(player.game_id.clone()),
+                    JsValue::from(player.joined_at.clone()),
+                    JsValue::from(player.color.as_str()),
+                    join_key_hash.map_or(JsValue::NULL, JsValue::from),
+                ],
+            ),
+            (
+                "deal hand",
+                "UPDATE cards SET player_id = ?, location = 'Hand' WHERE id IN (
+                    SELECT id FROM cards WHERE game_id = ? AND location = 'Deck' ORDER BY RANDOM() LIMIT ?
+                );",
+                vec![
+                    JsValue::from(player.id.clone()),
+                    JsValue::from(player.game_id.clone()),
+                    JsValue::from(hand_size),
+                ],
+            ),
+        ];
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        if let Err(err) = run_labeled_batch::<Player>(&self.db, statements).await {
+            return Err(Box::new(err));
+        }
+
+        let mut added_player = self.get_player(&player.id).await?;
+        added_player.assigned_cards = card_repo.get_all_cards(None, Some(player.id)).await?;
+        added_player.number_of_cards = added_player.assigned_cards.len();
+        Ok(added_player)
+    }
+
     /// Updates an existing player in the D1 database.
     ///
     /// # Arguments
@@ -105,7 +306,8 @@ impl PlayerRepository {
     /// # Returns
     ///
     /// A `Result` indicating success or failure of the operation, containing the updated `Player`
-    /// instance on success.
+    /// instance and a `ChangeSet` with the fields that actually changed on success. When nothing
+    /// changes, the stored player is returned without running an update.
     ///
     /// # Errors
     ///
@@ -114,7 +316,14 @@ impl PlayerRepository {
     pub async fn update_player(
         &self,
         player_data: UpdatePlayerDTO,
-    ) -> Result<Player, Box<dyn ApplicationError>> {
+    ) -> Result<(Player, ChangeSet), Box<dyn ApplicationError>> {
+        // compare with the stored player before it gets overwritten
+        let current_player = self.get_player(&player_data.id).await?;
+        let changes = player_data.changes_against(&current_player);
+        if changes.is_empty() {
+            return Ok((current_player, changes));
+        }
+
         // Prepare the SQL statement to update the player
         // Note: The SQL statement uses positional parameters (1?, 2?, etc.) for binding values.
         // This is a common practice to prevent SQL injection attacks.
@@ -134,12 +343,12 @@ impl PlayerRepository {
                 err.to_string(),
                 None,
                 StatusCode::INTERNAL_SERVER_ERROR
-            )))
+            ).with_query(&query, &bindings)))
         };
 
         match updated_player {
             Ok(good_query_result) => match good_query_result {
-                Some(result_player) => Ok(result_player),
+                Some(result_player) => Ok((result_player, changes)),
                 None => Err(Box::new(DatabaseQueryError::<UpdatePlayerDTO>::new(
                     "Failed to update player in the database".to_string(),
                     Some(Json(player_data)),
@@ -150,7 +359,7 @@ impl PlayerRepository {
                 e.to_string(),
                 Some(Json(player_data)),
                 StatusCode::INTERNAL_SERVER_ERROR,
-            ))),
+            ).with_query(&query, &bindings))),
         }
     }
 
@@ -180,10 +389,6 @@ impl PlayerRepository {
             query.push_str("name = ?, ");
             bindings.push(JsValue::from(name));
         }
-        if let Some(score) = player.score {
-            query.push_str("score = ?, ");
-            bindings.push(JsValue::from(score));
-        }
 
         if let Some(last_time_update_requested) = &player.last_time_update_requested {
             query.push_str("last_time_update_requested = ?, ");
@@ -200,42 +405,110 @@ impl PlayerRepository {
 
     /// Deletes a player from the D1 database.
     ///
+    /// The rows referring to the player are removed in the same batch, see `removal_queries`, so
+    /// a player who already chatted or placed claims can be deleted as well. Deleting a player
+    /// that doesn't exist isn't an error, the caller decides how to answer.
+    ///
     /// # Arguments
     ///
     /// * `player_id` - A string slice representing the ID of the player to be deleted.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure of the operation.
+    /// A `Result` containing whether a player was actually deleted.
     ///
     /// # Errors
     ///
-    /// If the database query fails, it returns a `DatabaseQueryError` containing the error
-    /// details.
-    pub async fn delete_player(&self, player_id: &str) -> Result<(), Box<dyn ApplicationError>> {
-        let deleted_player = match self
-            .db
-            .prepare("DELETE FROM players WHERE id = ?;")
-            .bind(&[JsValue::from(player_id)])
-        {
-            Ok(removed_data) => removed_data.run().await,
-            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
-                err.to_string(),
-                None,
-                StatusCode::INTERNAL_SERVER_ERROR
-            )))
+    /// If a query of the batch fails, it returns a `ProcessError` naming the failed operation
+    /// and nothing is deleted.
+    pub async fn delete_player(&self, player_id: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let mut queries = Self::removal_queries(player_id);
+        queries.push(("remove player", "DELETE FROM players WHERE id = ? RETURNING id;", vec![JsValue::from(player_id)]));
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        let results = match run_labeled_batch::<Player>(&self.db, statements).await {
+            Ok(results) => results,
+            Err(err) => return Err(Box::new(err)),
         };
 
-        match deleted_player {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+        match results.last().map(|result| result.results::<serde_json::Value>()) {
+            Some(Ok(deleted)) => Ok(!deleted.is_empty()),
+            Some(Err(e)) => Err(Box::new(DatabaseQueryError::<Player>::new(
                 e.to_string(),
                 None,
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::INTERNAL_SERVER_ERROR,
             ))),
+            None => Ok(false),
         }
     }
 
+    /// Returns the queries that remove everything referring to a player, before the player
+    /// itself can be deleted.
+    ///
+    /// The cards in the hand of the player return to the deck. The claims of the player are
+    /// removed, their cards stay on the pile. Their challenges, chat messages and score events
+    /// are removed as well; reactions and read receipts are removed by the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player to be deleted.
+    ///
+    /// # Returns
+    ///
+    /// The label, the query and the bindings of every statement, in the order they have to run.
+    pub fn removal_queries(player_id: &str) -> Vec<(&'static str, &'static str, Vec<JsValue>)> {
+        vec![
+            (
+                "return cards of player",
+                "UPDATE cards SET player_id = NULL, location = 'Deck' WHERE player_id = ?;",
+                vec![JsValue::from(player_id)],
+            ),
+            (
+                "release cards of claims",
+                "UPDATE cards SET claim_id = NULL WHERE claim_id IN (SELECT id FROM claims WHERE created_by = ?);",
+                vec![JsValue::from(player_id)],
+            ),
+            (
+                "clear challenges",
+                "UPDATE claims SET challenged_by = NULL WHERE challenged_by = ?;",
+                vec![JsValue::from(player_id)],
+            ),
+            (
+                "remove claims",
+                "DELETE FROM claims WHERE created_by = ?;",
+                vec![JsValue::from(player_id)],
+            ),
+            (
+                "recount messages",
+                "UPDATE chats SET number_of_messages = number_of_messages
+                    - (SELECT COUNT(*) FROM chat_messages WHERE chat_messages.chat_id = chats.id AND player_id = ?)
+                    WHERE id IN (SELECT chat_id FROM chat_messages WHERE player_id = ?);",
+                vec![JsValue::from(player_id), JsValue::from(player_id)],
+            ),
+            (
+                "remove messages",
+                "DELETE FROM chat_messages WHERE player_id = ?;",
+                vec![JsValue::from(player_id)],
+            ),
+            (
+                "remove score events",
+                "DELETE FROM score_events WHERE player_id = ?;",
+                vec![JsValue::from(player_id)],
+            ),
+        ]
+    }
+
     /// Retrieves a player by their ID from the D1 database.
     ///
     /// # Arguments
@@ -278,18 +551,468 @@ impl PlayerRepository {
         }
     }
 
+    /// Retrieves several players by their IDs in a single query.
+    ///
+    /// The cards aren't loaded, only their number, like the players are shown to other players.
+    /// Unknown IDs are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_ids` - Identifiers of the players to be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the found players in the order of `player_ids`, or a
+    /// `DatabaseQueryError` on failure.
+    pub async fn get_players_by_ids(&self, player_ids: &[String]) -> Result<Vec<Player>, Box<dyn ApplicationError>> {
+        if player_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let placeholders = vec!["?"; player_ids.len()].join(", ");
+        let query = format!(
+            "SELECT players.*, (SELECT COUNT(*) FROM cards WHERE cards.player_id = players.id AND cards.location = 'Hand') AS number_of_cards
+                FROM players WHERE id IN ({});",
+            placeholders
+        );
+        let bindings: Vec<JsValue> = player_ids.iter().map(JsValue::from).collect();
+
+        let query_result = match self.db.prepare(&query).bind(&bindings) {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(&query, &bindings)))
+        };
+
+        let mut players = match query_result {
+            Ok(fetched_players) => match fetched_players.results::<Player>() {
+                Ok(players) => players,
+                Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(&query, &bindings))),
+        };
+        players.sort_by_key(|player| player_ids.iter().position(|id| *id == player.id));
+
+        Ok(players)
+    }
+
+    /// Checks whether a player is part of a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `game_id` - A string slice representing the ID of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` when the player is in the game, or a `DatabaseQueryError` on
+    /// failure.
+    pub async fn is_player_in_game(&self, player_id: &str, game_id: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("SELECT COUNT(*) AS number_of_players FROM players WHERE id = ? AND game_id = ?;")
+            .bind(&[JsValue::from(player_id), JsValue::from(game_id)])
+        {
+            Ok(fetched_data) => fetched_data.first::<usize>(Some("number_of_players")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(number_of_players) => Ok(number_of_players.unwrap_or(0) > 0),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Stores the hash of the session token of a player.
+    ///
+    /// The token itself is only known by the client of the player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `session_token_hash` - The hashed session token, see `hash_session_token`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn set_session_token_hash(&self, player_id: &str, session_token_hash: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE players SET session_token_hash = ? WHERE id = ?;")
+            .bind(&[JsValue::from(session_token_hash), JsValue::from(player_id)])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Stores the time of the last authenticated request of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `last_seen` - The current point in time.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn set_last_seen(&self, player_id: &str, last_seen: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query = "UPDATE players SET last_seen = ? WHERE id = ?;";
+        let bindings = [JsValue::from(last_seen), JsValue::from(player_id)];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Gives a player the next free finishing position of their game.
+    ///
+    /// The position is counted in the same statement, so two players finishing at the same time
+    /// still get different positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `game_id` - Identifier of the game the player has to be part of.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the position, or `None` when the player already finished or isn't
+    /// part of the game.
+    pub async fn finish_player(&self, player_id: &str, game_id: &str) -> Result<Option<usize>, Box<dyn ApplicationError>> {
+        let query = "UPDATE players SET finished_position = (
+                SELECT COUNT(*) FROM players WHERE game_id = ? AND finished_position IS NOT NULL
+            ) + 1
+            WHERE id = ? AND game_id = ? AND finished_position IS NULL RETURNING finished_position;";
+        let bindings = [JsValue::from(game_id), JsValue::from(player_id), JsValue::from(game_id)];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<usize>(Some("finished_position")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(position) => Ok(position),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Marks a player as out of the current round, so they are skipped in the turn rotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `game_id` - Identifier of the game the player has to be part of.
+    /// * `forfeited_at` - The current point in time.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the player was marked, `false` when they aren't part of the
+    /// game or already forfeited the round.
+    pub async fn forfeit_round(&self, player_id: &str, game_id: &str, forfeited_at: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE players SET forfeited_at = ? WHERE id = ? AND game_id = ? AND forfeited_at IS NULL RETURNING id;")
+            .bind(&[
+                JsValue::from(forfeited_at),
+                JsValue::from(player_id),
+                JsValue::from(game_id),
+            ])
+        {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(updated_id) => Ok(updated_id.is_some()),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Marks a player of a game as ready to start it.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `game_id` - Identifier of the game the player has to be part of.
+    /// * `ready_at` - The current point in time.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing whether the player was marked, `false` when they aren't part of the
+    /// game or already are ready.
+    pub async fn set_ready(&self, player_id: &str, game_id: &str, ready_at: &str) -> Result<bool, Box<dyn ApplicationError>> {
+        let query = "UPDATE players SET ready_at = ? WHERE id = ? AND game_id = ? AND ready_at IS NULL RETURNING id;";
+        let bindings = [JsValue::from(ready_at), JsValue::from(player_id), JsValue::from(game_id)];
+
+        let query_result = match self.db.prepare(query).bind(&bindings) {
+            Ok(prepared) => prepared.first::<String>(Some("id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            ).with_query(query, &bindings)))
+        };
+
+        match query_result {
+            Ok(updated_id) => Ok(updated_id.is_some()),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).with_query(query, &bindings))),
+        }
+    }
+
+    /// Lets all players of a game take part in the next round again.
+    ///
+    /// The forfeits and the finishing positions of the last round are cleared.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the operation.
+    pub async fn clear_round_results(&self, game_id: &str) -> Result<(), Box<dyn ApplicationError>> {
+        let query_result = match self
+            .db
+            .prepare("UPDATE players SET forfeited_at = NULL, finished_position = NULL WHERE game_id = ?;")
+            .bind(&[JsValue::from(game_id)])
+        {
+            Ok(prepared) => prepared.run().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Retrieves the player a session token was handed out to.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_token_hash` - The hashed session token sent by the client.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Player` or `None` when no player has the token, or a
+    /// `DatabaseQueryError` on failure.
+    pub async fn get_player_by_session_token_hash(&self, session_token_hash: &str) -> Result<Option<Player>, Box<dyn ApplicationError>> {
+        let player = match self
+            .db
+            .prepare("SELECT * FROM players WHERE session_token_hash = ?;")
+            .bind(&[JsValue::from(session_token_hash)])
+        {
+            Ok(fetched_data) => fetched_data.first::<Player>(None).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match player {
+            Ok(player) => Ok(player),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Looks up the game a player is currently taking part in.
+    ///
+    /// Games that `Ended` or were `Aborted` don't count, so the id of such a game is never
+    /// returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the ID of the game, `None` when the player doesn't exist or their
+    /// game is over, or a `DatabaseQueryError` on failure.
+    pub async fn find_active_game(&self, player_id: &str) -> Result<Option<String>, Box<dyn ApplicationError>> {
+        let game_id = match self
+            .db
+            .prepare(
+                "SELECT games.id AS game_id FROM players \
+                 JOIN games ON games.id = players.game_id \
+                 WHERE players.id = ? AND games.state NOT IN (?, ?);",
+            )
+            .bind(&[
+                JsValue::from(player_id),
+                JsValue::from(GameState::Ended.index()),
+                JsValue::from(GameState::Aborted.index()),
+            ])
+        {
+            Ok(fetched_data) => fetched_data.first::<String>(Some("game_id")).await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match game_id {
+            Ok(game_id) => Ok(game_id),
+            Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                e.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
+    /// Lists the games a player took part in, the most recently started game first.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_id` - A string slice representing the ID of the player.
+    /// * `state` - Optional state the games have to be in.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the IDs of the games, or a `DatabaseQueryError` on failure.
+    pub async fn get_games_for_player(&self, player_id: &str, state: Option<&GameState>) -> Result<Vec<String>, Box<dyn ApplicationError>> {
+        let prepared = match state {
+            Some(state) => self
+                .db
+                .prepare(
+                    "SELECT games.id AS game_id FROM players \
+                     JOIN games ON games.id = players.game_id \
+                     WHERE players.id = ? AND games.state = ? ORDER BY games.started_at DESC;",
+                )
+                .bind(&[JsValue::from(player_id), JsValue::from(state.index())]),
+            None => self
+                .db
+                .prepare(
+                    "SELECT games.id AS game_id FROM players \
+                     JOIN games ON games.id = players.game_id \
+                     WHERE players.id = ? ORDER BY games.started_at DESC;",
+                )
+                .bind(&[JsValue::from(player_id)]),
+        };
+
+        let query_result = match prepared {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(fetched_games) => match fetched_games.results::<PlayerGame>() {
+                Ok(games) => Ok(games.into_iter().map(|game| game.game_id).collect()),
+                Err(err) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<Player>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+
     /// Retrieves all players from the D1 database.
     ///
+    /// The players are ordered by the time they joined, players who joined at the same time by
+    /// their id. The turn order and the seating in the UI rely on this order.
+    ///
     /// # Arguments
     ///
     /// - `game_id` -> Optional game id after which either all players are return or just all
     /// players in a game.
     /// - `card_repository` -> Reference to the `CardRepository` to fetch cards associated with
     ///
+    /// When the repository was built `with_degraded_hands`, a player whose cards can't be loaded
+    /// is returned with an empty hand instead of failing the whole query.
+    ///
     /// # Returns
     ///
     /// A `Result` containing a vector of `Player` instances on success, or a `DatabaseQueryError`
-    /// on failure.
+    /// on failure. A game nobody joined yet has no players, so the vector is empty then.
     pub async fn get_all_players(
         &self,
         game_id: Option<&str>,
@@ -300,7 +1023,7 @@ impl PlayerRepository {
         let query_result = match game_id {
             None => {
                 match self.db
-                    .prepare("SELECT * FROM players;")
+                    .prepare("SELECT * FROM players ORDER BY joined_at ASC, id ASC;")
                     .bind(&[])
                     {
                         Ok(fetched_data) => fetched_data.all().await,
@@ -313,7 +1036,7 @@ impl PlayerRepository {
             }
             Some(_game_id) => {
                 match self.db
-                    .prepare("SELECT * FROM players WHERE game_id = ?;")
+                    .prepare("SELECT * FROM players WHERE game_id = ? ORDER BY joined_at ASC, id ASC;")
                     .bind(&[JsValue::from(_game_id)])
                     {
                         Ok(fetched_data) => fetched_data.all().await,
@@ -344,6 +1067,14 @@ impl PlayerRepository {
                         .await
                     {
                         Ok(cards) => cards,
+                        Err(err) if self.degrade_failed_hands => {
+                            log::warn!(
+                                "The cards of the player '{}' couldn't be loaded, the hand is left empty: {}",
+                                player.id,
+                                err
+                            );
+                            Vec::new()
+                        }
                         Err(err) => {
                             return Err(Box::new(DatabaseQueryError::<Player>::new(
                                 err.to_string(),
@@ -354,15 +1085,7 @@ impl PlayerRepository {
                     };
                 }
 
-                if players.is_empty() {
-                    Err(Box::new(DatabaseQueryError::<Player>::new(
-                        "No players found".to_string(),
-                        None,
-                        StatusCode::NOT_FOUND,
-                    )))
-                } else {
-                    Ok(players)
-                }
+                Ok(players)
             }
             Err(e) => Err(Box::new(DatabaseQueryError::<Player>::new(
                 e.to_string(),
@@ -373,4 +1096,10 @@ impl PlayerRepository {
     }
 }
 
+/// Row type for queries that select the game of a player.
+#[derive(Deserialize)]
+struct PlayerGame {
+    game_id: String,
+}
+
 }}
@@ -0,0 +1,106 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+
+use axum::http::StatusCode;
+use wasm_bindgen::JsValue;
+use crate::backend::utils::query_budget::CountedDatabase;
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError},
+    types::rate_limit::RateLimitWindow,
+    utils::database_service::{run_labeled_batch, LabeledStatement},
+};
+
+/// A database repository for interacting with the `rate_limits` table.
+///
+/// Every row counts the requests of a key in one fixed time window. The worker has no memory
+/// that is shared between requests, therefore the counters are kept in the database.
+///
+/// # Properties
+///
+/// `db`: A `CountedDatabase` that provides access to the D1 database.
+pub struct RateLimitRepository {
+    /// The D1 database instance used for accessing the rate limit windows.
+    db: CountedDatabase,
+}
+
+impl RateLimitRepository {
+    /// Creates a new `RateLimitRepository` instance with the provided D1 database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - An instance of `D1Database` to be used for database operations.
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        RateLimitRepository { db: db.into() }
+    }
+
+    /// Counts a request of a key in its current window and removes the older windows of the key.
+    ///
+    /// Both statements are executed in one batch, so concurrent requests can't overwrite each
+    /// other's count.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Identifies the limited action and the client.
+    /// * `window_start` - Unix timestamp in seconds when the current window started.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of requests in the current window including this one,
+    /// or an error on failure.
+    pub async fn hit(&self, key: &str, window_start: i64) -> Result<usize, Box<dyn ApplicationError>> {
+        let queries = [
+            (
+                "remove expired windows",
+                "DELETE FROM rate_limits WHERE key = ? AND window_start < ?;",
+                vec![JsValue::from(key), JsValue::from(window_start as f64)],
+            ),
+            (
+                "count request",
+                "INSERT INTO rate_limits (key, window_start, hits) VALUES (?, ?, 1)
+                    ON CONFLICT (key, window_start) DO UPDATE SET hits = hits + 1 RETURNING *;",
+                vec![JsValue::from(key), JsValue::from(window_start as f64)],
+            ),
+        ];
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<RateLimitWindow>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        let results = match run_labeled_batch::<RateLimitWindow>(&self.db, statements).await {
+            Ok(results) => results,
+            Err(err) => return Err(Box::new(err)),
+        };
+
+        let windows = match results.last().map(|result| result.results::<RateLimitWindow>()) {
+            Some(Ok(windows)) => windows,
+            Some(Err(err)) => return Err(Box::new(DatabaseQueryError::<RateLimitWindow>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+            None => vec![],
+        };
+
+        match windows.first() {
+            Some(window) => Ok(window.hits),
+            None => Err(Box::new(DatabaseQueryError::<RateLimitWindow>::new(
+                format!("The request of the key ['{}'] couldn't be counted!", key),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+}
+    }
+}
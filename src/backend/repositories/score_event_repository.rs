@@ -0,0 +1,167 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+
+
+use axum::{http::StatusCode, Json};
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+use crate::backend::utils::query_budget::CountedDatabase;
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, database_query_error::DatabaseQueryError},
+    types::score_event::ScoreEvent,
+    utils::database_service::{run_labeled_batch, LabeledStatement},
+};
+
+/// A database repository for interacting with the `score_events` table.
+///
+/// The table is the ledger of all score changes. Recording an event also updates the `score`
+/// column of the player, which is only a cached sum of the ledger.
+///
+/// # Properties
+///
+/// `db`: A `CountedDatabase` that provides access to the D1 database.
+pub struct ScoreEventRepository {
+    /// The D1 database instance used for accessing the score events.
+    db: CountedDatabase,
+}
+
+impl ScoreEventRepository {
+    /// Creates a new `ScoreEventRepository` instance with the provided D1 database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - An instance of `D1Database` to be used for database operations.
+    pub fn new(db: impl Into<CountedDatabase>) -> Self {
+        ScoreEventRepository { db: db.into() }
+    }
+
+    /// Records a score event and recalculates the score of the player from the ledger.
+    ///
+    /// Both statements are executed in one batch, so the score never differs from the sum of
+    /// the events. A negative sum results in a score of 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `ScoreEvent` to be recorded.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new score of the player, or an error on failure.
+    pub async fn record_score_event(&self, event: ScoreEvent) -> Result<usize, Box<dyn ApplicationError>> {
+        let queries = [
+            (
+                "record score event",
+                "INSERT INTO score_events (id, game_id, player_id, round_number, delta, reason, created_at) VALUES (?, ?, ?, ?, ?, ?, ?);",
+                vec![
+                    JsValue::from(&event.id),
+                    JsValue::from(&event.game_id),
+                    JsValue::from(&event.player_id),
+                    JsValue::from(event.round_number),
+                    JsValue::from(event.delta as f64),
+                    JsValue::from(&event.reason),
+                    JsValue::from(&event.created_at),
+                ],
+            ),
+            (
+                "recalculate score",
+                "UPDATE players SET score = MAX(0, (SELECT COALESCE(SUM(delta), 0) FROM score_events WHERE player_id = ?)) WHERE id = ? RETURNING score;",
+                vec![JsValue::from(&event.player_id), JsValue::from(&event.player_id)],
+            ),
+        ];
+
+        let mut statements = vec![];
+        for (label, query, bindings) in queries {
+            match self.db.prepare(query).bind(&bindings) {
+                Ok(statement) => statements.push(LabeledStatement::new(label, statement)),
+                Err(err) => return Err(Box::new(DatabaseQueryError::<ScoreEvent>::new(
+                    err.to_string(),
+                    Some(Json(event.clone())),
+                    StatusCode::INTERNAL_SERVER_ERROR
+                ).with_query(query, &bindings)))
+            }
+        }
+
+        let results = match run_labeled_batch::<ScoreEvent>(&self.db, statements).await {
+            Ok(results) => results,
+            Err(err) => return Err(Box::new(err)),
+        };
+
+        let scores = match results.last().map(|result| result.results::<PlayerScore>()) {
+            Some(Ok(scores)) => scores,
+            Some(Err(err)) => return Err(Box::new(DatabaseQueryError::<ScoreEvent>::new(
+                err.to_string(),
+                Some(Json(event)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+            None => vec![],
+        };
+
+        match scores.first() {
+            Some(player_score) => Ok(player_score.score),
+            None => Err(Box::new(DatabaseQueryError::<ScoreEvent>::new(
+                format!("The player with the id ['{}'] couldn't be found, therefore the score wasn't recorded!", event.player_id),
+                Some(Json(event)),
+                StatusCode::NOT_FOUND,
+            ))),
+        }
+    }
+
+    /// Gets the ledger of a game, optionally only the events of a single player.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` - Identifier of the game.
+    /// * `player_id` - Optional identifier of the player whose events are returned.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the events in the order they were recorded, or an error on failure.
+    pub async fn get_score_events(&self, game_id: &str, player_id: Option<&str>) -> Result<Vec<ScoreEvent>, Box<dyn ApplicationError>> {
+        let prepared = match player_id {
+            Some(player_id) => self
+                .db
+                .prepare("SELECT * FROM score_events WHERE game_id = ? AND player_id = ? ORDER BY created_at ASC;")
+                .bind(&[JsValue::from(game_id), JsValue::from(player_id)]),
+            None => self
+                .db
+                .prepare("SELECT * FROM score_events WHERE game_id = ? ORDER BY created_at ASC;")
+                .bind(&[JsValue::from(game_id)]),
+        };
+
+        let query_result = match prepared {
+            Ok(fetched_data) => fetched_data.all().await,
+            Err(err) => return Err(Box::new(DatabaseQueryError::<ScoreEvent>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR
+            )))
+        };
+
+        match query_result {
+            Ok(fetched_events) => match fetched_events.results::<ScoreEvent>() {
+                Ok(events) => Ok(events),
+                Err(err) => Err(Box::new(DatabaseQueryError::<ScoreEvent>::new(
+                    err.to_string(),
+                    None,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))),
+            },
+            Err(err) => Err(Box::new(DatabaseQueryError::<ScoreEvent>::new(
+                err.to_string(),
+                None,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))),
+        }
+    }
+}
+
+/// Row type for queries that return the recalculated score of a player.
+#[derive(Deserialize)]
+struct PlayerScore {
+    score: usize,
+}
+
+    }
+}
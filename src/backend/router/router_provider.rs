@@ -7,13 +7,35 @@ cfg_if! {
 
     use leptos::*;
 
-    use axum::routing::{put, post};
+    use axum::routing::{delete, get, put, post};
     use axum::Router;
     use axum::Extension;
+    use axum::middleware;
     use leptos_axum::{generate_route_list, LeptosRoutes};
 
-    use crate::backend::handlers::game_handlers::update_game;
+    use crate::backend::handlers::{
+        admin_handlers::{cleanup_abandoned_games, get_all_hands},
+        card_handlers::{get_card, move_card},
+        chat_handlers::{
+            add_reaction, clear_chat, get_chat, get_recent_messages, mark_messages_read,
+            remove_reaction, send_message,
+        },
+        claim_handlers::get_claim_cards,
+        player_handlers::{
+            draw_card, forfeit_round, get_my_games, get_player_games, get_players_batch, join_game,
+            leave_game, mark_ready, quick_join_game, resume_game,
+        },
+        game_handlers::{
+            apply_game_operations, challenge_claim, create_game, get_claims, get_game, get_game_events,
+            get_game_summary, get_games, get_players, get_score_ledger, get_scoreboard, rename_game,
+            reset_game, resync_game, submit_claim, tick, transfer_host, undo_claim, update_game,
+            verify_game,
+        },
+    };
     use crate::app::*;
+    use crate::backend::utils::{
+        config::Config, query_budget::check_query_budget, repository_provider::Repositories,
+    };
     use worker::Env;
     use leptos::prelude::LeptosOptions;
 
@@ -30,10 +52,26 @@ cfg_if! {
         pub leptos_options: LeptosOptions
     }
 
+    // constants
+
+    /// Prefix of all API routes, when the `API_PREFIX` variable of the worker isn't set, see
+    /// `Config`.
+    pub const DEFAULT_API_PREFIX: &str = "/api/v1";
+
+    /// Unversioned prefix the API routes were served under before they got versioned.
+    pub const LEGACY_API_PREFIX: &str = "/api";
+
     /// Router provider for the Axum application.
     ///
     /// This module defines the router for the Axum application, setting up the routes
     ///
+    /// The API routes are nested under the prefix of the `API_PREFIX` variable, by default
+    /// `DEFAULT_API_PREFIX`. Unless `API_LEGACY_ALIAS` is `false`, they are served under
+    /// `LEGACY_API_PREFIX` as well, so existing clients keep working.
+    ///
+    /// The `Config` of the worker is injected as `Extension` next to the `Repositories`. The D1
+    /// queries of the request are counted and checked against the `query_budget` of the `Config`.
+    ///
     /// # Arguments
     ///
     /// - `env` -> Cloudflare Worker environment
@@ -44,17 +82,75 @@ cfg_if! {
          // retrieve all leptos routes
         let routes = generate_route_list(|| view! { <App />});
 
-        Router::new()
-        // Register all necessary endpoints
-        // game instance endpoints
-        .route("/api/game/update", put(update_game))
+        let config = Config::load(&env);
+        let repos = Repositories::new(Arc::new(env));
+        let queries = repos.query_counter();
+        let query_budget = config.query_budget;
+        let mut router = Router::new().nest(&config.api_prefix, api_routes());
+        if config.api_prefix != LEGACY_API_PREFIX && config.legacy_api_alias {
+            router = router.nest(LEGACY_API_PREFIX, api_routes());
+        }
+
+        router
         .leptos_routes(&leptos_options, routes,{
             let leptos_options = leptos_options.clone();
             move || shell(leptos_options.clone())
         })
         // .fallback()  TODO: Add a fallback handler / page
         .with_state(leptos_options)
-        .layer(Extension(Arc::new(env)))
+        .layer(middleware::from_fn(move |request, next| {
+            check_query_budget(queries.clone(), query_budget, request, next)
+        }))
+        .layer(Extension(repos))
+        .layer(Extension(config))
+    }
+
+    /// Creates all API endpoints relative to the prefix they are nested under.
+    fn api_routes() -> Router<LeptosOptions> {
+        Router::new()
+        // Register all necessary endpoints
+        // game instance endpoints
+        .route("/game", post(create_game))
+        .route("/games", get(get_games))
+        .route("/game/update", put(update_game))
+        .route("/game/{game_id}", get(get_game).patch(rename_game))
+        .route("/game/{game_id}/claims", post(submit_claim).get(get_claims))
+        .route("/game/{game_id}/claims/{claim_id}", delete(undo_claim))
+        .route("/game/{game_id}/debug/hands", get(get_all_hands))
+        .route("/game/{game_id}/challenge", post(challenge_claim))
+        .route("/game/{game_id}/events", get(get_game_events))
+        .route("/game/{game_id}/host", post(transfer_host))
+        .route("/game/{game_id}/ops", post(apply_game_operations))
+        .route("/game/{game_id}/players", get(get_players).post(join_game))
+        .route("/game/{game_id}/players/{player_id}", delete(leave_game))
+        .route("/game/{game_id}/players/{player_id}/draw", post(draw_card))
+        .route("/game/{game_id}/players/{player_id}/forfeit", post(forfeit_round))
+        .route("/game/{game_id}/players/{player_id}/ready", post(mark_ready))
+        .route("/game/{game_id}/quick-join", post(quick_join_game))
+        .route("/game/{game_id}/reset", post(reset_game))
+        .route("/game/{game_id}/resync", get(resync_game))
+        .route("/game/{game_id}/scoreboard", get(get_scoreboard))
+        .route("/game/{game_id}/scores", get(get_score_ledger))
+        .route("/game/{game_id}/summary", get(get_game_summary))
+        .route("/game/{game_id}/tick", post(tick))
+        .route("/game/{game_id}/verify", get(verify_game))
+        // player endpoints
+        .route("/me/games", get(get_my_games))
+        .route("/player/game", get(resume_game))
+        .route("/player/{player_id}/games", get(get_player_games))
+        .route("/players/batch", post(get_players_batch))
+        // card endpoints
+        .route("/card/{card_id}", get(get_card).patch(move_card))
+        // claim endpoints
+        .route("/claim/{claim_id}/cards", get(get_claim_cards))
+        // chat endpoints
+        .route("/chat/{chat_id}", get(get_chat))
+        .route("/chat/{chat_id}/messages", post(send_message).get(get_recent_messages).delete(clear_chat))
+        .route("/chat/{chat_id}/messages/{message_id}/reactions", post(add_reaction))
+        .route("/chat/{chat_id}/messages/{message_id}/reactions/{emoji}", delete(remove_reaction))
+        .route("/chat/{chat_id}/read", post(mark_messages_read))
+        // admin endpoints
+        .route("/admin/cleanup", post(cleanup_abandoned_games))
     }
 
 }}
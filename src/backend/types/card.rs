@@ -5,13 +5,17 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use crate::backend::utils::id_generator::generate_id;
+
 // using statements
-use crate::backend::enums::card_types::CardType;
+use crate::backend::enums::{card_location::CardLocation, card_types::CardType};
 
 use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(feature = "ssr")] {
-        use crate::backend::errors::{application_error::ErrorObject, process_error::ProcessError};
+        use crate::backend::errors::{
+            application_error::ErrorObject, forbidden_error::ForbiddenError, process_error::ProcessError,
+        };
     }
 }
 
@@ -23,7 +27,7 @@ cfg_if! {
 ///
 /// # Fields
 /// - `card_type`: An enum representing the type of the card, such as King, Queen, Jack, Ace, or
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, PartialEq)]
 pub struct Card {
     /// The unique identifier for the card, typically a string.
     pub id: String,
@@ -41,7 +45,7 @@ impl Card {
     /// A new `Card` instance.
     pub fn new(card_type: CardType) -> Self {
         Card {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: generate_id(),
             card_type,
         }
     }
@@ -90,6 +94,113 @@ cfg_if! {
     }
 }
 
+/// A card together with its location, like it's stored in the `cards` table.
+///
+/// The `location` is authoritative, `player_id` is only set for cards in a hand and `claim_id`
+/// for cards in a claim or on the pile.
+///
+/// # Fields
+///
+/// - `id`: The unique identifier for the card.
+/// - `card_type`: The type of the card.
+/// - `game_id`: The ID of the game the card is part of.
+/// - `player_id`: The ID of the player holding the card, if any.
+/// - `claim_id`: The ID of the claim the card was placed in, if any.
+/// - `location`: Where the card currently is.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CardDetails {
+    /// The unique identifier for the card.
+    pub id: String,
+    /// The type of the card.
+    pub card_type: CardType,
+    /// The ID of the game the card is part of.
+    pub game_id: Option<String>,
+    /// The ID of the player holding the card, if any.
+    pub player_id: Option<String>,
+    /// The ID of the claim the card was placed in, if any.
+    pub claim_id: Option<String>,
+    /// Where the card currently is.
+    #[serde(default)]
+    pub location: CardLocation,
+}
+
+/// Number of cards of a game that aren't in the hand of a player.
+///
+/// # Fields
+///
+/// - `pile_size`: Number of cards placed in claims, which form the central pile.
+/// - `deck_remaining`: Number of cards neither in a hand nor in a claim, which weren't dealt
+///   yet.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct CardCounts {
+    /// Number of cards in the central pile
+    pub pile_size: usize,
+    /// Number of undealt cards
+    pub deck_remaining: usize,
+}
+
+/// Request to move a single card into a hand, a claim or back to the deck.
+///
+/// At most one of the fields can be set; when both are missing, the card returns to the deck.
+///
+/// # Fields
+///
+/// - `player_id`: The ID of the player who gets the card.
+/// - `claim_id`: The ID of the claim the card is moved to.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MoveCardRequest {
+    /// The ID of the player who gets the card.
+    #[serde(default)]
+    pub player_id: Option<String>,
+    /// The ID of the claim the card is moved to.
+    #[serde(default)]
+    pub claim_id: Option<String>,
+}
+
+impl Display for MoveCardRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MoveCardRequest {{ player_id: {:?}, claim_id: {:?} }}",
+            self.player_id, self.claim_id
+        )
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        impl<'a> ErrorObject<'a> for MoveCardRequest {}
+
+impl CardDetails {
+    /// Checks that the card is in the hand of a player of a game, before the player places it in
+    /// a claim.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the game the card has to be part of.
+    /// - `player_id` -> Identifier of the player who has to hold the card.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ForbiddenError` when the card belongs to another game, isn't in a hand or is
+    /// held by another player.
+    pub fn check_held_by(&self, game_id: &str, player_id: &str) -> Result<(), ForbiddenError> {
+        if self.game_id.as_deref() != Some(game_id)
+            || self.location != CardLocation::Hand
+            || self.player_id.as_deref() != Some(player_id)
+        {
+            return Err(ForbiddenError::new(format!(
+                "The card with the id ['{}'] isn't in the hand of the player ['{}'] in the game ['{}']!",
+                self.id, player_id, game_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+    }
+}
+
 cfg_if! {
     if #[cfg(feature = "ssr")] {
 
@@ -180,3 +291,43 @@ impl Display for UpdateCardDTO {
 
 impl<'a> ErrorObject<'a> for UpdateCardDTO {}
 }}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    /// Creates a card in the hand of `player` in the game `game`.
+    fn card_in_hand(game: &str, player: &str) -> CardDetails {
+        CardDetails {
+            id: "card".to_string(),
+            card_type: CardType::King,
+            game_id: Some(game.to_string()),
+            player_id: Some(player.to_string()),
+            claim_id: None,
+            location: CardLocation::Hand,
+        }
+    }
+
+    #[test]
+    fn a_player_can_place_a_card_of_their_hand() {
+        assert!(card_in_hand("game", "alice").check_held_by("game", "alice").is_ok());
+    }
+
+    #[test]
+    fn a_player_cant_place_a_card_of_another_hand() {
+        assert!(card_in_hand("game", "bob").check_held_by("game", "alice").is_err());
+    }
+
+    #[test]
+    fn a_player_cant_place_a_card_of_another_game() {
+        assert!(card_in_hand("other game", "alice").check_held_by("game", "alice").is_err());
+    }
+
+    #[test]
+    fn a_player_cant_place_a_card_that_isnt_in_a_hand() {
+        let mut card = card_in_hand("game", "alice");
+        card.location = CardLocation::Pile;
+
+        assert!(card.check_held_by("game", "alice").is_err());
+    }
+}
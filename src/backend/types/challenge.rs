@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{enums::claim_outcome::ClaimOutcome, types::game_view::GameView};
+
+/// A request of a player who doubts that a claim on the stack is true.
+///
+/// # Properties
+///
+/// - challenger_id: The ID of the player calling the bluff.
+/// - claim_id: The ID of the claim that is challenged.
+#[derive(Deserialize, Serialize)]
+pub struct ChallengeRequest {
+    /// The ID of the player calling the bluff.
+    pub challenger_id: String,
+    /// The ID of the claim that is challenged.
+    pub claim_id: String,
+}
+
+/// Result of a resolved challenge.
+///
+/// The loser has to pick up all cards of the stack.
+///
+/// # Properties
+///
+/// - claim_id: The ID of the challenged claim.
+/// - challenger_id: The ID of the player who called the bluff.
+/// - accused_id: The ID of the player who placed the claim.
+/// - was_bluff: Indicates whether the claim contained a card that doesn't match the card to play.
+/// - loser_id: The ID of the player who lost the challenge.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChallengeOutcome {
+    /// The ID of the challenged claim.
+    pub claim_id: String,
+    /// The ID of the player who called the bluff.
+    pub challenger_id: String,
+    /// The ID of the player who placed the claim.
+    pub accused_id: String,
+    /// Indicates whether the claim was a bluff.
+    pub was_bluff: bool,
+    /// The ID of the player who has to pick up the stack.
+    pub loser_id: String,
+}
+
+impl ChallengeOutcome {
+    /// Returns the `ClaimOutcome` that is stored with the challenged claim.
+    pub fn claim_outcome(&self) -> ClaimOutcome {
+        if self.was_bluff {
+            ClaimOutcome::Bluff
+        } else {
+            ClaimOutcome::Truthful
+        }
+    }
+}
+
+/// Answer to a challenge, so clients don't need a second request to update the hands.
+///
+/// # Properties
+///
+/// - outcome: The resolved `ChallengeOutcome`.
+/// - game: The game after the loser picked up the stack, as seen by the challenger.
+/// - card_counts: New number of cards in the hands of the challenger and the accused player.
+#[derive(Deserialize, Serialize)]
+pub struct ChallengeResponse {
+    /// The resolved `ChallengeOutcome`.
+    pub outcome: ChallengeOutcome,
+    /// The game after the loser picked up the stack, as seen by the challenger.
+    pub game: GameView,
+    /// Number of cards per affected player ID.
+    pub card_counts: HashMap<String, usize>,
+}
@@ -0,0 +1,72 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// Names of the fields that were actually changed by an update.
+///
+/// Update methods return it alongside the updated entity, so events and diff payloads only
+/// contain what really changed.
+///
+/// # Properties
+///
+/// - changed_fields: Names of the changed fields in the order they were detected.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ChangeSet {
+    /// Names of the changed fields.
+    pub changed_fields: Vec<String>,
+}
+
+impl ChangeSet {
+    /// Creates an empty `ChangeSet`.
+    pub fn new() -> Self {
+        ChangeSet {
+            changed_fields: vec![],
+        }
+    }
+
+    /// Marks a field as changed.
+    ///
+    /// A field is only listed once, even when it is marked several times.
+    ///
+    /// # Arguments
+    ///
+    /// - `field`: Name of the changed field.
+    pub fn mark(&mut self, field: &str) {
+        if !self.contains(field) {
+            self.changed_fields.push(field.to_string());
+        }
+    }
+
+    /// Checks whether a field was changed.
+    pub fn contains(&self, field: &str) -> bool {
+        self.changed_fields.iter().any(|changed| changed == field)
+    }
+
+    /// Returns `true` when no field was changed.
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields.is_empty()
+    }
+}
+
+impl Display for ChangeSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Changed fields: [{}]", self.changed_fields.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChangeSet;
+
+    #[test]
+    fn a_field_marked_twice_is_listed_once() {
+        let mut changes = ChangeSet::new();
+        assert!(changes.is_empty());
+
+        changes.mark("name");
+        changes.mark("name");
+
+        assert!(!changes.is_empty());
+        assert_eq!(changes.changed_fields, vec!["name"]);
+    }
+}
@@ -11,13 +11,30 @@ cfg_if! {
     }
 }
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{collections::HashMap, fmt};
+
+use crate::backend::utils::id_generator::generate_id;
 
 // constants
 
 /// The maximum number of messages that can be stored in a chat.
 const MAX_CHAT_MESSAGE_LENGTH: usize = 30; // 30 messages
 
+/// The maximum number of characters of a reaction, enough for emojis built from several code
+/// points like flags or families.
+pub const MAX_REACTION_LENGTH: usize = 16;
+
+/// The maximum number of characters of the content of a chat message.
+pub const MAX_MESSAGE_LENGTH: usize = 500;
+
+/// Counts the characters of the content of a chat message.
+///
+/// Unicode scalar values are counted instead of bytes, so a message with umlauts or emojis isn't
+/// rejected earlier than one with plain ASCII characters.
+pub fn message_length(content: &str) -> usize {
+    content.chars().count()
+}
+
 /// Represents a chat in the game, containing messages exchanged between players.
 ///
 /// The `Chat` struct holds a vector of `ChatMessage` instances and tracks the number of messages.
@@ -33,7 +50,7 @@ const MAX_CHAT_MESSAGE_LENGTH: usize = 30; // 30 messages
 ///    id: "9fd2151d-432e-4549-99bf-b684b5be9555".to_string()
 ///    };
 /// ```
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Chat {
     /// Identifier of a chat instance
     pub id: String,
@@ -45,6 +62,18 @@ pub struct Chat {
     pub number_of_messages: usize,
     /// The identifier of the game the chat belongs to.
     pub game_id: String,
+    /// Optional maximum number of stored messages.
+    ///
+    /// When a new message exceeds it, the oldest messages are removed. Without a value the chat
+    /// isn't trimmed.
+    #[serde(default)]
+    pub max_chat_messages: Option<usize>,
+    /// Number of unread messages of every player in the game, keyed by the player id.
+    ///
+    /// Isn't a column of the `chats` table, it's only counted from the `read_receipts` when
+    /// requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unread_counts: Option<HashMap<String, usize>>,
 }
 
 /// Represents a chat message in the game, containing the player ID and the message content.
@@ -65,9 +94,12 @@ pub struct Chat {
 ///    sent_at: Utc::now().to_string(),
 ///    };
 /// ```  
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub struct ChatMessage {
     /// Identifier of the ChatMessage
+    ///
+    /// Can be generated by the client, it's generated by the server when it's empty.
+    #[serde(default)]
     pub id: String,
     /// ID of the player, who sent the message
     pub player_id: String,
@@ -77,13 +109,73 @@ pub struct ChatMessage {
     pub sent_at: String, // as chrono::DateTime<chrono::Utc>,
     /// ID of the chat, where the message was sent.
     pub chat_id: String,
+    /// How often every emoji was used to react to the message.
+    ///
+    /// Isn't a column of the `chat_messages` table, it's counted from the `message_reactions`.
+    #[serde(default)]
+    pub reactions: Vec<ReactionCount>,
+}
+
+/// Number of players who reacted to a message with the same emoji.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ReactionCount {
+    /// The emoji of the reaction
+    pub emoji: String,
+    /// Number of players who reacted with the emoji
+    pub count: usize,
+}
+
+/// Request body to react to a chat message.
+///
+/// # Fields
+///
+/// - `emoji`: The emoji the player reacts with.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ReactionRequest {
+    /// The emoji the player reacts with
+    pub emoji: String,
+}
+
+impl ReactionRequest {
+    /// Returns the trimmed emoji, when it isn't empty and not longer than `MAX_REACTION_LENGTH`.
+    pub fn validated_emoji(&self) -> Option<String> {
+        let emoji = self.emoji.trim();
+        if emoji.is_empty() || emoji.chars().count() > MAX_REACTION_LENGTH {
+            return None;
+        }
+        Some(emoji.to_string())
+    }
+}
+
+/// Request body to mark the messages of a chat as read.
+///
+/// Every message sent up to and including `message_id` counts as read by the player.
+///
+/// # Fields
+///
+/// - `player_id`: The player who read the messages. Always replaced by the authenticated
+///   player.
+/// - `message_id`: The last message the player has read.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ReadReceiptRequest {
+    /// The player who read the messages
+    #[serde(default)]
+    pub player_id: String,
+    /// Identifier of the last read message
+    pub message_id: String,
+}
+
+impl fmt::Display for ReactionRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ReactionRequest Emoji: {}", self.emoji)
+    }
 }
 
 // Implementation of 'Chat' struct
 
 impl Default for Chat {
     fn default() -> Self {
-        Self::new(uuid::Uuid::new_v4().to_string())
+        Self::new(generate_id())
     }
 }
 
@@ -94,10 +186,12 @@ impl Chat {
     /// - 'Chat' object with no messages
     pub fn new(game_id: String) -> Self {
         Chat {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: generate_id(),
             messages: vec![],
             number_of_messages: 0,
             game_id,
+            max_chat_messages: None,
+            unread_counts: None,
         }
     }
 
@@ -187,7 +281,11 @@ impl ChatMessage {
         sent_at: String,
         chat_id: String,
     ) -> Result<Self, BadClientRequest<ChatMessage>> {
-        if content.is_empty() || player_id.is_empty() || sent_at.is_empty() {
+        if content.is_empty()
+            || message_length(&content) > MAX_MESSAGE_LENGTH
+            || player_id.is_empty()
+            || sent_at.is_empty()
+        {
             return Err::<ChatMessage, BadClientRequest<_>>(BadClientRequest {
                 bad_data: Json(ChatMessage {
                     id: id.clone(),
@@ -195,10 +293,11 @@ impl ChatMessage {
                     sent_at: sent_at.clone(),
                     content: content.clone(),
                     chat_id: chat_id.clone(),
+                    reactions: vec![],
                 }),
                 message: format!(
-                    "The provided data by player with id: {} for a chat message was not valid!",
-                    &player_id
+                    "The provided data by player with id: {} for a chat message was not valid! The content must have between 1 and {} characters.",
+                    &player_id, MAX_MESSAGE_LENGTH
                 ),
             });
         };
@@ -208,6 +307,7 @@ impl ChatMessage {
             content,
             sent_at,
             chat_id,
+            reactions: vec![],
         })
     }
 }
@@ -245,3 +345,26 @@ impl fmt::Debug for ChatMessage {
 
 #[cfg(feature = "ssr")]
 impl<'a> ErrorObject<'a> for ChatMessage {}
+
+#[cfg(feature = "ssr")]
+impl<'a> ErrorObject<'a> for ReactionRequest {}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn umlauts_and_emojis_count_as_single_characters() {
+        assert_eq!(message_length("Hallo"), 5);
+        assert_eq!(message_length("Grüße"), 5);
+        assert_eq!(message_length("🃏🃏"), 2);
+    }
+
+    #[test]
+    fn the_longest_message_fits_with_multi_byte_characters() {
+        let message = "ü".repeat(MAX_MESSAGE_LENGTH);
+
+        assert!(message.len() > MAX_MESSAGE_LENGTH);
+        assert_eq!(message_length(&message), MAX_MESSAGE_LENGTH);
+    }
+}
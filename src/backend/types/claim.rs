@@ -6,37 +6,58 @@ use std::fmt;
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
-        use crate::backend::errors::{application_error::ErrorObject, bad_client_request::BadClientRequest};
+        use crate::backend::errors::{
+            application_error::{ApplicationError, ErrorObject},
+            bad_client_request::BadClientRequest,
+            validation_error::ValidationError,
+        };
+        use crate::backend::utils::id_generator::generate_id;
         use axum::Json;
     }
 }
 use serde::{Deserialize, Serialize};
 
 // using statements
-use crate::backend::types::card::Card;
+use crate::backend::{
+    enums::{card_types::CardType, claim_outcome::ClaimOutcome},
+    types::card::Card,
+};
 
 // constants
 
 /// Max number of cards that can be claimed in a single claim.
-const MAX_CARDS_PER_CLAIM: usize = 4;
+pub const MAX_CARDS_PER_CLAIM: usize = 4;
 
 /// The `Claim` struct represents a claim made by a player in a card game.
 ///
 /// It contains information about the player who made the claim and the number of cards claimed.
 ///
+/// A player announces "N cards of rank R", the placed cards themselves stay hidden until the
+/// claim is challenged.
+///
 /// # Fields
 /// - `created_by`: The unique identifier of the player who made the claim.
+/// - `announced_rank`: The rank the player claims to have placed.
 /// - `number_of_cards`: The number of cards claimed by the player.
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub struct Claim {
     /// Unique identifier for the claim
     pub id: String,
     /// Id of the user that placed the claim on the stack
     pub created_by: String,
+    /// Rank the player announced for all cards of the claim
+    pub announced_rank: CardType,
     /// Number of cards used in the claim
     pub number_of_cards: usize,
     /// List of placed cards in the claim
+    #[serde(default)]
     pub cards: Vec<Card>,
+    /// What happened with the claim after it was placed
+    #[serde(default)]
+    pub outcome: ClaimOutcome,
+    /// Id of the player who challenged the claim, if it was challenged
+    #[serde(default)]
+    pub challenged_by: Option<String>,
 }
 
 impl Claim {
@@ -44,39 +65,71 @@ impl Claim {
     ///
     /// # Arguments
     /// - `created_by`: The unique identifier of the player making the claim.
+    /// - `announced_rank`: The rank the player announced for the cards.
     /// - `number_of_cards`: The number of cards claimed by the player.
     /// - 'cards' : List of cards with a maximum number of 4
     ///
     /// # Error
     ///
-    /// Return a 'BadClientRequest<Claim>' error when the provided error of the user is invalid.
+    /// Return a boxed 'BadClientRequest<Claim>' error when the provided error of the user is
+    /// invalid, e.g. when the number of placed cards doesn't match the announced number of cards.
     ///
     /// # Returns
     /// A new `Claim` instance.
     #[cfg(feature = "ssr")]
     pub fn new(
         created_by: String,
+        announced_rank: CardType,
         number_of_cards: usize,
         cards: Vec<Card>,
-    ) -> Result<Self, BadClientRequest<Claim>> {
-        if number_of_cards > MAX_CARDS_PER_CLAIM {
-            return Err::<Claim, BadClientRequest<Claim>>(BadClientRequest {
-                message: "The user handed in an invalid claim object!".to_string(),
+    ) -> Result<Self, Box<dyn ApplicationError>> {
+        if number_of_cards == 0 || number_of_cards > MAX_CARDS_PER_CLAIM || cards.len() != number_of_cards {
+            return Err(Box::new(BadClientRequest {
+                message: format!(
+                    "The user handed in an invalid claim object! A claim must announce between 1 and {} cards and contain exactly the announced number of cards.",
+                    MAX_CARDS_PER_CLAIM
+                ),
                 bad_data: Json(Claim {
                     id: "No ID".to_string(),
                     created_by: created_by.clone(),
+                    announced_rank,
                     number_of_cards,
                     cards: cards.clone(),
+                    outcome: ClaimOutcome::Unchallenged,
+                    challenged_by: None,
                 }),
-            });
+            }));
         };
         Ok(Claim {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: generate_id(),
             created_by,
+            announced_rank,
             number_of_cards,
             cards,
+            outcome: ClaimOutcome::Unchallenged,
+            challenged_by: None,
         })
     }
+
+    /// Checks that the claim announces at least one card.
+    ///
+    /// A claim without cards can't be challenged in a meaningful way, because there is nothing to
+    /// reveal. The type of `number_of_cards` already rules out negative numbers.
+    ///
+    /// # Error
+    ///
+    /// Returns a `ValidationError` when `number_of_cards` is 0.
+    #[cfg(feature = "ssr")]
+    pub fn validate_number_of_cards(&self) -> Result<(), ValidationError> {
+        if self.number_of_cards == 0 {
+            return Err(ValidationError::new(format!(
+                "The claim of the player with the id ['{}'] must announce at least 1 card!",
+                self.created_by
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Claim {
@@ -86,10 +139,13 @@ impl fmt::Display for Claim {
             "
         Id: {},
         Created By: {},
+        Announced Rank: {},
         Number of Cards: {},
-        All cards: {:?}
+        All cards: {:?},
+        Outcome: {},
+        Challenged By: {:?}
             ",
-            self.id, self.created_by, self.number_of_cards, self.cards
+            self.id, self.created_by, self.announced_rank, self.number_of_cards, self.cards, self.outcome, self.challenged_by
         )
     }
 }
@@ -101,10 +157,13 @@ impl fmt::Debug for Claim {
             "
         id: {},
         Created By: {},
+        Announced Rank: {},
         Number of Cards: {},
-        All cards: {:?}
+        All cards: {:?},
+        Outcome: {},
+        Challenged By: {:?}
             ",
-            self.id, self.created_by, self.number_of_cards, self.cards
+            self.id, self.created_by, self.announced_rank, self.number_of_cards, self.cards, self.outcome, self.challenged_by
         )
     }
 }
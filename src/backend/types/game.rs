@@ -2,26 +2,35 @@ use cfg_if::cfg_if;
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
+        use std::collections::BTreeMap;
         use crate::backend::errors::application_error::ErrorObject;
         use crate::backend::errors::process_error::ProcessError;
+        use crate::backend::errors::validation_error::ValidationError;
         use axum::http::StatusCode;
         use axum::response::IntoResponse;
         use crate::backend::utils::game_service::select_new_card_to_be_played;
+        use crate::backend::types::change_set::ChangeSet;
     }
 }
 
 use std::fmt::{Debug, Display};
 
-use crate::backend::enums::game_state::GameState;
+use crate::backend::enums::{game_phase::GamePhase, game_state::GameState};
+use crate::backend::types::card::CardCounts;
 use crate::backend::types::chat::Chat;
 use crate::backend::types::claim::Claim;
+use crate::backend::types::game_settings::GameSettings;
 use crate::{backend::enums::card_types::CardType, backend::types::player::Player};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use crate::backend::utils::id_generator::generate_id;
 
 // constants
-/// The maximum number of players allowed in a game.
-const MAX_PLAYERS: usize = 5;
+/// The maximum number of players allowed in a game, when the `MAX_PLAYERS` variable of the
+/// worker isn't set.
+pub const MAX_PLAYERS: usize = 5;
+
+/// The maximum number of characters of a game title.
+pub const MAX_TITLE_LENGTH: usize = 50;
 
 /// Global struct representing a game in the system.k
 ///
@@ -31,16 +40,22 @@ const MAX_PLAYERS: usize = 5;
 ///
 /// Holds information about the state of the game, such as players, scores, and other relevant
 /// details.
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub struct Game {
     /// Unique identifier for the game instance.
     pub id: String,
+    /// Human-friendly title shown in the lobby.
+    #[serde(default)]
+    pub title: String,
     /// List of player IDs participating in the game.
     pub players: Vec<Player>,
     /// ID of the player whose turn it is.
     pub which_player_turn: String, // ID of the player whose turn it is
     /// Current state of the game, represented as a string.
     pub state: GameState,
+    /// Phase of the current round, only meaningful while the game is `InProgress`.
+    #[serde(default)]
+    pub phase: GamePhase,
     /// Timestamp when the game was created
     ///
     /// This property is static.
@@ -53,6 +68,51 @@ pub struct Game {
     pub card_to_play: CardType,
     /// Vector of claims every player made
     pub claims: Vec<Claim>,
+    /// Point in time until the player whose turn it is has to make a move.
+    ///
+    /// Games which aren't running have no deadline.
+    #[serde(default)]
+    pub turn_deadline: Option<String>,
+    /// Point in time until the last claim can be challenged by the other players.
+    ///
+    /// Is `None` when there is no claim that can be challenged.
+    #[serde(default)]
+    pub challenge_deadline: Option<String>,
+    /// ID of the latest claim, as long as it can still be challenged.
+    ///
+    /// Is set when a claim is placed and cleared when its challenge window closes.
+    #[serde(default)]
+    pub current_claim_id: Option<String>,
+    /// The rules the game is played with.
+    #[serde(default, deserialize_with = "GameSettings::deserialize_column")]
+    pub settings: GameSettings,
+    /// Number of changes of the game so far.
+    ///
+    /// Is incremented by the database on every change of the game, its players, cards, claims
+    /// and chat, so it never goes backwards. Clients compare it to detect missed updates.
+    #[serde(default)]
+    pub sequence: usize,
+    /// Optional seed of the shuffled deck, so the deals of a game can be reproduced.
+    ///
+    /// The deck is shuffled randomly when it's missing.
+    #[serde(default)]
+    pub deck_seed: Option<u32>,
+    /// Number of cards in the central pile and in the deck.
+    ///
+    /// Isn't a column of the `games` table, it's counted from the `cards`.
+    #[serde(default)]
+    pub card_counts: CardCounts,
+    /// Point in time when the game was archived.
+    ///
+    /// Archived games are kept in the database, but can't be fetched anymore.
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    /// SHA-256 hash of the code needed to join the game.
+    ///
+    /// Games with a join code are private and hidden from the lobby. The hash is never sent to
+    /// the clients.
+    #[serde(default, skip_serializing)]
+    pub join_code_hash: Option<String>,
 }
 
 impl Default for Game {
@@ -78,18 +138,29 @@ impl Game {
     /// assert_eq!(game.game_state, "initialized");
     /// ```
     pub fn new() -> Self {
-        let game_id = Uuid::new_v4().to_string();
+        let game_id = generate_id();
 
         Game {
             id: game_id.clone(),
+            title: Game::default_title(&game_id),
             players: vec![],
             which_player_turn: String::new(),
             state: GameState::Starting, // Placeholder for actual game state
+            phase: GamePhase::Dealing,
             started_at: chrono::Utc::now().to_string(),
             card_to_play: CardType::King,
             chat: Chat::new(game_id),
             claims: vec![],
             round_number: 1,
+            turn_deadline: None,
+            challenge_deadline: None,
+            current_claim_id: None,
+            settings: GameSettings::default(),
+            sequence: 0,
+            deck_seed: None,
+            card_counts: CardCounts::default(),
+            archived_at: None,
+            join_code_hash: None,
         }
     }
 
@@ -106,17 +177,85 @@ impl Game {
     pub fn from_ref(game: &Game) -> Self {
         Game {
             id: game.id.clone(),
+            title: game.title.clone(),
             players: game.players.clone(),
             which_player_turn: game.which_player_turn.clone(),
             state: game.state.clone(),
+            phase: game.phase.clone(),
             started_at: game.started_at.clone(),
             card_to_play: game.card_to_play.clone(),
             chat: game.chat.clone(),
             claims: game.claims.clone(),
             round_number: game.round_number,
+            turn_deadline: game.turn_deadline.clone(),
+            challenge_deadline: game.challenge_deadline.clone(),
+            current_claim_id: game.current_claim_id.clone(),
+            settings: game.settings.clone(),
+            sequence: game.sequence,
+            deck_seed: game.deck_seed,
+            card_counts: game.card_counts.clone(),
+            archived_at: game.archived_at.clone(),
+            join_code_hash: game.join_code_hash.clone(),
         }
     }
 
+    /// Checks whether the game was archived and can't be fetched anymore.
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    /// Checks whether a claim is the current claim of the game, the only one that can be
+    /// challenged.
+    ///
+    /// # Arguments
+    ///
+    /// - `claim_id` -> Identifier of the claim.
+    pub fn is_current_claim(&self, claim_id: &str) -> bool {
+        self.current_claim_id.as_deref() == Some(claim_id)
+    }
+
+    /// Checks whether the game can only be joined with a join code.
+    pub fn is_private(&self) -> bool {
+        self.join_code_hash.is_some()
+    }
+
+    /// Returns the title a game gets when it is created, e.g. 'Game 1a2b3c4d'.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the game, only its first 8 characters are used.
+    pub fn default_title(game_id: &str) -> String {
+        format!("Game {}", game_id.chars().take(8).collect::<String>())
+    }
+
+    /// Hides the hands of all other players from the requesting player.
+    ///
+    /// The `assigned_cards` of every other player are replaced by their `number_of_cards`, so
+    /// nobody can see the exact cards of the opponents. The requesting player keeps their own
+    /// cards.
+    ///
+    /// # Arguments
+    ///
+    /// - `player_id` -> Identifier of the player the game is sent to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lue_lue_game::shared::Game;
+    ///
+    /// let game = Game::new().redact_for("player-id");
+    /// assert!(game.players.is_empty());
+    /// ```
+    pub fn redact_for(mut self, player_id: &str) -> Self {
+        self.players = self
+            .players
+            .into_iter()
+            .map(|player| player.redact_for(player_id))
+            .collect();
+
+        self
+    }
+
     cfg_if! {
         if #[cfg(feature = "ssr")] {
     /// Prepares a Game for it's next round.
@@ -168,15 +307,22 @@ impl Debug for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "Game {{ id: {}, players: {:?}, which_player_turn: {}, state: {:?}, started_at: {}, round_number: {}, card_to_play: {:?}, claims: {:?} }}",
+            "Game {{ id: {}, title: {}, players: {:?}, which_player_turn: {}, state: {:?}, phase: {:?}, started_at: {}, round_number: {}, card_to_play: {:?}, claims: {:?}, turn_deadline: {:?}, challenge_deadline: {:?}, current_claim_id: {:?}, settings: {:?}, archived_at: {:?} }}",
             self.id,
+            self.title,
             self.players,
             self.which_player_turn,
             self.state,
+            self.phase,
             self.started_at,
             self.round_number,
             self.card_to_play,
-            self.claims
+            self.claims,
+            self.turn_deadline,
+            self.challenge_deadline,
+            self.current_claim_id,
+            self.settings,
+            self.archived_at
         )
     }
 }
@@ -210,7 +356,7 @@ impl IntoResponse for Game {
 /// - `round_number` -> New round number of a Game
 /// - `chat` -> Potentially new chat instance
 /// - `card_to_play` -> Changes after every made round
-/// - `claims` -> List of claims in the current round
+/// - `claims` -> Claims to add, an empty list deletes all claims and `None` keeps them
 #[derive(Deserialize, Debug, Clone)]
 pub struct UpdateGameDTO {
     /// Identifier of the game is always needed.
@@ -221,6 +367,10 @@ pub struct UpdateGameDTO {
     pub which_player_turn: Option<String>,
     /// Optional new game state of the game
     pub state: Option<GameState>,
+    /// Optional new phase of the current round
+    ///
+    /// Isn't part of the constructor, set it on the created DTO when needed.
+    pub phase: Option<GamePhase>,
     /// Optional new round number
     ///
     /// Starts by 1 and increments by 1
@@ -229,14 +379,13 @@ pub struct UpdateGameDTO {
     pub chat: Option<Chat>,
     /// Optional mutated card to play in the current round
     pub card_to_play: Option<CardType>,
-    /// Optional array of one or no claim that will be added to the claims list of a game
-    ///
-    /// Can be empty or have one element.
+    /// Optional list of claims that will be added to the claims list of a game
     ///
-    /// If there is one element, it will be added to the claims list of a game.
-    ///
-    /// If the array is empty, all claims in the list of the game will be deleted.
-    pub claims: Option<[Claim; 1]>,
+    /// - `None` (the field is missing or `null`) -> The claims of the game stay unchanged.
+    /// - `Some` with claims -> The claims are added to the claims list of the game.
+    /// - `Some` with an empty list -> All claims of the game are deleted. A client has to send
+    ///   `[]` explicitly, so it can't wipe the claims by accident.
+    pub claims: Option<Vec<Claim>>,
 }
 
 impl UpdateGameDTO {
@@ -255,13 +404,14 @@ impl UpdateGameDTO {
         round_number: Option<usize>,
         chat: Option<Chat>,
         card_to_play: Option<CardType>,
-        claims: Option<[Claim; 1]>,
+        claims: Option<Vec<Claim>>,
     ) -> Self {
         UpdateGameDTO {
             id,
             players,
             which_player_turn,
             state,
+            phase: None,
             round_number,
             chat,
             card_to_play,
@@ -270,17 +420,202 @@ impl UpdateGameDTO {
     }
 }
 
+impl UpdateGameDTO {
+    /// Checks whether the update doesn't contain any field besides the `id` of the game.
+    pub fn is_empty(&self) -> bool {
+        self.players.is_none()
+            && self.which_player_turn.is_none()
+            && self.state.is_none()
+            && self.phase.is_none()
+            && self.round_number.is_none()
+            && self.chat.is_none()
+            && self.card_to_play.is_none()
+            && self.claims.is_none()
+    }
+
+    /// Detects which of the provided fields differ from the current state of the game.
+    ///
+    /// Players and chat messages are compared by their ids. Provided claims count as changed,
+    /// because they are added, unless an empty list clears a game without claims.
+    ///
+    /// # Arguments
+    ///
+    /// - `current` -> The game as it is currently stored.
+    ///
+    /// # Returns
+    ///
+    /// -> ***`ChangeSet`*** with the names of the fields that will change
+    pub fn changes_against(&self, current: &Game) -> ChangeSet {
+        let mut changes = ChangeSet::new();
+
+        if let Some(players) = &self.players {
+            let same_players = players.len() == current.players.len()
+                && players
+                    .iter()
+                    .all(|player| current.players.iter().any(|p| p.id == player.id));
+            if !same_players {
+                changes.mark("players");
+            }
+        }
+        if self
+            .which_player_turn
+            .as_ref()
+            .is_some_and(|player_id| *player_id != current.which_player_turn)
+        {
+            changes.mark("which_player_turn");
+        }
+        if self.state.as_ref().is_some_and(|state| *state != current.state) {
+            changes.mark("state");
+        }
+        if self.phase.as_ref().is_some_and(|phase| *phase != current.phase) {
+            changes.mark("phase");
+        }
+        if self
+            .round_number
+            .is_some_and(|round| round != current.round_number)
+        {
+            changes.mark("round_number");
+        }
+        if let Some(chat) = &self.chat {
+            let same_messages = chat.messages.len() == current.chat.messages.len()
+                && chat
+                    .messages
+                    .iter()
+                    .zip(current.chat.messages.iter())
+                    .all(|(new, old)| new.id == old.id);
+            if !same_messages {
+                changes.mark("chat");
+            }
+        }
+        if self
+            .card_to_play
+            .as_ref()
+            .is_some_and(|card| card.index() != current.card_to_play.index())
+        {
+            changes.mark("card_to_play");
+        }
+        if self
+            .claims
+            .as_ref()
+            .is_some_and(|claims| !claims.is_empty() || !current.claims.is_empty())
+        {
+            changes.mark("claims");
+        }
+
+        changes
+    }
+
+    /// Validates the state and phase changes against the current game.
+    ///
+    /// Unchanged values are always valid. The phase can only change while the game is (or
+    /// becomes) `InProgress`.
+    ///
+    /// # Arguments
+    ///
+    /// - `current` -> The game as it is currently stored.
+    ///
+    /// # Returns
+    ///
+    /// -> ***`Err(String)`*** describing the first invalid transition
+    pub fn validate_transitions(&self, current: &Game) -> Result<(), String> {
+        if let Some(state) = &self.state {
+            if *state != current.state && !current.state.can_transition_to(state) {
+                return Err(format!(
+                    "The state of a game can't change from '{}' to '{}'!",
+                    current.state, state
+                ));
+            }
+        }
+
+        if let Some(phase) = &self.phase {
+            if *phase != current.phase {
+                let resulting_state = self.state.as_ref().unwrap_or(&current.state);
+                if *resulting_state != GameState::InProgress {
+                    return Err(format!(
+                        "The phase of a game can only change while it's in progress, not while it's '{}'!",
+                        resulting_state
+                    ));
+                }
+                if !current.phase.can_transition_to(phase) {
+                    return Err(format!(
+                        "The phase of a round can't change from '{}' to '{}'!",
+                        current.phase, phase
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the round number against the current game.
+    ///
+    /// A round number can only stay the same or increase by one, so no round is skipped and the
+    /// history of the game isn't rewritten. Use `GameRepository::start_next_round` to start a new
+    /// round without knowing the current number.
+    ///
+    /// # Arguments
+    ///
+    /// - `current` -> The game as it is currently stored.
+    ///
+    /// # Returns
+    ///
+    /// -> ***`Err(String)`*** describing why the round number is invalid
+    pub fn validate_round_number(&self, current: &Game) -> Result<(), String> {
+        match self.round_number {
+            Some(round) if round != current.round_number && round != current.round_number + 1 => {
+                Err(format!(
+                    "The round number of a game can only stay at {} or increase to {}, not change to {}!",
+                    current.round_number,
+                    current.round_number + 1,
+                    round
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validates that the turn is passed to a player of the game.
+    ///
+    /// When the DTO replaces the players as well, the player has to be one of the new players.
+    ///
+    /// # Arguments
+    ///
+    /// - `current` -> The game as it is currently stored.
+    ///
+    /// # Returns
+    ///
+    /// -> ***`Err(String)`*** describing why the player can't have the turn
+    pub fn validate_turn(&self, current: &Game) -> Result<(), String> {
+        let player_id = match &self.which_player_turn {
+            Some(player_id) => player_id,
+            None => return Ok(()),
+        };
+
+        let players = self.players.as_ref().unwrap_or(&current.players);
+        if players.iter().any(|player| player.id == *player_id) {
+            Ok(())
+        } else {
+            Err(format!(
+                "The turn can't be passed to the player ['{}'], who isn't part of the game ['{}']!",
+                player_id, current.id
+            ))
+        }
+    }
+}
+
 impl Display for UpdateGameDTO {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
             "Id: {}, Players: {:?}, Id of Player who needs to make a claim: {:?},
-                Game State: {:?}, Round: {:?}, 
+                Game State: {:?}, Phase: {:?}, Round: {:?}, 
                 Chat: {:?}, Card to Play: {:?},  Claims: {:?}",
             self.id,
             self.players,
             self.which_player_turn,
             self.state,
+            self.phase,
             self.round_number,
             self.chat,
             self.card_to_play,
@@ -291,5 +626,276 @@ impl Display for UpdateGameDTO {
 
 
 impl<'a> ErrorObject<'a> for UpdateGameDTO {}
+
+/// Request of a host to rename a game.
+///
+/// # Props
+///
+/// - `title` -> New title of the game
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RenameGameRequest {
+    /// New title of the game
+    pub title: String,
+}
+
+impl RenameGameRequest {
+    /// Returns the trimmed title, when it isn't empty and not longer than `MAX_TITLE_LENGTH`.
+    pub fn validated_title(&self) -> Option<String> {
+        match title_error(&self.title) {
+            Some(_) => None,
+            None => Some(self.title.trim().to_string()),
+        }
+    }
+
+    /// Checks all fields of the request at once.
+    ///
+    /// # Error
+    ///
+    /// Returns a `ValidationError` listing the `title` when it's empty or too long.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut field_errors = BTreeMap::new();
+        if let Some(reason) = title_error(&self.title) {
+            field_errors.insert("title".to_string(), reason);
+        }
+
+        ValidationError::check_fields(field_errors)
+    }
+}
+
+/// Describes why a game title can't be used, `None` when it's valid.
+fn title_error(title: &str) -> Option<String> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        return Some(format!("A game title must have between 1 and {} characters!", MAX_TITLE_LENGTH));
+    }
+    None
+}
+
+impl Display for RenameGameRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Title: {}", self.title)
+    }
+}
+
+impl<'a> ErrorObject<'a> for RenameGameRequest {}
+
+/// Request of a host to pass the host role on to another player of the game.
+///
+/// # Props
+///
+/// - `player_id` -> Identifier of the player who becomes the new host
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TransferHostRequest {
+    /// Identifier of the new host
+    pub player_id: String,
+}
+
+impl Display for TransferHostRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "New Host Id: {}", self.player_id)
+    }
+}
+
+impl<'a> ErrorObject<'a> for TransferHostRequest {}
+
+/// Lightweight overview of a game, e.g. for badges in the UI.
+///
+/// # Props
+///
+/// - `game_id` -> Identifier of the game.
+/// - `title` -> Human-friendly title of the game.
+/// - `state` -> Current state of the game.
+/// - `number_of_claims` -> Number of claims on the stack.
+#[derive(Serialize, Debug, Clone)]
+pub struct GameSummary {
+    /// Identifier of the game
+    pub game_id: String,
+    /// Human-friendly title of the game
+    pub title: String,
+    /// Current state of the game
+    pub state: GameState,
+    /// Number of claims on the stack
+    pub number_of_claims: usize,
+}
+
+/// Request to create a new game.
+///
+/// # Props
+///
+/// - `title` -> Optional title of the game, `Game::default_title` is used when it's missing
+/// - `settings` -> Rules of the game, the standard rules when they are missing
+/// - `deck_seed` -> Optional seed of the shuffled deck to reproduce the deals of a game
+/// - `join_code` -> Optional code the players need to join, makes the game private
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CreateGameRequest {
+    /// Title of the game
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Rules of the game
+    #[serde(default)]
+    pub settings: GameSettings,
+    /// Seed of the shuffled deck
+    #[serde(default)]
+    pub deck_seed: Option<u32>,
+    /// Code the players need to join the game
+    #[serde(default)]
+    pub join_code: Option<String>,
+}
+
+impl CreateGameRequest {
+    /// Returns the trimmed title, or the default title of the game when none was given.
+    ///
+    /// Is `None` when the given title is empty or longer than `MAX_TITLE_LENGTH`.
+    ///
+    /// # Arguments
+    ///
+    /// - `game_id` -> Identifier of the new game, used for the default title.
+    pub fn validated_title(&self, game_id: &str) -> Option<String> {
+        match &self.title {
+            Some(title) => match title_error(title) {
+                Some(_) => None,
+                None => Some(title.trim().to_string()),
+            },
+            None => Some(Game::default_title(game_id)),
+        }
+    }
+
+    /// Checks all fields of the request at once.
+    ///
+    /// # Error
+    ///
+    /// Returns a `ValidationError` listing the `title` when it's given but empty or too long,
+    /// `settings.auto_start_at` when it isn't between 2 and `max_players` and the `join_code`
+    /// when it's given but empty.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_players` -> Maximum number of players of a game, see `Config`.
+    pub fn validate(&self, max_players: usize) -> Result<(), ValidationError> {
+        let mut field_errors = BTreeMap::new();
+        if let Some(reason) = self.title.as_deref().and_then(title_error) {
+            field_errors.insert("title".to_string(), reason);
+        }
+        if let Some(auto_start_at) = self.settings.auto_start_at {
+            if !(2..=max_players).contains(&auto_start_at) {
+                field_errors.insert(
+                    "settings.auto_start_at".to_string(),
+                    format!("A game can only start by itself with 2 to {} players!", max_players),
+                );
+            }
+        }
+        if self.join_code.as_deref().is_some_and(|join_code| join_code.trim().is_empty()) {
+            field_errors.insert("join_code".to_string(), "The join code of a game can't be empty!".to_string());
+        }
+
+        ValidationError::check_fields(field_errors)
+    }
+}
+
+impl Display for CreateGameRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Title: {}", self.title.as_deref().unwrap_or_default())
+    }
+}
+
+impl<'a> ErrorObject<'a> for CreateGameRequest {}
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    /// Returns the names of the fields a `ValidationError` lists.
+    fn invalid_fields(result: Result<(), ValidationError>) -> Vec<String> {
+        result.err().map(|err| err.field_errors.into_keys().collect()).unwrap_or_default()
+    }
+
+    #[test]
+    fn only_the_current_claim_can_be_challenged() {
+        let mut game = Game::new();
+        assert!(!game.is_current_claim("first"));
+
+        game.current_claim_id = Some("second".to_string());
+        assert!(game.is_current_claim("second"));
+        assert!(!game.is_current_claim("first"));
+    }
+
+    #[test]
+    fn rename_request_only_needs_a_valid_title() {
+        let request: RenameGameRequest = serde_json::from_str(r#"{ "title": " New title " }"#).unwrap();
+
+        assert!(request.validate().is_ok());
+        assert_eq!(request.validated_title().as_deref(), Some("New title"));
+    }
+
+    #[test]
+    fn rename_request_rejects_empty_and_long_titles() {
+        let empty = RenameGameRequest { title: "   ".to_string() };
+        let long = RenameGameRequest { title: "x".repeat(MAX_TITLE_LENGTH + 1) };
+
+        assert_eq!(invalid_fields(empty.validate()), vec!["title"]);
+        assert_eq!(invalid_fields(long.validate()), vec!["title"]);
+    }
+
+    #[test]
+    fn an_update_needs_at_least_one_field() {
+        let empty: UpdateGameDTO = serde_json::from_str(r#"{ "id": "game" }"#).unwrap();
+        let renumbered: UpdateGameDTO = serde_json::from_str(r#"{ "id": "game", "round_number": 2 }"#).unwrap();
+        let cleared: UpdateGameDTO = serde_json::from_str(r#"{ "id": "game", "claims": [] }"#).unwrap();
+
+        assert!(empty.is_empty());
+        assert!(!renumbered.is_empty());
+        assert!(!cleared.is_empty());
+    }
+
+    #[test]
+    fn an_update_with_an_unknown_state_is_rejected() {
+        let out_of_range = GameState::number_of_values();
+
+        assert!(GameState::from_usize(out_of_range).is_none());
+        assert!(serde_json::from_str::<UpdateGameDTO>(&format!(r#"{{ "id": "game", "state": {} }}"#, out_of_range)).is_err());
+        assert!(serde_json::from_str::<UpdateGameDTO>(r#"{ "id": "game", "state": "Paused" }"#).is_err());
+    }
+
+    #[test]
+    fn every_state_is_stored_with_a_known_index() {
+        let states = [
+            GameState::InProgress,
+            GameState::Ended,
+            GameState::WaitingForPlayers,
+            GameState::Starting,
+            GameState::Aborted,
+        ];
+        assert_eq!(states.len(), GameState::number_of_values());
+
+        for state in states {
+            assert_eq!(GameState::from_usize(state.index()), Some(state));
+        }
+    }
+
+    #[test]
+    fn a_create_request_lists_all_invalid_fields_at_once() {
+        let request: CreateGameRequest = serde_json::from_str(
+            r#"{ "title": " ", "settings": { "auto_start_at": 9 }, "join_code": "" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            invalid_fields(request.validate(MAX_PLAYERS)),
+            vec!["join_code", "settings.auto_start_at", "title"]
+        );
+    }
+
+    #[test]
+    fn a_game_with_a_join_code_is_private() {
+        let request: CreateGameRequest = serde_json::from_str(r#"{ "join_code": "secret" }"#).unwrap();
+        assert!(request.validate(MAX_PLAYERS).is_ok());
+
+        let mut game = Game::new();
+        assert!(!game.is_private());
+
+        game.join_code_hash = Some("hash".to_string());
+        assert!(game.is_private());
     }
 }
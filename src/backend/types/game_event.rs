@@ -0,0 +1,73 @@
+use cfg_if::cfg_if;
+
+use std::fmt::Display;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::backend::errors::application_error::ErrorObject;
+    }
+}
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::backend::enums::game_event_kind::GameEventKind;
+
+/// A single recorded event of a game, e.g. a placed claim or a challenge.
+///
+/// The events of a game are numbered with a `sequence` that starts at 1, so a replay viewer can
+/// load them step by step from the last event it has seen.
+///
+/// # Properties
+///
+/// - game_id: The ID of the game the event happened in.
+/// - sequence: Position of the event in the log of the game.
+/// - kind: What happened.
+/// - player_id: The ID of the player who caused the event, if any.
+/// - round_number: The round in which the event happened.
+/// - details: Additional data depending on the kind, e.g. the announced rank of a claim.
+/// - created_at: Point in time when the event was recorded.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GameEvent {
+    /// The ID of the game the event happened in.
+    pub game_id: String,
+    /// Position of the event in the log of the game.
+    pub sequence: usize,
+    /// What happened.
+    pub kind: GameEventKind,
+    /// The ID of the player who caused the event, if any.
+    #[serde(default)]
+    pub player_id: Option<String>,
+    /// The round in which the event happened.
+    pub round_number: usize,
+    /// Additional data depending on the kind.
+    #[serde(default, deserialize_with = "deserialize_details")]
+    pub details: Value,
+    /// Point in time when the event was recorded.
+    pub created_at: String,
+}
+
+/// Reads the details either from the JSON text of the `details` column or from a JSON object.
+///
+/// Text that can't be parsed is kept as a string, so a broken row doesn't hide the event.
+fn deserialize_details<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(text) => serde_json::from_str(&text).unwrap_or(Value::String(text)),
+        details => details,
+    })
+}
+
+impl Display for GameEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "GameEvent Game: {}, Sequence: {}, Kind: {}, Player: {:?}, Round: {}",
+            self.game_id, self.sequence, self.kind, self.player_id, self.round_number
+        )
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        impl<'a> ErrorObject<'a> for GameEvent {}
+    }
+}
@@ -0,0 +1,216 @@
+// Explicit operations that modify a game, as an alternative to the diffing of `UpdateGameDTO`.
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::collections::HashMap;
+
+        use crate::backend::types::{
+            chat::{message_length, MAX_MESSAGE_LENGTH},
+            claim::MAX_CARDS_PER_CLAIM,
+            game::Game,
+        };
+    }
+}
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::enums::{card_types::CardType, game_state::GameState};
+
+/// A single modification of a game.
+///
+/// Operations are sent as a list and applied in their order in one batch, so either all or none
+/// of them are stored. Unlike the `UpdateGameDTO` nothing is derived from comparing lists: every
+/// addition and removal is stated explicitly.
+///
+/// In JSON the variant is selected by the `op` field, e.g.
+/// `{ "op": "SetTurn", "player_id": "..." }`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "op")]
+pub enum GameOperation {
+    /// Adds a new player with the given name to the game.
+    AddPlayer { name: String },
+    /// Removes a player from the game, the cards in their hand return to the deck.
+    RemovePlayer { player_id: String },
+    /// Places a claim with cards from the hand of the player on the stack.
+    AddClaim {
+        created_by: String,
+        announced_rank: CardType,
+        card_ids: Vec<String>,
+    },
+    /// Removes a claim from the stack, its cards return to the hand of the player who placed it.
+    RemoveClaim { claim_id: String },
+    /// Sends a message in the chat of the game.
+    AddMessage { player_id: String, content: String },
+    /// Removes a message from the chat of the game.
+    RemoveMessage { message_id: String },
+    /// Passes the turn to a player.
+    SetTurn { player_id: String },
+    /// Changes the state of the game.
+    SetState { state: GameState },
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+/// Checks that a list of operations can be applied to a game in the given order.
+///
+/// The operations are simulated on the ids of the players, claims, messages and hands of the
+/// game, so e.g. a turn can't be passed to a player who is removed by an earlier operation.
+/// Players added in the same list get their id when they are stored, therefore later operations
+/// can't refer to them.
+///
+/// # Arguments
+///
+/// - `game` -> The game as it is currently stored.
+/// - `operations` -> The operations in the order they will be applied.
+/// - `max_players` -> Maximum number of players of a game, see `Config`.
+///
+/// # Returns
+///
+/// -> ***`Err(String)`*** describing the first invalid operation and its position
+pub fn validate_operations(game: &Game, operations: &[GameOperation], max_players: usize) -> Result<(), String> {
+    let mut number_of_players = game.players.len();
+    let mut hands: HashMap<&str, Vec<&str>> = game
+        .players
+        .iter()
+        .map(|player| {
+            (
+                player.id.as_str(),
+                player.assigned_cards.iter().map(|card| card.id.as_str()).collect(),
+            )
+        })
+        .collect();
+    let mut claims: Vec<&str> = game.claims.iter().map(|claim| claim.id.as_str()).collect();
+    let mut messages: Vec<&str> = game.chat.messages.iter().map(|message| message.id.as_str()).collect();
+    let mut state = game.state.clone();
+
+    for (index, operation) in operations.iter().enumerate() {
+        let invalid = |reason: String| Err(format!("Operation {} ({:?}) is invalid: {}", index, operation, reason));
+
+        match operation {
+            GameOperation::AddPlayer { name } => {
+                if name.trim().is_empty() {
+                    return invalid("The name of a player can't be empty!".to_string());
+                }
+                if number_of_players >= max_players {
+                    return invalid(format!("A game can't have more than {} players!", max_players));
+                }
+                number_of_players += 1;
+            }
+            GameOperation::RemovePlayer { player_id } => {
+                if hands.remove(player_id.as_str()).is_none() {
+                    return invalid(format!("The player ['{}'] isn't part of the game!", player_id));
+                }
+                number_of_players -= 1;
+            }
+            GameOperation::AddClaim { created_by, card_ids, .. } => {
+                if card_ids.is_empty() || card_ids.len() > MAX_CARDS_PER_CLAIM {
+                    return invalid(format!("A claim must contain between 1 and {} cards!", MAX_CARDS_PER_CLAIM));
+                }
+                let hand = match hands.get_mut(created_by.as_str()) {
+                    Some(hand) => hand,
+                    None => return invalid(format!("The player ['{}'] isn't part of the game!", created_by)),
+                };
+                for card_id in card_ids {
+                    match hand.iter().position(|id| id == card_id) {
+                        Some(position) => {
+                            hand.remove(position);
+                        }
+                        None => {
+                            return invalid(format!(
+                                "The card ['{}'] isn't in the hand of the player ['{}']!",
+                                card_id, created_by
+                            ))
+                        }
+                    }
+                }
+            }
+            GameOperation::RemoveClaim { claim_id } => match claims.iter().position(|id| id == claim_id) {
+                Some(position) => {
+                    claims.remove(position);
+                }
+                None => return invalid(format!("The claim ['{}'] isn't on the stack of the game!", claim_id)),
+            },
+            GameOperation::AddMessage { player_id, content } => {
+                if !hands.contains_key(player_id.as_str()) {
+                    return invalid(format!("The player ['{}'] isn't part of the game!", player_id));
+                }
+                if content.trim().is_empty() {
+                    return invalid("A message can't be empty!".to_string());
+                }
+                if message_length(content) > MAX_MESSAGE_LENGTH {
+                    return invalid(format!("A message can't be longer than {} characters!", MAX_MESSAGE_LENGTH));
+                }
+            }
+            GameOperation::RemoveMessage { message_id } => match messages.iter().position(|id| id == message_id) {
+                Some(position) => {
+                    messages.remove(position);
+                }
+                None => return invalid(format!("The message ['{}'] isn't part of the chat of the game!", message_id)),
+            },
+            GameOperation::SetTurn { player_id } => {
+                if !hands.contains_key(player_id.as_str()) {
+                    return invalid(format!("The player ['{}'] isn't part of the game!", player_id));
+                }
+            }
+            GameOperation::SetState { state: next } => {
+                if *next != state && !state.can_transition_to(next) {
+                    return invalid(format!("The state of a game can't change from '{}' to '{}'!", state, next));
+                }
+                state = next.clone();
+            }
+        }
+    }
+
+    Ok(())
+}
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::backend::{
+        enums::player_color::PlayerColor,
+        types::{card::Card, player::Player},
+    };
+
+    /// Creates a game with two players holding one king each.
+    fn game() -> Game {
+        let mut game = Game::new();
+        for name in ["Alice", "Bob"] {
+            let mut player = Player::new(name.to_string(), game.id.clone(), PlayerColor::default());
+            player.assigned_cards.push(Card::new(CardType::King));
+            game.players.push(player);
+        }
+        game
+    }
+
+    #[test]
+    fn a_claim_can_only_contain_cards_of_its_creator() {
+        let game = game();
+        let claim = |card_id: &str| GameOperation::AddClaim {
+            created_by: game.players[0].id.clone(),
+            announced_rank: CardType::King,
+            card_ids: vec![card_id.to_string()],
+        };
+
+        let own_card = game.players[0].assigned_cards[0].id.clone();
+        let foreign_card = game.players[1].assigned_cards[0].id.clone();
+        assert!(validate_operations(&game, &[claim(&own_card)], 6).is_ok());
+        assert!(validate_operations(&game, &[claim(&foreign_card)], 6).is_err());
+        assert!(validate_operations(&game, &[claim(&own_card), claim(&own_card)], 6).is_err());
+    }
+
+    #[test]
+    fn a_removed_player_can_not_be_referred_to_later() {
+        let game = game();
+        let player_id = game.players[1].id.clone();
+        let operations = [
+            GameOperation::RemovePlayer { player_id: player_id.clone() },
+            GameOperation::SetTurn { player_id },
+        ];
+
+        assert!(validate_operations(&game, &operations, 6).is_err());
+    }
+}
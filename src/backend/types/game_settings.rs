@@ -0,0 +1,60 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The rules a game is played with, chosen when the game is created.
+///
+/// Is stored as JSON text in the `settings` column of the `games` table. Games created before
+/// the column existed have no settings and are played with the standard rules, see `Default`.
+///
+/// # Props
+///
+/// - `allow_draw` -> Whether a player can draw a card from the deck instead of placing a claim.
+/// - `allow_late_join` -> Whether players can join while the game is in progress.
+/// - `auto_start_at` -> Optional number of players at which a waiting game starts and deals by
+///   itself, once all of them are ready.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct GameSettings {
+    /// Whether a player can draw a card from the deck instead of placing a claim
+    #[serde(default)]
+    pub allow_draw: bool,
+    /// Whether players can join while the game is in progress, they are dealt a hand from the
+    /// deck
+    #[serde(default)]
+    pub allow_late_join: bool,
+    /// Number of players at which a game that is waiting for players starts by itself when all of
+    /// them are ready, otherwise the host starts it
+    #[serde(default)]
+    pub auto_start_at: Option<usize>,
+}
+
+impl GameSettings {
+    /// Reads the settings of a game either from the JSON text of the `settings` column or from
+    /// a JSON object sent by a client.
+    ///
+    /// A missing value or text that can't be parsed results in the standard rules, so a broken
+    /// row doesn't make the whole game unreadable.
+    pub fn deserialize_column<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameSettings, D::Error> {
+        match Option::<StoredSettings>::deserialize(deserializer)? {
+            Some(StoredSettings::Text(text)) => Ok(serde_json::from_str(&text).unwrap_or_else(|err| {
+                log::warn!("The stored settings '{}' of a game are invalid, the standard rules are used: {}", text, err);
+                GameSettings::default()
+            })),
+            Some(StoredSettings::Object(settings)) => Ok(settings),
+            None => Ok(GameSettings::default()),
+        }
+    }
+
+    /// Converts the settings into the JSON text stored in the `settings` column.
+    pub fn to_column(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// The ways the settings of a game can arrive, from the database or from a client.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredSettings {
+    /// JSON text of the `settings` column.
+    Text(String),
+    /// A JSON object sent by a client.
+    Object(GameSettings),
+}
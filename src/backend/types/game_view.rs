@@ -0,0 +1,287 @@
+// The representation of a game that is sent to the clients.
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    enums::{
+        card_types::CardType, claim_outcome::ClaimOutcome, game_phase::GamePhase,
+        game_state::GameState, player_color::PlayerColor,
+    },
+    logic::game_logic::max_possible_of_rank,
+    types::{
+        card::Card,
+        claim::Claim,
+        game::Game,
+        game_settings::GameSettings,
+        player::{Player, PLAYER_ONLINE_THRESHOLD_SECONDS},
+    },
+};
+
+/// A game like it's sent to the clients.
+///
+/// Unlike `Game` the shape doesn't follow the database tables: the chat is reduced to its id and
+/// number of messages, and hidden cards are left out instead of being sent as empty lists. So
+/// schema changes don't leak into the API.
+///
+/// # JSON shape
+///
+/// ```json
+/// {
+///   "id": "...", "title": "...", "is_private": false,
+///   "settings": { "allow_draw": false, "allow_late_join": false, "auto_start_at": null },
+///   "state": "InProgress", "phase": "Playing",
+///   "round_number": 1, "card_to_play": "King", "which_player_turn": "...",
+///   "turn_deadline": "...", "challenge_deadline": null, "current_claim_id": null, "sequence": 42,
+///   "pile_size": 4, "deck_remaining": 0,
+///   "chat_id": "...", "number_of_messages": 3,
+///   "players": [{ "id": "...", "name": "...", "color": "Red", "score": 0,
+///                 "forfeited": false, "online": true, "number_of_cards": 5, "cards": null }],
+///   "claims": [{ "id": "...", "created_by": "...", "announced_rank": "King",
+///                "number_of_cards": 2, "outcome": "Unchallenged", "challenged_by": null,
+///                "impossible": false, "cards": null }]
+/// }
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GameView {
+    /// Unique identifier of the game
+    pub id: String,
+    /// Human-friendly title of the game
+    pub title: String,
+    /// Whether the game needs a join code and is hidden from the lobby
+    pub is_private: bool,
+    /// Rules the game is played with, the standard rules for games created before there were
+    /// settings
+    pub settings: GameSettings,
+    /// Lifecycle state of the game
+    pub state: GameState,
+    /// Phase of the current round
+    pub phase: GamePhase,
+    /// Number of the current round
+    pub round_number: usize,
+    /// Rank that has to be played in the current turn
+    pub card_to_play: CardType,
+    /// ID of the player whose turn it is
+    pub which_player_turn: String,
+    /// Point in time until the current player has to make a move
+    pub turn_deadline: Option<String>,
+    /// Point in time until the last claim can be challenged
+    pub challenge_deadline: Option<String>,
+    /// ID of the claim that can be challenged now
+    pub current_claim_id: Option<String>,
+    /// Number of changes of the game so far, a gap means the client missed an update
+    pub sequence: usize,
+    /// Number of cards in the central pile, placed in claims
+    pub pile_size: usize,
+    /// Number of cards that weren't dealt yet
+    pub deck_remaining: usize,
+    /// ID of the chat of the game
+    pub chat_id: String,
+    /// Number of messages in the chat
+    pub number_of_messages: usize,
+    /// Players of the game in the order they joined
+    pub players: Vec<PlayerView>,
+    /// Claims on the stack, the oldest first
+    pub claims: Vec<ClaimView>,
+}
+
+/// A player like it's sent to the clients.
+///
+/// `cards` is only set for the player who receives the view, or for everybody in the full view.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PlayerView {
+    /// Unique identifier of the player
+    pub id: String,
+    /// Name of the player
+    pub name: String,
+    /// Color the player is shown with
+    pub color: PlayerColor,
+    /// Score of the player
+    pub score: usize,
+    /// Whether the player forfeited the current round
+    pub forfeited: bool,
+    /// Whether the player sent a request within the last `PLAYER_ONLINE_THRESHOLD_SECONDS`
+    pub online: bool,
+    /// Number of cards in the hand of the player
+    pub number_of_cards: usize,
+    /// Cards in the hand of the player, if they can be seen
+    pub cards: Option<Vec<Card>>,
+}
+
+/// A claim like it's sent to the clients.
+///
+/// `cards` is only set once the claim was challenged and its cards were revealed, or in the full
+/// view.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ClaimView {
+    /// Unique identifier of the claim
+    pub id: String,
+    /// ID of the player who placed the claim
+    pub created_by: String,
+    /// Rank the player announced
+    pub announced_rank: CardType,
+    /// Number of announced cards
+    pub number_of_cards: usize,
+    /// What happened with the claim
+    pub outcome: ClaimOutcome,
+    /// ID of the player who challenged the claim
+    pub challenged_by: Option<String>,
+    /// Whether the game has fewer cards of the announced rank than the claim contains, so it
+    /// can only be a bluff
+    pub impossible: bool,
+    /// The placed cards, if they can be seen
+    pub cards: Option<Vec<Card>>,
+}
+
+impl GameView {
+    /// Creates the view of a game for a single player.
+    ///
+    /// Only the own cards of the player and the cards of challenged claims are visible.
+    ///
+    /// # Arguments
+    ///
+    /// - `game` -> The game loaded from the database.
+    /// - `player_id` -> Identifier of the player the view is sent to.
+    pub fn for_player(game: Game, player_id: &str) -> Self {
+        GameView::from_game(game, Some(player_id))
+    }
+
+    /// Creates the view of a game with all cards visible, e.g. for the host or spectators.
+    ///
+    /// # Arguments
+    ///
+    /// - `game` -> The game loaded from the database.
+    pub fn full(game: Game) -> Self {
+        GameView::from_game(game, None)
+    }
+
+    /// Converts a game, `viewer_id` is `None` when all cards are visible.
+    fn from_game(game: Game, viewer_id: Option<&str>) -> Self {
+        let impossible_claims: Vec<bool> = game
+            .claims
+            .iter()
+            .map(|claim| claim.number_of_cards > max_possible_of_rank(&game, &claim.announced_rank))
+            .collect();
+
+        let is_private = game.is_private();
+
+        GameView {
+            id: game.id,
+            is_private,
+            title: game.title,
+            settings: game.settings,
+            state: game.state,
+            phase: game.phase,
+            round_number: game.round_number,
+            card_to_play: game.card_to_play,
+            which_player_turn: game.which_player_turn,
+            turn_deadline: game.turn_deadline,
+            challenge_deadline: game.challenge_deadline,
+            current_claim_id: game.current_claim_id,
+            sequence: game.sequence,
+            pile_size: game.card_counts.pile_size,
+            deck_remaining: game.card_counts.deck_remaining,
+            chat_id: game.chat.id,
+            number_of_messages: game.chat.number_of_messages,
+            players: game
+                .players
+                .into_iter()
+                .map(|player| PlayerView::from_player(player, viewer_id))
+                .collect(),
+            claims: game
+                .claims
+                .into_iter()
+                .zip(impossible_claims)
+                .map(|(claim, impossible)| ClaimView::from_claim(claim, viewer_id.is_none(), impossible))
+                .collect(),
+        }
+    }
+}
+
+impl PlayerView {
+    /// Converts a player, the cards are only kept for the viewer or when `viewer_id` is `None`.
+    fn from_player(player: Player, viewer_id: Option<&str>) -> Self {
+        let can_see_cards = viewer_id.is_none_or(|viewer_id| viewer_id == player.id);
+
+        PlayerView {
+            forfeited: player.has_forfeited(),
+            online: player.is_online(
+                chrono::Utc::now(),
+                chrono::Duration::seconds(PLAYER_ONLINE_THRESHOLD_SECONDS),
+            ),
+            number_of_cards: player.assigned_cards.len(),
+            cards: if can_see_cards {
+                Some(player.assigned_cards)
+            } else {
+                None
+            },
+            id: player.id,
+            name: player.name,
+            color: player.color,
+            score: player.score,
+        }
+    }
+}
+
+impl ClaimView {
+    /// Converts a claim, the cards are only kept when they were revealed or `show_cards` is set.
+    fn from_claim(claim: Claim, show_cards: bool, impossible: bool) -> Self {
+        let can_see_cards = show_cards || claim.outcome.was_challenged();
+
+        ClaimView {
+            cards: if can_see_cards { Some(claim.cards) } else { None },
+            id: claim.id,
+            created_by: claim.created_by,
+            announced_rank: claim.announced_rank,
+            number_of_cards: claim.number_of_cards,
+            outcome: claim.outcome,
+            challenged_by: claim.challenged_by,
+            impossible,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    /// Creates a game with two players holding a king each and a claim of the first one.
+    fn game() -> Game {
+        let mut game = Game::new();
+        for name in ["Alice", "Bob"] {
+            let mut player = Player::new(name.to_string(), game.id.clone(), PlayerColor::default());
+            player.assigned_cards.push(Card::new(CardType::King));
+            game.players.push(player);
+        }
+        let claim = Claim::new(game.players[0].id.clone(), CardType::King, 1, vec![Card::new(CardType::Queen)]).unwrap();
+        game.claims.push(claim);
+        game
+    }
+
+    #[test]
+    fn a_player_only_sees_their_own_hand() {
+        let game = game();
+        let alice = game.players[0].id.clone();
+        let view = GameView::for_player(game, &alice);
+
+        assert!(view.players[0].cards.is_some());
+        assert!(view.players[1].cards.is_none());
+        assert_eq!(view.players[1].number_of_cards, 1);
+        assert!(view.claims[0].cards.is_none());
+    }
+
+    #[test]
+    fn the_cards_of_a_challenged_claim_are_revealed() {
+        let mut game = game();
+        game.claims[0].outcome = ClaimOutcome::Bluff;
+        let view = GameView::for_player(game, "");
+
+        assert_eq!(view.claims[0].cards.as_ref().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn the_full_view_shows_every_hand() {
+        let view = GameView::full(game());
+
+        assert!(view.players.iter().all(|player| player.cards.is_some()));
+        assert!(view.claims.iter().all(|claim| claim.cards.is_some()));
+    }
+}
@@ -1,6 +1,15 @@
 pub mod card;
+pub mod challenge;
+pub mod change_set;
 pub mod chat;
 pub mod claim;
 pub mod game;
+pub mod game_event;
+pub mod game_operation;
+pub mod game_settings;
+pub mod game_view;
 pub mod player;
+pub mod rate_limit;
+pub mod score_event;
+pub mod scoreboard;
 pub mod status;
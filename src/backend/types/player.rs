@@ -9,18 +9,35 @@ cfg_if! {
     response::{IntoResponse, Response},
 };
 
-use crate::backend::{errors::application_error::ErrorObject};
+use std::collections::BTreeMap;
+
+use crate::backend::{
+    errors::{application_error::ErrorObject, validation_error::ValidationError},
+    types::change_set::ChangeSet,
+    utils::pagination::MAX_PAGE_SIZE,
+};
     }
 }
-use crate::backend::types::card::Card;
+use crate::backend::{enums::player_color::PlayerColor, types::card::Card};
 use serde::{Deserialize, Serialize};
 
+use crate::backend::utils::id_generator::generate_id;
+
+// constants
+
+/// Minutes without a status update request after which the session of a player counts as
+/// inactive.
+pub const PLAYER_SESSION_TIMEOUT_MINUTES: i64 = 5;
+
+/// Seconds without an authenticated request after which a player is shown as offline.
+pub const PLAYER_ONLINE_THRESHOLD_SECONDS: i64 = 60;
+
 /// Player struct representing a player in the game system.
 ///
 /// He / she can be identified by a unique ID.
 ///
 /// Contains data set by the user like the name, etc. ...
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Player {
     /// Unique identifier of the player.
     pub id: String,
@@ -29,14 +46,56 @@ pub struct Player {
     pub name: String,
 
     /// Score of the player in the game.
+    ///
+    /// Is the sum of the score events of the player and kept up to date by the
+    /// `ScoreEventRepository`.
     pub score: usize,
 
     /// The date and time when the player joined the game.
     pub joined_at: String,
 
+    /// Color the player is shown with, unique within the game.
+    #[serde(default)]
+    pub color: PlayerColor,
+
+    /// The date and time when the player forfeited the current round.
+    ///
+    /// Is `None` while the player takes part in the round and reset when the next round is
+    /// dealt.
+    #[serde(default)]
+    pub forfeited_at: Option<String>,
+
+    /// The date and time of the last authenticated request of the player.
+    ///
+    /// Is `None` until the player sent a request with their session token.
+    #[serde(default)]
+    pub last_seen: Option<String>,
+
+    /// The date and time when the player marked themselves as ready to start the game.
+    ///
+    /// Is `None` while the player isn't ready. A game with `auto_start_at` only starts once all
+    /// of its players are ready.
+    #[serde(default)]
+    pub ready_at: Option<String>,
+
+    /// Place in which the player emptied their hand, starting at 1.
+    ///
+    /// Is `None` while the player still has cards. Finished players don't take turns anymore.
+    #[serde(default)]
+    pub finished_position: Option<usize>,
+
     /// The cards assigned to the player.
+    ///
+    /// Isn't a column of the `players` table, so it's empty when loaded from the database.
+    #[serde(default)]
     pub assigned_cards: Vec<Card>,
 
+    /// Number of cards in the hand of the player.
+    ///
+    /// Is set when the cards themselves are hidden from other players, see `Game::redact_for`.
+    #[serde(default)]
+    pub number_of_cards: usize,
+
     /// The ID of the game the player is currently in.
     ///
     /// This field is used to associate the player with a specific game instance.
@@ -53,21 +112,81 @@ impl Player {
     ///
     /// # Arguments
     /// - `name`: A string representing the name of the player.
+    /// - `game_id`: Identifier of the game the player joins.
+    /// - `color`: The color of the player, see `PlayerColor::assign`.
     ///
     /// # Returns
     /// A new `Player` instance with a unique ID, the provided name, and an empty card list.
-    pub fn new(name: String, game_id: String) -> Self {
+    pub fn new(name: String, game_id: String, color: PlayerColor) -> Self {
         Player {
-            id: uuid::Uuid::new_v4().to_string(),
+            id: generate_id(),
             name,
             game_id,
             score: 0,
             joined_at: chrono::Utc::now().to_string(),
+            color,
+            forfeited_at: None,
+            last_seen: None,
+            ready_at: None,
+            finished_position: None,
             assigned_cards: Vec::new(),
+            number_of_cards: 0,
             last_time_update_requested: chrono::Utc::now().to_string(),
         }
     }
 
+    /// Checks whether the player requested a status update within the last
+    /// `PLAYER_SESSION_TIMEOUT_MINUTES`.
+    ///
+    /// The timestamps are compared as strings like in the database.
+    ///
+    /// # Arguments
+    /// - `now`: The current point in time.
+    pub fn has_active_session(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let timeout = now - chrono::Duration::minutes(PLAYER_SESSION_TIMEOUT_MINUTES);
+        self.last_time_update_requested > timeout.to_string()
+    }
+
+    /// Checks whether the player sent an authenticated request within the `threshold`.
+    ///
+    /// The timestamps are compared as strings like in the database.
+    ///
+    /// # Arguments
+    /// - `now`: The current point in time.
+    /// - `threshold`: How long a player counts as online after their last request, see
+    ///   `PLAYER_ONLINE_THRESHOLD_SECONDS`.
+    pub fn is_online(&self, now: chrono::DateTime<chrono::Utc>, threshold: chrono::Duration) -> bool {
+        let offline_before = (now - threshold).to_string();
+        self.last_seen
+            .as_ref()
+            .is_some_and(|last_seen| *last_seen > offline_before)
+    }
+
+    /// Checks whether the player is ready to start the game.
+    pub fn is_ready(&self) -> bool {
+        self.ready_at.is_some()
+    }
+
+    /// Checks whether the player forfeited the current round and is skipped until the next one.
+    pub fn has_forfeited(&self) -> bool {
+        self.forfeited_at.is_some()
+    }
+
+    /// Hides the cards of the player from anybody else than the player themselves.
+    ///
+    /// Only the `number_of_cards` stays visible to the other players.
+    ///
+    /// # Arguments
+    /// - `viewer_id`: Identifier of the player the data is sent to.
+    pub fn redact_for(mut self, viewer_id: &str) -> Self {
+        self.number_of_cards = self.assigned_cards.len();
+        if self.id != viewer_id {
+            self.assigned_cards = vec![];
+        }
+
+        self
+    }
+
     // ----- Implementation for 'Vec<Player>' to be serialized to JSON -----
 
     /// Converts a vector of `Player` instances into a JSON string.
@@ -112,15 +231,16 @@ impl<'a> ErrorObject<'a> for Player {}
 /// Data Transfer Object (DTO) for updating a player's information.
 ///
 /// This struct is used to encapsulate the data required to update a player's
-/// information in the system. It includes optional fields for the player's name,
-/// score, and assigned cards, allowing for partial updates.
+/// information in the system. It includes optional fields for the player's name and the last
+/// status request, allowing for partial updates. The score isn't part of it, it changes only
+/// through score events, see `ScoreEventRepository`. Neither are the cards, they are moved with
+/// the `CardRepository`.
 ///
 /// # Fields
 ///
 /// - `id`: The unique identifier of the player to be updated.
 /// - `name`: An optional new name for the player.
-/// - `score`: An optional new score for the player.
-/// - `assigned_cards`: An optional list of new cards assigned to the player.
+/// - `last_time_update_requested`: An optional new time of the last status request.
 #[derive(Deserialize, Debug)]
 pub struct UpdatePlayerDTO {
     /// The unique identifier of the player to be updated.
@@ -129,12 +249,6 @@ pub struct UpdatePlayerDTO {
     /// The new name for the player.
     pub name: Option<String>,
 
-    /// The new score for the player.
-    pub score: Option<usize>,
-
-    /// The new game ID for the player.
-    pub assigned_cards: Option<Vec<Card>>,
-
     /// The last time when the client requested a status update
     pub last_time_update_requested: Option<String>,
 }
@@ -145,28 +259,51 @@ impl UpdatePlayerDTO {
     /// # Arguments
     /// - `id`: The unique identifier of the player to be updated.
     /// - `name`: An optional new name for the player.
-    /// - `score`: An optional new score for the player.
-    /// - `assigned_cards`: An optional list of new cards assigned to the player.
+    /// - `last_time_update_requested`: An optional new time of the last status request.
     ///
     /// # Returns
     /// A new `UpdatePlayerDTO` instance with the provided player ID and default values for other fields.
     pub fn new(
         id: String,
         name: Option<String>,
-        score: Option<usize>,
-        assigned_cards: Option<Vec<Card>>,
         last_time_update_requested: Option<String>,
     ) -> Self {
         UpdatePlayerDTO {
             id,
             name,
-            score,
-            assigned_cards,
             last_time_update_requested,
         }
     }
 }
 
+impl UpdatePlayerDTO {
+    /// Detects which of the provided fields differ from the current state of the player.
+    ///
+    /// Fields that aren't stored by `PlayerRepository::update_player` are ignored.
+    ///
+    /// # Arguments
+    /// - `current`: The player as it is currently stored.
+    ///
+    /// # Returns
+    /// A `ChangeSet` with the names of the fields that will change.
+    pub fn changes_against(&self, current: &Player) -> ChangeSet {
+        let mut changes = ChangeSet::new();
+
+        if self.name.as_ref().is_some_and(|name| *name != current.name) {
+            changes.mark("name");
+        }
+        if self
+            .last_time_update_requested
+            .as_ref()
+            .is_some_and(|time| *time != current.last_time_update_requested)
+        {
+            changes.mark("last_time_update_requested");
+        }
+
+        changes
+    }
+}
+
 // ----- Implementation of 'ErrorObject' trait for 'UpdatePlayerDTO' -----
 
 impl Display for UpdatePlayerDTO {
@@ -174,18 +311,114 @@ impl Display for UpdatePlayerDTO {
     ///
     /// # Returns
     /// A string representation of the `UpdatePlayerDTO` instance, including the player's ID,
-    /// name, score, and last time updated.
+    /// name and last time updated.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "UpdatePlayerDTO ID: {}, Name: {:?}, Score: {:?}, Last time when update requested: {:?}",
-            self.id, self.name, self.score, self.last_time_update_requested
+            "UpdatePlayerDTO ID: {}, Name: {:?}, Last time when update requested: {:?}",
+            self.id, self.name, self.last_time_update_requested
         )
     }
 }
 
 impl<'a> ErrorObject<'a> for UpdatePlayerDTO {}
 
+// ----- DTOs to join a game -----
+
+/// Request body to join a game.
+///
+/// # Fields
+///
+/// - `name`: The name the player wants to be shown with.
+/// - `color`: The color the player would like to have, another free color is used when it's
+///   taken.
+/// - `join_code`: The code of a private game, not needed for public games.
+/// - `ready`: Whether the player is ready to start right away, see `GameSettings::auto_start_at`.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JoinGameRequest {
+    /// Name of the new player
+    pub name: String,
+    /// Preferred color of the new player
+    pub color: Option<PlayerColor>,
+    /// Code of a private game
+    #[serde(default)]
+    pub join_code: Option<String>,
+    /// Whether the new player is ready to start the game right away
+    #[serde(default)]
+    pub ready: bool,
+}
+
+impl JoinGameRequest {
+    /// Checks all fields of the request at once.
+    ///
+    /// # Error
+    ///
+    /// Returns a `ValidationError` listing the `name` when it's empty.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut field_errors = BTreeMap::new();
+        if self.name.trim().is_empty() {
+            field_errors.insert("name".to_string(), "The name of a player can't be empty!".to_string());
+        }
+
+        ValidationError::check_fields(field_errors)
+    }
+}
+
+impl Display for JoinGameRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "JoinGameRequest Name: {}", self.name)
+    }
+}
+
+impl<'a> ErrorObject<'a> for JoinGameRequest {}
+
+/// Request body to fetch several players at once.
+///
+/// # Fields
+///
+/// - `ids`: Identifiers of the requested players, at most `MAX_PAGE_SIZE`.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PlayerBatchRequest {
+    /// Identifiers of the requested players
+    pub ids: Vec<String>,
+}
+
+impl PlayerBatchRequest {
+    /// Checks all fields of the request at once.
+    ///
+    /// # Error
+    ///
+    /// Returns a `ValidationError` listing the `ids` when there are more than `MAX_PAGE_SIZE`.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut field_errors = BTreeMap::new();
+        if self.ids.len() > MAX_PAGE_SIZE {
+            field_errors.insert(
+                "ids".to_string(),
+                format!("At most {} players can be fetched at once!", MAX_PAGE_SIZE),
+            );
+        }
+
+        ValidationError::check_fields(field_errors)
+    }
+}
+
+/// Response after a player joined a game.
+///
+/// The session token is only sent once, the client has to send it with every request that
+/// changes the game in the name of the player.
+///
+/// # Fields
+///
+/// - `player`: The newly created player.
+/// - `session_token`: Secret token identifying the player.
+#[derive(Serialize, Debug)]
+pub struct JoinGameResponse {
+    /// The newly created player
+    pub player: Player,
+    /// Secret token identifying the player
+    pub session_token: String,
+}
+
 // ----- Implementation of 'IntoResponse' trait for 'Player' -----
 impl IntoResponse for Player {
     /// Converts the `Player` instance into a response.
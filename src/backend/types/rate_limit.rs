@@ -0,0 +1,43 @@
+use cfg_if::cfg_if;
+
+use std::fmt::Display;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::backend::errors::application_error::ErrorObject;
+    }
+}
+use serde::{Deserialize, Serialize};
+
+/// The requests of a client in one fixed time window of a rate limit.
+///
+/// # Properties
+///
+/// - key: Identifies the limited action and the client, e.g. 'create-game:203.0.113.7'.
+/// - window_start: Unix timestamp in seconds when the window started.
+/// - hits: Number of requests in the window.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RateLimitWindow {
+    /// Identifies the limited action and the client.
+    pub key: String,
+    /// Unix timestamp in seconds when the window started.
+    pub window_start: i64,
+    /// Number of requests in the window.
+    pub hits: usize,
+}
+
+impl Display for RateLimitWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RateLimitWindow Key: {}, Window Start: {}, Hits: {}",
+            self.key, self.window_start, self.hits
+        )
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        impl<'a> ErrorObject<'a> for RateLimitWindow {}
+    }
+}
@@ -0,0 +1,85 @@
+use cfg_if::cfg_if;
+
+use std::fmt::Display;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::backend::errors::application_error::ErrorObject;
+    }
+}
+use serde::{Deserialize, Serialize};
+
+use crate::backend::utils::id_generator::generate_id;
+
+/// A single change of the score of a player, e.g. for winning a challenge.
+///
+/// The score events of a game form its ledger. The `score` of a player is the sum of the deltas
+/// of all their events, so the scoreboard of every round can be shown afterwards.
+///
+/// # Properties
+///
+/// - id: Unique identifier of the event.
+/// - game_id: The ID of the game the score changed in.
+/// - player_id: The ID of the player whose score changed.
+/// - round_number: The round in which the score changed.
+/// - delta: Points added to the score, negative when points are taken away.
+/// - reason: Short description why the score changed.
+/// - created_at: Point in time when the event was recorded.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ScoreEvent {
+    /// Unique identifier of the event.
+    pub id: String,
+    /// The ID of the game the score changed in.
+    pub game_id: String,
+    /// The ID of the player whose score changed.
+    pub player_id: String,
+    /// The round in which the score changed.
+    pub round_number: usize,
+    /// Points added to the score, negative when points are taken away.
+    pub delta: i64,
+    /// Short description why the score changed.
+    pub reason: String,
+    /// Point in time when the event was recorded.
+    pub created_at: String,
+}
+
+impl ScoreEvent {
+    /// Creates a new `ScoreEvent` that is recorded now.
+    ///
+    /// # Arguments
+    /// - `game_id`: The ID of the game the score changed in.
+    /// - `player_id`: The ID of the player whose score changed.
+    /// - `round_number`: The round in which the score changed.
+    /// - `delta`: Points added to the score.
+    /// - `reason`: Short description why the score changed.
+    ///
+    /// # Returns
+    /// A new `ScoreEvent` instance.
+    pub fn new(game_id: String, player_id: String, round_number: usize, delta: i64, reason: String) -> Self {
+        ScoreEvent {
+            id: generate_id(),
+            game_id,
+            player_id,
+            round_number,
+            delta,
+            reason,
+            created_at: chrono::Utc::now().to_string(),
+        }
+    }
+}
+
+impl Display for ScoreEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ScoreEvent ID: {}, Game: {}, Player: {}, Round: {}, Delta: {}, Reason: {}",
+            self.id, self.game_id, self.player_id, self.round_number, self.delta, self.reason
+        )
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        impl<'a> ErrorObject<'a> for ScoreEvent {}
+    }
+}
@@ -0,0 +1,45 @@
+// Final results of a game.
+use serde::{Deserialize, Serialize};
+
+/// Final ranking of the players of an ended game.
+///
+/// # Props
+///
+/// - `game_id` -> Identifier of the game.
+/// - `entries` -> The players from the first to the last place.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Scoreboard {
+    /// Identifier of the game
+    pub game_id: String,
+    /// The players from the first to the last place
+    pub entries: Vec<ScoreboardEntry>,
+}
+
+/// The place of a single player on the `Scoreboard`.
+///
+/// Players with the same score and the same number of cards left share a rank; the following
+/// rank is skipped, e.g. 1, 1, 3.
+///
+/// # Props
+///
+/// - `rank` -> Place of the player, starting at 1.
+/// - `player_id` -> Identifier of the player.
+/// - `name` -> Name of the player.
+/// - `score` -> Final score of the player.
+/// - `number_of_cards` -> Cards left in the hand of the player.
+/// - `finished_position` -> Place in which the player emptied their hand, if they did.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ScoreboardEntry {
+    /// Place of the player, starting at 1
+    pub rank: usize,
+    /// Identifier of the player
+    pub player_id: String,
+    /// Name of the player
+    pub name: String,
+    /// Final score of the player
+    pub score: usize,
+    /// Cards left in the hand of the player
+    pub number_of_cards: usize,
+    /// Place in which the player emptied their hand
+    pub finished_position: Option<usize>,
+}
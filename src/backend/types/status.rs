@@ -6,7 +6,7 @@ cfg_if! {
     }
 }
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use crate::backend::utils::id_generator::generate_id;
 
 use crate::backend::types::{game::Game, player::Player};
 
@@ -72,8 +72,8 @@ impl Default for StatusUpdateRequest {
     /// A new `StatusUpdateRequest` instance with default values.
     fn default() -> Self {
         StatusUpdateRequest {
-            player_id: Uuid::new_v4().to_string(),
-            game_id: Uuid::new_v4().to_string(),
+            player_id: generate_id(),
+            game_id: generate_id(),
         }
     }
 }
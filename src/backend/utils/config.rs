@@ -0,0 +1,290 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::{str::FromStr, sync::OnceLock};
+
+use worker::Env;
+
+use crate::backend::{
+    enums::player_color::PlayerColor,
+    logic::game_logic::{DEFAULT_CHALLENGE_WINDOW_SECONDS, TURN_DURATION_SECONDS},
+    router::router_provider::DEFAULT_API_PREFIX,
+    types::game::MAX_PLAYERS,
+    utils::{
+        query_budget::DEFAULT_QUERY_BUDGET,
+        rate_limiter::{RateLimit, DEFAULT_CREATE_GAME_RATE_LIMIT},
+    },
+};
+
+// constants
+
+/// Name of the worker variable which configures the maximum number of players of a game.
+const MAX_PLAYERS_VARIABLE: &str = "MAX_PLAYERS";
+
+/// Name of the worker variable which configures how long a player has for a move.
+const TURN_DURATION_VARIABLE: &str = "TURN_DURATION_SECONDS";
+
+/// Name of the worker variable which configures the length of the challenge window.
+const CHALLENGE_WINDOW_VARIABLE: &str = "CHALLENGE_WINDOW_SECONDS";
+
+/// Name of the worker variable which configures how many messages the chat of a new game keeps.
+const MAX_CHAT_MESSAGES_VARIABLE: &str = "MAX_CHAT_MESSAGES";
+
+/// Name of the worker variable which configures how many games a client can create per window.
+const CREATE_GAME_RATE_LIMIT_VARIABLE: &str = "CREATE_GAME_RATE_LIMIT";
+
+/// Name of the worker variable which configures the length of the create-game window.
+const CREATE_GAME_RATE_LIMIT_WINDOW_VARIABLE: &str = "CREATE_GAME_RATE_LIMIT_WINDOW_SECONDS";
+
+/// Name of the worker variable which configures the prefix of the API routes.
+const API_PREFIX_VARIABLE: &str = "API_PREFIX";
+
+/// Name of the worker variable which disables the unversioned alias when set to `false`.
+const LEGACY_API_ALIAS_VARIABLE: &str = "API_LEGACY_ALIAS";
+
+/// Name of the worker variable which lets the game view skip hands that failed to load.
+const DEGRADE_FAILED_HANDS_VARIABLE: &str = "DEGRADE_FAILED_HANDS";
+
+/// Name of the worker variable which configures how many D1 queries a request may run.
+const QUERY_BUDGET_VARIABLE: &str = "QUERY_BUDGET";
+
+/// Name of the worker variable which allows messages in the chat of an aborted game when set to
+/// `false`.
+const REJECT_MESSAGES_IN_ABORTED_GAMES_VARIABLE: &str = "REJECT_MESSAGES_IN_ABORTED_GAMES";
+
+/// The configuration of the worker, read once from the variables in the `wrangler.toml`.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Settings of the worker that can be changed without changing the code.
+///
+/// Is loaded from the worker variables when the first request arrives and injected into the
+/// router as `Extension`, so the handlers don't read the variables themselves. Every value that
+/// is missing falls back to its default; a value that is set but invalid is logged and falls back
+/// as well.
+///
+/// # Props
+///
+/// - `max_players` -> Maximum number of players of a game, between 2 and the number of
+///   `PlayerColor`s. Default: `MAX_PLAYERS`.
+/// - `turn_duration_seconds` -> Seconds a player has for a move. Default: `TURN_DURATION_SECONDS`.
+/// - `challenge_window_seconds` -> Seconds the other players have to challenge a claim. Default:
+///   `DEFAULT_CHALLENGE_WINDOW_SECONDS`.
+/// - `max_chat_messages` -> Number of messages the chat of a new game keeps, unlimited by
+///   default.
+/// - `create_game_rate_limit` -> How many games a client can create. Default:
+///   `DEFAULT_CREATE_GAME_RATE_LIMIT`.
+/// - `api_prefix` -> Prefix of the API routes. Default: `DEFAULT_API_PREFIX`.
+/// - `legacy_api_alias` -> Whether the API routes are served under `/api` as well. Default: `true`.
+/// - `degrade_failed_hands` -> Whether the game view shows a player with an empty hand when their
+///   cards can't be loaded, instead of failing. Default: `false`.
+/// - `query_budget` -> Number of D1 queries a request may run before a warning is logged.
+///   Default: `DEFAULT_QUERY_BUDGET`.
+/// - `reject_messages_in_aborted_games` -> Whether messages sent to the chat of an aborted game
+///   are rejected. Default: `true`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Maximum number of players of a game
+    pub max_players: usize,
+    /// Seconds a player has for a move
+    pub turn_duration_seconds: i64,
+    /// Seconds the other players have to challenge a claim
+    pub challenge_window_seconds: i64,
+    /// Number of messages the chat of a new game keeps
+    pub max_chat_messages: Option<usize>,
+    /// How many games a client can create
+    pub create_game_rate_limit: RateLimit,
+    /// Prefix of the API routes
+    pub api_prefix: String,
+    /// Whether the API routes are served under `/api` as well
+    pub legacy_api_alias: bool,
+    /// Whether the game view shows a player with an empty hand when their cards can't be loaded
+    pub degrade_failed_hands: bool,
+    /// Number of D1 queries a request may run
+    pub query_budget: usize,
+    /// Whether messages sent to the chat of an aborted game are rejected
+    pub reject_messages_in_aborted_games: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_players: MAX_PLAYERS,
+            turn_duration_seconds: TURN_DURATION_SECONDS,
+            challenge_window_seconds: DEFAULT_CHALLENGE_WINDOW_SECONDS,
+            max_chat_messages: None,
+            create_game_rate_limit: DEFAULT_CREATE_GAME_RATE_LIMIT,
+            api_prefix: DEFAULT_API_PREFIX.to_string(),
+            legacy_api_alias: true,
+            degrade_failed_hands: false,
+            query_budget: DEFAULT_QUERY_BUDGET,
+            reject_messages_in_aborted_games: true,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the configuration of the worker, it's only read from the variables the first time.
+    ///
+    /// # Arguments
+    ///
+    /// - `env` -> Cloudflare Worker environment containing the variables.
+    pub fn load(env: &Env) -> Config {
+        CONFIG.get_or_init(|| Config::from_env(env)).clone()
+    }
+
+    /// Reads the configuration from the worker variables.
+    ///
+    /// # Arguments
+    ///
+    /// - `env` -> Cloudflare Worker environment containing the variables.
+    pub fn from_env(env: &Env) -> Config {
+        Config::from_variables(|name| env.var(name).ok().map(|value| value.to_string()))
+    }
+
+    /// Builds the configuration from a lookup of the variables, so it doesn't depend on a real
+    /// worker environment.
+    ///
+    /// # Arguments
+    ///
+    /// - `variable` -> Returns the value of a variable by its name, `None` when it isn't set.
+    pub fn from_variables(variable: impl Fn(&str) -> Option<String>) -> Config {
+        let default = Config::default();
+
+        Config {
+            max_players: parse_variable(&variable, MAX_PLAYERS_VARIABLE, default.max_players, |max_players| {
+                (2..=PlayerColor::PALETTE.len()).contains(max_players)
+            }),
+            turn_duration_seconds: parse_variable(&variable, TURN_DURATION_VARIABLE, default.turn_duration_seconds, |seconds| *seconds > 0),
+            challenge_window_seconds: parse_variable(&variable, CHALLENGE_WINDOW_VARIABLE, default.challenge_window_seconds, |seconds| *seconds > 0),
+            max_chat_messages: parse_optional_variable(&variable, MAX_CHAT_MESSAGES_VARIABLE, |max_messages| *max_messages > 0),
+            create_game_rate_limit: RateLimit {
+                max_requests: parse_variable(
+                    &variable,
+                    CREATE_GAME_RATE_LIMIT_VARIABLE,
+                    default.create_game_rate_limit.max_requests,
+                    |max_requests| *max_requests > 0,
+                ),
+                window_seconds: parse_variable(
+                    &variable,
+                    CREATE_GAME_RATE_LIMIT_WINDOW_VARIABLE,
+                    default.create_game_rate_limit.window_seconds,
+                    |seconds| *seconds > 0,
+                ),
+            },
+            api_prefix: match variable(API_PREFIX_VARIABLE) {
+                Some(prefix) => normalize_api_prefix(&prefix).unwrap_or(default.api_prefix),
+                None => default.api_prefix,
+            },
+            legacy_api_alias: parse_variable(&variable, LEGACY_API_ALIAS_VARIABLE, default.legacy_api_alias, |_| true),
+            degrade_failed_hands: parse_variable(&variable, DEGRADE_FAILED_HANDS_VARIABLE, default.degrade_failed_hands, |_| true),
+            query_budget: parse_variable(&variable, QUERY_BUDGET_VARIABLE, default.query_budget, |budget| *budget > 0),
+            reject_messages_in_aborted_games: parse_variable(
+                &variable,
+                REJECT_MESSAGES_IN_ABORTED_GAMES_VARIABLE,
+                default.reject_messages_in_aborted_games,
+                |_| true,
+            ),
+        }
+    }
+}
+
+/// Parses a variable and checks it with `is_valid`, a missing or invalid value results in the
+/// `default`.
+fn parse_variable<T: FromStr>(
+    variable: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    default: T,
+    is_valid: impl Fn(&T) -> bool,
+) -> T {
+    parse_optional_variable(variable, name, is_valid).unwrap_or(default)
+}
+
+/// Parses a variable and checks it with `is_valid`.
+///
+/// Returns `None` when the variable is missing. An unparsable or invalid value is logged and
+/// results in `None` as well, so a typo in the `wrangler.toml` doesn't stop the worker.
+fn parse_optional_variable<T: FromStr>(
+    variable: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    is_valid: impl Fn(&T) -> bool,
+) -> Option<T> {
+    let value = variable(name)?;
+
+    match value.trim().parse::<T>() {
+        Ok(parsed) if is_valid(&parsed) => Some(parsed),
+        _ => {
+            log::warn!("The value '{}' of the variable '{}' is invalid, the default is used!", value, name);
+            None
+        }
+    }
+}
+
+/// Adds a missing leading slash to a prefix of the API routes and removes a trailing slash,
+/// because `Router::nest` expects a path like `/api/v1`.
+///
+/// Returns `None` for an empty prefix.
+fn normalize_api_prefix(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim().trim_matches('/');
+    if prefix.is_empty() {
+        return None;
+    }
+
+    Some(format!("/{}", prefix))
+}
+
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    /// Builds the configuration from a fixed list of variables.
+    fn config_from(variables: &[(&str, &str)]) -> Config {
+        Config::from_variables(|name| {
+            variables
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn missing_variables_fall_back_to_the_defaults() {
+        assert_eq!(config_from(&[]), Config::default());
+    }
+
+    #[test]
+    fn the_variables_override_the_defaults() {
+        let config = config_from(&[
+            (MAX_PLAYERS_VARIABLE, "4"),
+            (CREATE_GAME_RATE_LIMIT_VARIABLE, " 10 "),
+            (MAX_CHAT_MESSAGES_VARIABLE, "50"),
+            (API_PREFIX_VARIABLE, "api/v2/"),
+        ]);
+
+        assert_eq!(config.max_players, 4);
+        assert_eq!(config.create_game_rate_limit.max_requests, 10);
+        assert_eq!(config.max_chat_messages, Some(50));
+        assert_eq!(config.api_prefix, "/api/v2");
+    }
+
+    #[test]
+    fn invalid_variables_keep_the_defaults() {
+        let config = config_from(&[
+            (MAX_PLAYERS_VARIABLE, "1"),
+            (TURN_DURATION_VARIABLE, "soon"),
+            (QUERY_BUDGET_VARIABLE, "0"),
+            (API_PREFIX_VARIABLE, "/"),
+        ]);
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn messages_in_aborted_games_are_rejected_unless_allowed() {
+        assert!(config_from(&[]).reject_messages_in_aborted_games);
+        assert!(config_from(&[(REJECT_MESSAGES_IN_ABORTED_GAMES_VARIABLE, "true")]).reject_messages_in_aborted_games);
+        assert!(!config_from(&[(REJECT_MESSAGES_IN_ABORTED_GAMES_VARIABLE, "false")]).reject_messages_in_aborted_games);
+    }
+}
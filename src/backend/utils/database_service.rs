@@ -0,0 +1,173 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::sync::OnceLock;
+
+use worker::{D1Database, D1Result, Env};
+
+use crate::backend::{
+    errors::{
+        application_error::{ApplicationError, ErrorObject},
+        process_error::ProcessError,
+    },
+    types::game::Game,
+    utils::query_budget::{CountedDatabase, CountedStatement},
+};
+
+/// Name of the D1 database binding declared in the `wrangler.toml`.
+pub const DATABASE_BINDING: &str = "DB";
+
+/// Version of the database schema the code expects, the number of the latest migration.
+///
+/// Has to be increased together with the `schema_version` table by every new migration.
+pub const EXPECTED_SCHEMA_VERSION: usize = 39;
+
+/// Version of the database schema, read once by every worker instance.
+static SCHEMA_VERSION: OnceLock<usize> = OnceLock::new();
+
+/// Retrieves the D1 database binding from the Cloudflare Worker environment.
+///
+/// Every repository owns its own `D1Database` instance, so this function is called once per
+/// repository in the handler functions.
+///
+/// # Arguments
+///
+/// - `env` -> Cloudflare Worker environment passed to the router as an extension.
+///
+/// # Returns
+///
+/// 1.) Ok(D1Database), WHEN the binding could be found.
+/// 2.) Err(Box<dyn ApplicationError>), WHEN the binding is missing in the environment.
+pub fn get_database(env: &Env) -> Result<D1Database, Box<dyn ApplicationError>> {
+    match env.d1(DATABASE_BINDING) {
+        Ok(db) => Ok(db),
+        Err(err) => Err(Box::new(ProcessError::<Game>::new(
+            format!("The database binding ['{}'] couldn't be found! Error: {}", DATABASE_BINDING, err),
+            "database_service::get_database".to_string(),
+            None,
+        ))),
+    }
+}
+
+/// Compares the version of the deployed database schema with `EXPECTED_SCHEMA_VERSION`.
+///
+/// Called before every request is handled, but the version is only read from the database by the
+/// first request of the worker instance, even when it doesn't match. Applying the migrations
+/// therefore needs a new deployment of the worker. A database without the `schema_version` table
+/// counts as version 0.
+///
+/// # Arguments
+///
+/// - `env` -> Cloudflare Worker environment containing the database binding.
+///
+/// # Returns
+///
+/// 1.) Ok(()), WHEN the schema has the expected version.
+/// 2.) Err(Box<dyn ApplicationError>), WHEN the versions don't match, naming both of them.
+pub async fn check_schema_version(env: &Env) -> Result<(), Box<dyn ApplicationError>> {
+    let version = match SCHEMA_VERSION.get() {
+        Some(version) => *version,
+        None => {
+            let version = match get_database(env)?
+                .prepare("SELECT version FROM schema_version WHERE id = 1;")
+                .first::<usize>(Some("version"))
+                .await
+            {
+                Ok(version) => version.unwrap_or(0),
+                // the table doesn't exist before its migration was applied
+                Err(_) => 0,
+            };
+            *SCHEMA_VERSION.get_or_init(|| version)
+        }
+    };
+
+    if version != EXPECTED_SCHEMA_VERSION {
+        return Err(Box::new(ProcessError::<Game>::new(
+            format!(
+                "The database schema has the version {}, but the worker expects the version {}! Apply the missing migrations with 'wrangler d1 migrations apply' or deploy the matching worker.",
+                version, EXPECTED_SCHEMA_VERSION
+            ),
+            "database_service::check_schema_version".to_string(),
+            None,
+        )));
+    }
+
+    Ok(())
+}
+
+/// A prepared statement of a batch together with a name of the operation it performs.
+///
+/// # Props
+///
+/// - `label` -> Short name of the operation, e.g. 'delete players'.
+/// - `statement` -> The prepared and bound statement.
+pub struct LabeledStatement {
+    /// Short name of the operation
+    pub label: String,
+    /// The prepared and bound statement
+    pub statement: CountedStatement,
+}
+
+impl LabeledStatement {
+    /// Creates a new `LabeledStatement` from a label and a prepared statement.
+    pub fn new(label: &str, statement: CountedStatement) -> Self {
+        LabeledStatement {
+            label: label.to_string(),
+            statement,
+        }
+    }
+}
+
+/// Executes labeled statements in one D1 batch, so either all or none of them are applied.
+///
+/// If a statement reports a failure, the error names its label. When D1 rejects the whole batch
+/// without a result per statement, all labels of the batch are listed, because the failing one
+/// can't be determined.
+///
+/// # Arguments
+///
+/// - `db` -> Database the batch is executed on.
+/// - `statements` -> Labeled statements in the order they should be executed.
+///
+/// # Returns
+///
+/// 1.) Ok(Vec<D1Result>), the results in the order of the statements.
+/// 2.) Err(ProcessError<T>), naming the failed operation.
+pub async fn run_labeled_batch<T: for<'a> ErrorObject<'a>>(
+    db: &CountedDatabase,
+    statements: Vec<LabeledStatement>,
+) -> Result<Vec<D1Result>, ProcessError<T>> {
+    let labels: Vec<String> = statements.iter().map(|s| s.label.clone()).collect();
+
+    let results = match db
+        .batch(statements.into_iter().map(|s| s.statement).collect())
+        .await
+    {
+        Ok(results) => results,
+        Err(err) => {
+            return Err(ProcessError::new(
+                format!("The batch with the operations [{}] failed! Error: {}", labels.join(", "), err),
+                "database_service::run_labeled_batch".to_string(),
+                None,
+            ))
+        }
+    };
+
+    for (label, result) in labels.iter().zip(results.iter()) {
+        if !result.success() {
+            return Err(ProcessError::new(
+                format!(
+                    "The operation ['{}'] of the batch failed! Error: {}",
+                    label,
+                    result.error().unwrap_or_default()
+                ),
+                "database_service::run_labeled_batch".to_string(),
+                None,
+            ));
+        }
+    }
+
+    Ok(results)
+}
+    }
+}
@@ -0,0 +1,102 @@
+use std::cell::RefCell;
+
+// constants
+
+/// Number of characters of a generated id.
+pub const ID_LENGTH: usize = 21;
+
+/// Characters a generated id consists of; all of them can be used in a URL without escaping.
+const URL_SAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Generates the identifiers of new entities like games, players, cards, claims and chats.
+///
+/// The default is the `ShortIdGenerator`. Tests can inject another generator with
+/// `set_id_generator`, e.g. to get deterministic ids.
+pub trait IdGenerator {
+    /// Returns a new unique identifier.
+    fn generate_id(&self) -> String;
+}
+
+/// Generates short, random ids from a URL-safe alphabet.
+///
+/// With `ID_LENGTH` characters and 64 possible characters per position, an id has 126 random
+/// bits, so collisions are practically impossible.
+pub struct ShortIdGenerator;
+
+impl IdGenerator for ShortIdGenerator {
+    fn generate_id(&self) -> String {
+        let mut bytes = [0u8; ID_LENGTH];
+        if let Err(err) = getrandom::fill(&mut bytes) {
+            log::warn!("Couldn't get random bytes for a new id! Falling back to a UUID. Error: {}", err);
+            return uuid::Uuid::new_v4().simple().to_string();
+        }
+
+        bytes
+            .iter()
+            .map(|byte| URL_SAFE_ALPHABET[(byte & 63) as usize] as char)
+            .collect()
+    }
+}
+
+thread_local! {
+    /// Generator that replaces the `ShortIdGenerator`, when one was injected.
+    static INJECTED_ID_GENERATOR: RefCell<Option<Box<dyn IdGenerator>>> = const { RefCell::new(None) };
+}
+
+/// Replaces the generator used by `generate_id`; `None` restores the `ShortIdGenerator`.
+///
+/// Workers run single-threaded, so the generator is stored per thread. Only tests inject a
+/// generator, the worker always uses random ids.
+#[cfg(test)]
+pub fn set_id_generator(generator: Option<Box<dyn IdGenerator>>) {
+    INJECTED_ID_GENERATOR.with(|injected| *injected.borrow_mut() = generator);
+}
+
+/// Returns a new id from the injected generator or the `ShortIdGenerator`.
+///
+/// Used by all constructors that create a new entity.
+pub fn generate_id() -> String {
+    INJECTED_ID_GENERATOR.with(|injected| match injected.borrow().as_ref() {
+        Some(generator) => generator.generate_id(),
+        None => ShortIdGenerator.generate_id(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, collections::HashSet};
+
+    use super::*;
+
+    /// Numbers the ids in the order they are generated.
+    struct CountingIdGenerator {
+        next: Cell<usize>,
+    }
+
+    impl IdGenerator for CountingIdGenerator {
+        fn generate_id(&self) -> String {
+            let id = self.next.get();
+            self.next.set(id + 1);
+            format!("id-{}", id)
+        }
+    }
+
+    #[test]
+    fn generated_ids_are_unique_and_url_safe() {
+        let ids: HashSet<String> = (0..1000).map(|_| generate_id()).collect();
+
+        assert_eq!(ids.len(), 1000);
+        assert!(ids.iter().all(|id| id.len() == ID_LENGTH && id.bytes().all(|byte| URL_SAFE_ALPHABET.contains(&byte))));
+    }
+
+    #[test]
+    fn an_injected_generator_replaces_the_random_ids() {
+        set_id_generator(Some(Box::new(CountingIdGenerator { next: Cell::new(1) })));
+        let ids = [generate_id(), generate_id()];
+        set_id_generator(None);
+
+        assert_eq!(ids, ["id-1", "id-2"]);
+        assert_eq!(generate_id().len(), ID_LENGTH);
+    }
+}
@@ -1 +1,11 @@
+pub mod config;
+pub mod database_service;
 pub mod game_service;
+pub mod id_generator;
+pub mod msgpack;
+pub mod pagination;
+pub mod query_budget;
+pub mod rate_limiter;
+pub mod repository_provider;
+pub mod session_token;
+pub mod turn_service;
@@ -0,0 +1,325 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use serde_json::Value;
+
+/// Encodes a JSON value as MessagePack, see https://github.com/msgpack/msgpack/blob/master/spec.md.
+///
+/// The bodies of the API are built with `serde_json` anyway, so encoding the resulting `Value`
+/// covers every type of the API without another serializer. Every value uses the smallest format
+/// that fits it.
+///
+/// # Arguments
+///
+/// - `value` -> The value to encode.
+///
+/// # Returns
+///
+/// The MessagePack bytes of the value.
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_value(&mut bytes, value);
+    bytes
+}
+
+/// Appends a single value and all values nested in it.
+fn write_value(bytes: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => bytes.push(0xc0),
+        Value::Bool(false) => bytes.push(0xc2),
+        Value::Bool(true) => bytes.push(0xc3),
+        Value::Number(number) => {
+            if let Some(unsigned) = number.as_u64() {
+                write_unsigned(bytes, unsigned);
+            } else if let Some(signed) = number.as_i64() {
+                write_signed(bytes, signed);
+            } else {
+                bytes.push(0xcb);
+                bytes.extend_from_slice(&number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        }
+        Value::String(string) => {
+            let length = string.len();
+            if length < 32 {
+                bytes.push(0xa0 | length as u8);
+            } else if length <= u8::MAX as usize {
+                bytes.push(0xd9);
+                bytes.push(length as u8);
+            } else if length <= u16::MAX as usize {
+                bytes.push(0xda);
+                bytes.extend_from_slice(&(length as u16).to_be_bytes());
+            } else {
+                bytes.push(0xdb);
+                bytes.extend_from_slice(&(length as u32).to_be_bytes());
+            }
+            bytes.extend_from_slice(string.as_bytes());
+        }
+        Value::Array(values) => {
+            write_length(bytes, values.len(), 0x90, 0xdc, 0xdd);
+            for value in values {
+                write_value(bytes, value);
+            }
+        }
+        Value::Object(entries) => {
+            write_length(bytes, entries.len(), 0x80, 0xde, 0xdf);
+            for (key, value) in entries {
+                write_value(bytes, &Value::String(key.clone()));
+                write_value(bytes, value);
+            }
+        }
+    }
+}
+
+/// Appends a non-negative integer.
+fn write_unsigned(bytes: &mut Vec<u8>, number: u64) {
+    if number <= 0x7f {
+        bytes.push(number as u8);
+    } else if number <= u8::MAX as u64 {
+        bytes.push(0xcc);
+        bytes.push(number as u8);
+    } else if number <= u16::MAX as u64 {
+        bytes.push(0xcd);
+        bytes.extend_from_slice(&(number as u16).to_be_bytes());
+    } else if number <= u32::MAX as u64 {
+        bytes.push(0xce);
+        bytes.extend_from_slice(&(number as u32).to_be_bytes());
+    } else {
+        bytes.push(0xcf);
+        bytes.extend_from_slice(&number.to_be_bytes());
+    }
+}
+
+/// Appends a negative integer.
+fn write_signed(bytes: &mut Vec<u8>, number: i64) {
+    if number >= -32 {
+        bytes.push(number as i8 as u8);
+    } else if number >= i8::MIN as i64 {
+        bytes.push(0xd0);
+        bytes.push(number as i8 as u8);
+    } else if number >= i16::MIN as i64 {
+        bytes.push(0xd1);
+        bytes.extend_from_slice(&(number as i16).to_be_bytes());
+    } else if number >= i32::MIN as i64 {
+        bytes.push(0xd2);
+        bytes.extend_from_slice(&(number as i32).to_be_bytes());
+    } else {
+        bytes.push(0xd3);
+        bytes.extend_from_slice(&number.to_be_bytes());
+    }
+}
+
+/// Appends the header of an array or map with `length` elements.
+fn write_length(bytes: &mut Vec<u8>, length: usize, fix_marker: u8, marker_16: u8, marker_32: u8) {
+    if length < 16 {
+        bytes.push(fix_marker | length as u8);
+    } else if length <= u16::MAX as usize {
+        bytes.push(marker_16);
+        bytes.extend_from_slice(&(length as u16).to_be_bytes());
+    } else {
+        bytes.push(marker_32);
+        bytes.extend_from_slice(&(length as u32).to_be_bytes());
+    }
+}
+
+/// Decodes MessagePack bytes into a JSON value, the counterpart of `encode`.
+///
+/// Covers every format `encode` writes as well as the 32 bit floats other encoders use. Binary
+/// data and extension types have no JSON equivalent and are rejected, just like map keys that
+/// aren't strings.
+///
+/// # Arguments
+///
+/// - `bytes` -> The MessagePack bytes of exactly one value.
+///
+/// # Returns
+///
+/// -> ***`Err(String)`*** describing why the bytes aren't a single supported value
+pub fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, position: 0 };
+    let value = reader.read_value()?;
+    if reader.position != bytes.len() {
+        return Err(format!(
+            "The MessagePack value ends at byte {}, but {} bytes were sent!",
+            reader.position,
+            bytes.len()
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Reads values from MessagePack bytes, starting at `position`.
+struct Reader<'a> {
+    /// The bytes that are decoded
+    bytes: &'a [u8],
+    /// Index of the next byte to read
+    position: usize,
+}
+
+impl Reader<'_> {
+    /// Reads a single value and all values nested in it.
+    fn read_value(&mut self) -> Result<Value, String> {
+        let marker = self.read_bytes(1)?[0];
+        match marker {
+            0x00..=0x7f => Ok(Value::from(marker)),
+            0x80..=0x8f => self.read_map((marker & 0x0f) as usize),
+            0x90..=0x9f => self.read_array((marker & 0x0f) as usize),
+            0xa0..=0xbf => self.read_string((marker & 0x1f) as usize),
+            0xc0 => Ok(Value::Null),
+            0xc2 => Ok(Value::Bool(false)),
+            0xc3 => Ok(Value::Bool(true)),
+            0xca => {
+                let number = f32::from_be_bytes(self.read_array_of()?);
+                float_value(number as f64)
+            }
+            0xcb => float_value(f64::from_be_bytes(self.read_array_of()?)),
+            0xcc => Ok(Value::from(self.read_bytes(1)?[0])),
+            0xcd => Ok(Value::from(u16::from_be_bytes(self.read_array_of()?))),
+            0xce => Ok(Value::from(u32::from_be_bytes(self.read_array_of()?))),
+            0xcf => Ok(Value::from(u64::from_be_bytes(self.read_array_of()?))),
+            0xd0 => Ok(Value::from(self.read_bytes(1)?[0] as i8)),
+            0xd1 => Ok(Value::from(i16::from_be_bytes(self.read_array_of()?))),
+            0xd2 => Ok(Value::from(i32::from_be_bytes(self.read_array_of()?))),
+            0xd3 => Ok(Value::from(i64::from_be_bytes(self.read_array_of()?))),
+            0xd9 => {
+                let length = self.read_bytes(1)?[0] as usize;
+                self.read_string(length)
+            }
+            0xda => {
+                let length = u16::from_be_bytes(self.read_array_of()?) as usize;
+                self.read_string(length)
+            }
+            0xdb => {
+                let length = u32::from_be_bytes(self.read_array_of()?) as usize;
+                self.read_string(length)
+            }
+            0xdc => {
+                let length = u16::from_be_bytes(self.read_array_of()?) as usize;
+                self.read_array(length)
+            }
+            0xdd => {
+                let length = u32::from_be_bytes(self.read_array_of()?) as usize;
+                self.read_array(length)
+            }
+            0xde => {
+                let length = u16::from_be_bytes(self.read_array_of()?) as usize;
+                self.read_map(length)
+            }
+            0xdf => {
+                let length = u32::from_be_bytes(self.read_array_of()?) as usize;
+                self.read_map(length)
+            }
+            0xe0..=0xff => Ok(Value::from(marker as i8)),
+            _ => Err(format!(
+                "The MessagePack format 0x{:02x} at byte {} isn't supported!",
+                marker,
+                self.position - 1
+            )),
+        }
+    }
+
+    /// Reads the next `length` bytes.
+    fn read_bytes(&mut self, length: usize) -> Result<&[u8], String> {
+        let end = self.position.checked_add(length).filter(|end| *end <= self.bytes.len());
+        match end {
+            Some(end) => {
+                let bytes = &self.bytes[self.position..end];
+                self.position = end;
+                Ok(bytes)
+            }
+            None => Err(format!(
+                "The MessagePack value needs {} more bytes after byte {}, but the data ends there!",
+                length, self.position
+            )),
+        }
+    }
+
+    /// Reads the next `N` bytes, e.g. to convert them into a number.
+    fn read_array_of<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    /// Reads a UTF-8 string of `length` bytes.
+    fn read_string(&mut self, length: usize) -> Result<Value, String> {
+        let start = self.position;
+        match std::str::from_utf8(self.read_bytes(length)?) {
+            Ok(string) => Ok(Value::String(string.to_string())),
+            Err(err) => Err(format!("The string at byte {} isn't valid UTF-8! Error: {}", start, err)),
+        }
+    }
+
+    /// Reads an array with `length` elements.
+    fn read_array(&mut self, length: usize) -> Result<Value, String> {
+        // the length is sent by the client, so nothing is reserved up front
+        let mut values = Vec::new();
+        for _ in 0..length {
+            values.push(self.read_value()?);
+        }
+        Ok(Value::Array(values))
+    }
+
+    /// Reads a map with `length` entries, whose keys have to be strings.
+    fn read_map(&mut self, length: usize) -> Result<Value, String> {
+        let mut entries = serde_json::Map::new();
+        for _ in 0..length {
+            let start = self.position;
+            let key = match self.read_value()? {
+                Value::String(key) => key,
+                other => return Err(format!("The map key {} at byte {} isn't a string!", other, start)),
+            };
+            entries.insert(key, self.read_value()?);
+        }
+        Ok(Value::Object(entries))
+    }
+}
+
+/// Converts a float into a JSON number, which can't be `NaN` or infinite.
+fn float_value(number: f64) -> Result<Value, String> {
+    match serde_json::Number::from_f64(number) {
+        Some(number) => Ok(Value::Number(number)),
+        None => Err(format!("The float {} can't be represented in JSON!", number)),
+    }
+}
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::backend::types::game::Game;
+    use serde_json::json;
+
+    #[test]
+    fn a_game_survives_the_round_trip() {
+        let game = Game::new();
+
+        let bytes = encode(&serde_json::to_value(&game).unwrap());
+        let decoded: Game = serde_json::from_value(decode(&bytes).unwrap()).unwrap();
+
+        assert_eq!(decoded, game);
+    }
+
+    #[test]
+    fn every_size_of_a_value_survives_the_round_trip() {
+        let value = json!({
+            "numbers": [0, 127, 128, 65_536, u64::MAX, -1, -33, -40_000, i64::MIN, 1.5],
+            "strings": ["", "x".repeat(31), "x".repeat(255), "x".repeat(70_000)],
+            "long_array": vec![true; 20],
+            "nothing": null,
+        });
+
+        assert_eq!(decode(&encode(&value)).unwrap(), value);
+    }
+
+    #[test]
+    fn incomplete_and_trailing_bytes_are_rejected() {
+        let bytes = encode(&json!({ "name": "Alice" }));
+
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&[bytes.as_slice(), &[0xc0]].concat()).is_err());
+        // binary data has no JSON equivalent
+        assert!(decode(&[0xc4, 0x01, 0x00]).is_err());
+    }
+}
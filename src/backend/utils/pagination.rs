@@ -0,0 +1,135 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
+
+// constants
+
+/// Header containing the number of items of a list across all pages.
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+
+/// Largest number of items a client can request per page.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// A page of a list endpoint, selected with the `offset` and `limit` query parameters.
+///
+/// # Props
+///
+/// - `offset` -> Number of items that are skipped.
+/// - `limit` -> Maximum number of items on the page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Page {
+    /// Number of skipped items
+    pub offset: usize,
+    /// Maximum number of items on the page
+    pub limit: usize,
+}
+
+impl Page {
+    /// Creates a page from the query parameters of a request.
+    ///
+    /// The limit is kept between 1 and `MAX_PAGE_SIZE`, a missing offset starts at the first
+    /// item.
+    ///
+    /// # Arguments
+    ///
+    /// - `offset` -> Requested number of skipped items.
+    /// - `limit` -> Requested page size.
+    /// - `default_limit` -> Page size when the client didn't ask for one.
+    pub fn new(offset: Option<usize>, limit: Option<usize>, default_limit: usize) -> Self {
+        Page {
+            offset: offset.unwrap_or(0),
+            limit: limit.unwrap_or(default_limit).clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    /// Keeps only the items of this page.
+    pub fn slice<T>(&self, items: Vec<T>) -> Vec<T> {
+        items.into_iter().skip(self.offset).take(self.limit).collect()
+    }
+
+    /// Creates the pagination headers of a response with this page.
+    ///
+    /// `X-Total-Count` contains the number of all items. `Link` points to the next and previous
+    /// pages, when they exist. The links are relative to the requested URL and keep all other
+    /// query parameters, like filters, so they work behind any API prefix.
+    ///
+    /// # Arguments
+    ///
+    /// - `total` -> Number of items across all pages.
+    /// - `raw_query` -> Query string of the request without the leading `?`.
+    pub fn headers(&self, total: usize, raw_query: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(total));
+
+        let mut links = Vec::new();
+        if self.offset + self.limit < total {
+            links.push(self.link(raw_query, self.offset + self.limit, "next"));
+        }
+        if self.offset > 0 {
+            links.push(self.link(raw_query, self.offset.saturating_sub(self.limit), "prev"));
+        }
+        if !links.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&links.join(", ")) {
+                headers.insert(header::LINK, value);
+            }
+        }
+
+        headers
+    }
+
+    /// Formats a single entry of the `Link` header, pointing to the page at `offset`.
+    fn link(&self, raw_query: Option<&str>, offset: usize, rel: &str) -> String {
+        let mut params: Vec<String> = raw_query
+            .unwrap_or_default()
+            .split('&')
+            .filter(|param| !param.is_empty() && !param.starts_with("offset=") && !param.starts_with("limit="))
+            .map(str::to_string)
+            .collect();
+        params.push(format!("offset={}", offset));
+        params.push(format!("limit={}", self.limit));
+
+        format!("<?{}>; rel=\"{}\"", params.join("&"), rel)
+    }
+}
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_page_size_stays_within_the_limits() {
+        assert_eq!(Page::new(None, None, 20), Page { offset: 0, limit: 20 });
+        assert_eq!(Page::new(Some(5), Some(0), 20).limit, 1);
+        assert_eq!(Page::new(None, Some(MAX_PAGE_SIZE + 1), 20).limit, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn a_page_only_keeps_its_items() {
+        let page = Page::new(Some(2), Some(2), 20);
+
+        assert_eq!(page.slice(vec![1, 2, 3, 4, 5]), vec![3, 4]);
+        assert!(page.slice(vec![1, 2]).is_empty());
+    }
+
+    #[test]
+    fn the_links_point_to_the_neighbouring_pages_and_keep_the_filters() {
+        let page = Page::new(Some(2), Some(2), 20);
+        let headers = page.headers(5, Some("state=Ended&offset=2&limit=2"));
+
+        assert_eq!(headers[&TOTAL_COUNT_HEADER], "5");
+        assert_eq!(
+            headers[header::LINK],
+            "<?state=Ended&offset=4&limit=2>; rel=\"next\", <?state=Ended&offset=0&limit=2>; rel=\"prev\""
+        );
+    }
+
+    #[test]
+    fn a_single_page_has_no_links() {
+        let headers = Page::new(None, None, 20).headers(3, None);
+
+        assert!(headers.get(header::LINK).is_none());
+    }
+}
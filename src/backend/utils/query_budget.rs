@@ -0,0 +1,192 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+use worker::{D1Database, D1PreparedStatement, D1Result};
+
+// constants
+
+/// Number of D1 queries a request may run, when the worker variables don't configure it, see
+/// `Config`.
+pub const DEFAULT_QUERY_BUDGET: usize = 100;
+
+/// Counts the D1 queries of a single request.
+///
+/// The router is built for every request, so the counter of the `Repositories` starts at zero for
+/// every request. Clones share the same count.
+#[derive(Clone, Debug, Default)]
+pub struct QueryCounter {
+    /// Number of statements executed so far
+    count: Arc<AtomicUsize>,
+}
+
+impl QueryCounter {
+    /// Counts `queries` more queries.
+    pub fn add(&self, queries: usize) {
+        self.count.fetch_add(queries, Ordering::Relaxed);
+    }
+
+    /// Returns the number of queries counted so far.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+/// A D1 database that counts every statement executed on it.
+///
+/// A statement is counted when it runs, not when it is prepared, so every statement of a batch
+/// is counted as well. The `D1Database` itself isn't exposed, so no query can bypass the counter.
+///
+/// # Props
+///
+/// - `db` -> The D1 database the queries run on.
+/// - `counter` -> Counter of the request the database is used for.
+pub struct CountedDatabase {
+    /// The D1 database the queries run on
+    db: D1Database,
+    /// Counter of the request the database is used for
+    counter: QueryCounter,
+}
+
+impl CountedDatabase {
+    /// Creates a database whose queries are added to the `counter`.
+    ///
+    /// # Arguments
+    ///
+    /// - `db` -> The D1 database the queries run on.
+    /// - `counter` -> Counter of the request, see `Repositories::query_counter`.
+    pub fn new(db: D1Database, counter: QueryCounter) -> Self {
+        CountedDatabase { db, counter }
+    }
+
+    /// Prepares a statement whose execution is counted as a query of the request.
+    pub fn prepare<T: Into<String>>(&self, query: T) -> CountedStatement {
+        CountedStatement {
+            statement: self.db.prepare(query),
+            counter: self.counter.clone(),
+        }
+    }
+
+    /// Executes statements in one D1 batch and counts every one of them as a query.
+    ///
+    /// # Arguments
+    ///
+    /// - `statements` -> The prepared and bound statements in the order they should be executed.
+    pub async fn batch(&self, statements: Vec<CountedStatement>) -> worker::Result<Vec<D1Result>> {
+        self.counter.add(statements.len());
+        self.db
+            .batch(statements.into_iter().map(|counted| counted.statement).collect())
+            .await
+    }
+}
+
+impl From<D1Database> for CountedDatabase {
+    /// Counts the queries on their own, e.g. in services outside of a request.
+    fn from(db: D1Database) -> Self {
+        CountedDatabase::new(db, QueryCounter::default())
+    }
+}
+
+/// A prepared D1 statement that is counted as a query of the request when it runs.
+///
+/// Offers the methods of `D1PreparedStatement` the repositories use.
+///
+/// # Props
+///
+/// - `statement` -> The prepared D1 statement.
+/// - `counter` -> Counter of the request the statement runs for.
+pub struct CountedStatement {
+    /// The prepared D1 statement
+    statement: D1PreparedStatement,
+    /// Counter of the request the statement runs for
+    counter: QueryCounter,
+}
+
+impl CountedStatement {
+    /// Binds the values to the parameters of the statement.
+    pub fn bind(self, values: &[JsValue]) -> worker::Result<Self> {
+        Ok(CountedStatement {
+            statement: self.statement.bind(values)?,
+            counter: self.counter,
+        })
+    }
+
+    /// Executes the statement and returns the first row or the column `col_name` of it.
+    pub async fn first<T>(&self, col_name: Option<&str>) -> worker::Result<Option<T>>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        self.counter.add(1);
+        self.statement.first(col_name).await
+    }
+
+    /// Executes the statement and only returns the metadata.
+    pub async fn run(&self) -> worker::Result<D1Result> {
+        self.counter.add(1);
+        self.statement.run().await
+    }
+
+    /// Executes the statement and returns all rows and the metadata.
+    pub async fn all(&self) -> worker::Result<D1Result> {
+        self.counter.add(1);
+        self.statement.all().await
+    }
+}
+
+/// Logs the number of D1 queries a request ran and warns when it ran more than `budget` queries,
+/// so N+1 query patterns show up in the logs.
+///
+/// The response isn't changed, because the handler may already have stored its changes.
+///
+/// # Arguments
+///
+/// - `counter` -> Counter of the request, see `Repositories::query_counter`.
+/// - `budget` -> Maximum number of queries of a request, see `Config`.
+/// - `request` -> The incoming request.
+/// - `next` -> The rest of the middleware stack and the handler.
+pub async fn check_query_budget(
+    counter: QueryCounter,
+    budget: usize,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let response = next.run(request).await;
+
+    let queries = counter.count();
+    if queries > budget {
+        log::warn!(
+            "The request to '{}' ran {} D1 queries, more than the budget of {}!",
+            path, queries, budget
+        );
+    } else {
+        log::debug!("The request to '{}' ran {} D1 queries", path, queries);
+    }
+
+    response
+}
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_of_a_counter_share_the_count_of_the_request() {
+        let counter = QueryCounter::default();
+        let repository_counter = counter.clone();
+
+        repository_counter.add(1);
+        repository_counter.add(3);
+
+        assert_eq!(counter.count(), 4);
+    }
+}
@@ -0,0 +1,127 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::http::HeaderMap;
+
+use crate::backend::{
+    errors::{application_error::ApplicationError, too_many_requests_error::TooManyRequestsError},
+    repositories::rate_limit_repository::RateLimitRepository,
+};
+
+// constants
+
+/// Limit of the create-game endpoint, when the worker variables don't configure it, see `Config`.
+pub const DEFAULT_CREATE_GAME_RATE_LIMIT: RateLimit = RateLimit {
+    max_requests: 5,
+    window_seconds: 60,
+};
+
+/// Header in which Cloudflare passes the IP address of the client to the worker.
+const CLIENT_IP_HEADER: &str = "CF-Connecting-IP";
+
+/// A fixed window rate limit, e.g. 5 requests per 60 seconds.
+///
+/// The time is split into windows of `window_seconds`; a client can send `max_requests` requests
+/// in every window. The counters are stored with the `RateLimitRepository`.
+///
+/// # Props
+///
+/// - `max_requests` -> Number of requests a client can send per window.
+/// - `window_seconds` -> Length of a window in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Number of requests a client can send per window
+    pub max_requests: usize,
+    /// Length of a window in seconds
+    pub window_seconds: i64,
+}
+
+impl RateLimit {
+    /// Counts a request of a client and checks that it's within the limit.
+    ///
+    /// # Arguments
+    ///
+    /// - `rate_limit_repo` -> Repository that stores the counters.
+    /// - `action` -> Name of the limited action, clients are counted separately per action.
+    /// - `client` -> Identifies the client, see `client_key`.
+    ///
+    /// # Returns
+    ///
+    /// 1.) Ok(()), WHEN the request is within the limit.
+    /// 2.) Err(Box<dyn ApplicationError>), a `TooManyRequestsError` (429) WHEN the limit is
+    ///     exceeded, or the error of a failed database operation.
+    pub async fn check(
+        &self,
+        rate_limit_repo: &RateLimitRepository,
+        action: &str,
+        client: &str,
+    ) -> Result<(), Box<dyn ApplicationError>> {
+        let now = chrono::Utc::now().timestamp();
+        let window_start = self.window_start(now);
+
+        let hits = rate_limit_repo
+            .hit(&format!("{}:{}", action, client), window_start)
+            .await?;
+        if hits > self.max_requests {
+            return Err(TooManyRequestsError::new(format!(
+                "Only {} requests to '{}' are allowed every {} seconds, try again in {} seconds!",
+                self.max_requests,
+                action,
+                self.window_seconds,
+                window_start + self.window_seconds - now
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the start of the window the point in time `now` belongs to, in seconds since the
+    /// Unix epoch.
+    fn window_start(&self, now: i64) -> i64 {
+        now - now.rem_euclid(self.window_seconds)
+    }
+}
+
+/// Identifies the client of a request by its IP address.
+///
+/// All requests without the `CF-Connecting-IP` header, e.g. in local development, share one
+/// counter.
+///
+/// # Arguments
+///
+/// - `headers` -> Headers of the request.
+pub fn client_key(headers: &HeaderMap) -> String {
+    headers
+        .get(CLIENT_IP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn requests_of_the_same_window_share_its_start() {
+        let limit = RateLimit { max_requests: 5, window_seconds: 60 };
+
+        assert_eq!(limit.window_start(120), 120);
+        assert_eq!(limit.window_start(179), 120);
+        assert_eq!(limit.window_start(180), 180);
+    }
+
+    #[test]
+    fn clients_are_told_apart_by_their_ip_address() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_key(&headers), "unknown");
+
+        headers.insert(CLIENT_IP_HEADER, HeaderValue::from_static("203.0.113.7"));
+        assert_eq!(client_key(&headers), "203.0.113.7");
+    }
+}
@@ -0,0 +1,118 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use std::sync::Arc;
+
+use worker::Env;
+
+use crate::backend::{
+    errors::application_error::ApplicationError,
+    repositories::{
+        card_repository::CardRepository,
+        chat::{chat_message_repository::ChatMessageRepository, chat_repository::ChatRepository},
+        claim_repository::ClaimsRepository,
+        game_event_repository::GameEventRepository,
+        game_repository::GameRepository,
+        player_repository::PlayerRepository,
+        rate_limit_repository::RateLimitRepository,
+        score_event_repository::ScoreEventRepository,
+    },
+    utils::{
+        database_service::get_database,
+        query_budget::{CountedDatabase, QueryCounter},
+    },
+};
+
+/// Provides the database repositories to the handler functions.
+///
+/// Is injected into the router as `Extension`. A repository is only created when a handler asks
+/// for it, so the handlers don't need to look up the database binding themselves.
+///
+/// # Props
+///
+/// - `env` -> Cloudflare Worker environment containing the database binding.
+/// - `queries` -> Counts the D1 queries of all repositories created by this provider.
+#[derive(Clone)]
+pub struct Repositories {
+    /// Cloudflare Worker environment containing the database binding
+    env: Arc<Env>,
+    /// Counts the D1 queries of all repositories created by this provider
+    queries: QueryCounter,
+}
+
+impl Repositories {
+    /// Creates a new provider for the repositories of the worker environment.
+    ///
+    /// # Arguments
+    ///
+    /// - `env` -> Shared Cloudflare Worker environment.
+    pub fn new(env: Arc<Env>) -> Self {
+        Repositories {
+            env,
+            queries: QueryCounter::default(),
+        }
+    }
+
+    /// Returns the Cloudflare Worker environment, e.g. for services that create their own
+    /// repositories.
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// Returns the counter of the D1 queries the repositories of this provider ran.
+    pub fn query_counter(&self) -> QueryCounter {
+        self.queries.clone()
+    }
+
+    /// Returns the database of the worker environment, counting its queries.
+    fn database(&self) -> Result<CountedDatabase, Box<dyn ApplicationError>> {
+        Ok(CountedDatabase::new(get_database(&self.env)?, self.query_counter()))
+    }
+
+    /// Creates a `GameRepository` for the `games` table.
+    pub fn games(&self) -> Result<GameRepository, Box<dyn ApplicationError>> {
+        Ok(GameRepository::new(self.database()?))
+    }
+
+    /// Creates a `PlayerRepository` for the `players` table.
+    pub fn players(&self) -> Result<PlayerRepository, Box<dyn ApplicationError>> {
+        Ok(PlayerRepository::new(self.database()?))
+    }
+
+    /// Creates a `ClaimsRepository` for the `claims` table.
+    pub fn claims(&self) -> Result<ClaimsRepository, Box<dyn ApplicationError>> {
+        Ok(ClaimsRepository::new(self.database()?))
+    }
+
+    /// Creates a `CardRepository` for the `cards` table.
+    pub fn cards(&self) -> Result<CardRepository, Box<dyn ApplicationError>> {
+        Ok(CardRepository::new(self.database()?))
+    }
+
+    /// Creates a `ChatRepository` for the `chats` table.
+    pub fn chats(&self) -> Result<ChatRepository, Box<dyn ApplicationError>> {
+        Ok(ChatRepository::new(self.database()?))
+    }
+
+    /// Creates a `ChatMessageRepository` for the `chat_messages` table.
+    pub fn chat_messages(&self) -> Result<ChatMessageRepository, Box<dyn ApplicationError>> {
+        Ok(ChatMessageRepository::new(self.database()?))
+    }
+
+    /// Creates a `ScoreEventRepository` for the `score_events` table.
+    pub fn score_events(&self) -> Result<ScoreEventRepository, Box<dyn ApplicationError>> {
+        Ok(ScoreEventRepository::new(self.database()?))
+    }
+
+    /// Creates a `GameEventRepository` for the `game_events` table.
+    pub fn game_events(&self) -> Result<GameEventRepository, Box<dyn ApplicationError>> {
+        Ok(GameEventRepository::new(self.database()?))
+    }
+
+    /// Creates a `RateLimitRepository` for the `rate_limits` table.
+    pub fn rate_limits(&self) -> Result<RateLimitRepository, Box<dyn ApplicationError>> {
+        Ok(RateLimitRepository::new(self.database()?))
+    }
+}
+    }
+}
@@ -0,0 +1,100 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use axum::http::{header, HeaderMap};
+use sha2::{Digest, Sha256};
+
+// constants
+
+/// Number of random bytes of a session token.
+const SESSION_TOKEN_BYTES: usize = 32;
+
+/// Scheme of the `Authorization` header that carries the session token.
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Minimum length of the idempotency key of a join, the session token is derived from it.
+pub const MIN_JOIN_KEY_LENGTH: usize = 32;
+
+/// Prefix of an idempotency key before it's hashed into a session token, so the token differs
+/// from the stored hash of the key.
+const JOIN_KEY_TOKEN_PREFIX: &str = "session-token:";
+
+/// Creates a new secret session token for a player.
+///
+/// The token is handed out once when the player joins a game; only its hash is stored.
+///
+/// # Returns
+///
+/// The token as hex string or `None` when no random bytes were available.
+pub fn generate_session_token() -> Option<String> {
+    let mut bytes = [0u8; SESSION_TOKEN_BYTES];
+    if let Err(err) = getrandom::fill(&mut bytes) {
+        log::error!("Couldn't get random bytes for a new session token! Error: {}", err);
+        return None;
+    }
+
+    Some(to_hex(&bytes))
+}
+
+/// Derives the session token of a player who joined with an idempotency key.
+///
+/// A retried join with the same key gets the same token, so the token of the existing player
+/// doesn't have to be replaced. Only the client knows the key, the database only stores the hash
+/// of the key and the hash of the token.
+///
+/// # Arguments
+///
+/// - `join_key` -> The idempotency key sent with the join, at least `MIN_JOIN_KEY_LENGTH` long.
+pub fn session_token_for_join_key(join_key: &str) -> String {
+    to_hex(&Sha256::digest(format!("{}{}", JOIN_KEY_TOKEN_PREFIX, join_key).as_bytes()))
+}
+
+/// Hashes a session token with SHA-256, so a leaked database doesn't reveal the tokens.
+///
+/// # Arguments
+///
+/// - `session_token` -> The token sent by the client.
+pub fn hash_session_token(session_token: &str) -> String {
+    to_hex(&Sha256::digest(session_token.as_bytes()))
+}
+
+/// Reads the session token from the `Authorization: Bearer <token>` header of a request.
+///
+/// # Arguments
+///
+/// - `headers` -> Headers of the request.
+pub fn session_token_from_headers(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+        .map(str::trim)
+}
+
+/// Encodes bytes as lowercase hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    const JOIN_KEY: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn a_retried_join_gets_the_same_session_token() {
+        assert_eq!(session_token_for_join_key(JOIN_KEY), session_token_for_join_key(JOIN_KEY));
+        assert_ne!(session_token_for_join_key(JOIN_KEY), session_token_for_join_key(&JOIN_KEY.to_uppercase()));
+    }
+
+    #[test]
+    fn the_stored_hash_of_a_join_key_isnt_the_session_token() {
+        let session_token = session_token_for_join_key(JOIN_KEY);
+
+        assert_ne!(hash_session_token(JOIN_KEY), session_token);
+        assert_ne!(hash_session_token(JOIN_KEY), hash_session_token(&session_token));
+    }
+}
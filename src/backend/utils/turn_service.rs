@@ -0,0 +1,143 @@
+use cfg_if::cfg_if;
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+use worker::Env;
+
+use crate::backend::{
+    errors::application_error::ApplicationError,
+    logic::game_logic::{check_winner, newly_finished, next_player_turn, turn_deadline_from, ROUND_WIN_POINTS},
+    repositories::{
+        card_repository::CardRepository, claim_repository::ClaimsRepository,
+        game_repository::GameRepository, player_repository::PlayerRepository,
+        score_event_repository::ScoreEventRepository,
+    },
+    types::{player::Player, score_event::ScoreEvent},
+    utils::{config::Config, database_service::get_database},
+};
+
+/// Passes the turn on to the next player in every running game whose turn deadline has passed
+/// or whose last claim wasn't challenged in time.
+///
+/// Used by the scheduled Cron trigger for all games and by the `tick` endpoint for a single game.
+///
+/// # Arguments
+///
+/// - `env` -> Cloudflare Worker environment containing the database binding.
+/// - `config` -> Configuration of the worker, provides the length of a turn.
+/// - `game_id` -> Optional id to only advance the turn of a single game.
+///
+/// # Returns
+///
+/// 1.) Ok(usize), the number of games whose turn was advanced.
+/// 2.) Err(Box<dyn ApplicationError>), WHEN a database operation failed.
+pub async fn advance_expired_turns(
+    env: &Env,
+    config: &Config,
+    game_id: Option<&str>,
+) -> Result<usize, Box<dyn ApplicationError>> {
+    let game_repo = GameRepository::new(get_database(env)?);
+    let player_repo = PlayerRepository::new(get_database(env)?);
+    let card_repo = CardRepository::new(get_database(env)?);
+    let claim_repo = ClaimsRepository::new(get_database(env)?);
+    let score_event_repo = ScoreEventRepository::new(get_database(env)?);
+
+    let now = chrono::Utc::now();
+    let mut expired_turns = game_repo.get_expired_turns(&now.to_string(), game_id).await?;
+    for expired_window in game_repo.get_expired_challenge_windows(&now.to_string(), game_id).await? {
+        if !expired_turns.iter().any(|(id, _)| *id == expired_window.0) {
+            expired_turns.push(expired_window);
+        }
+    }
+    let deadline = turn_deadline_from(now, config.turn_duration_seconds);
+
+    let mut advanced_games = 0;
+    for (expired_game_id, current_player_id) in expired_turns {
+        let mut players = player_repo
+            .get_all_players(Some(&expired_game_id), &card_repo)
+            .await?;
+
+        // the last claim stands, so its creator may have emptied their hand
+        if award_finishing_positions(&game_repo, &player_repo, &claim_repo, &score_event_repo, &expired_game_id, &mut players).await? {
+            continue;
+        }
+
+        if let Some(next_player) = next_player_turn(&players, &current_player_id) {
+            game_repo
+                .set_turn(&expired_game_id, &next_player.id, &deadline)
+                .await?;
+            advanced_games += 1;
+        }
+    }
+
+    Ok(advanced_games)
+}
+
+/// Gives the players who emptied their hand their finishing position, see `newly_finished`.
+///
+/// The positions are also set on the passed players, so the turn can be passed on right away.
+/// The player who gets the first position wins the round and gets `ROUND_WIN_POINTS`, see
+/// `check_winner`. Once only one player is left in the round, they get the last position and
+/// the game ends.
+///
+/// # Arguments
+///
+/// - `game_repo` -> Repository to end the game.
+/// - `player_repo` -> Repository to store the positions.
+/// - `claim_repo` -> Repository to look up who placed the last claim.
+/// - `score_event_repo` -> Repository to score the winner of the round.
+/// - `game_id` -> Identifier of the game.
+/// - `players` -> All players of the game with their cards in their seating order.
+///
+/// # Returns
+///
+/// 1.) Ok(bool), whether the game ended.
+/// 2.) Err(Box<dyn ApplicationError>), WHEN a database operation failed.
+pub async fn award_finishing_positions(
+    game_repo: &GameRepository,
+    player_repo: &PlayerRepository,
+    claim_repo: &ClaimsRepository,
+    score_event_repo: &ScoreEventRepository,
+    game_id: &str,
+    players: &mut [Player],
+) -> Result<bool, Box<dyn ApplicationError>> {
+    let last_claim_by = match claim_repo.get_latest_claim_id(game_id).await? {
+        Some(claim_id) => Some(claim_repo.get_claim_by_id(claim_id).await?.created_by),
+        None => None,
+    };
+
+    let winner_id = check_winner(players, last_claim_by.as_deref()).map(|player| player.id.clone());
+    let finished_ids: Vec<String> = newly_finished(players, last_claim_by.as_deref())
+        .into_iter()
+        .map(|player| player.id.clone())
+        .collect();
+    for player_id in &finished_ids {
+        let position = player_repo.finish_player(player_id, game_id).await?;
+        // the first position is only handed out once, so the round is scored once
+        if position == Some(1) && winner_id.as_ref() == Some(player_id) {
+            score_event_repo
+                .record_score_event(ScoreEvent::new(
+                    game_id.to_string(),
+                    player_id.clone(),
+                    game_repo.get_round_number(game_id).await?,
+                    ROUND_WIN_POINTS,
+                    "won the round".to_string(),
+                ))
+                .await?;
+        }
+        if let Some(player) = players.iter_mut().find(|player| player.id == *player_id) {
+            player.finished_position = position;
+        }
+    }
+
+    let round_over = players
+        .iter()
+        .filter(|player| !player.has_forfeited())
+        .all(|player| player.finished_position.is_some());
+    if finished_ids.is_empty() || !round_over {
+        return Ok(false);
+    }
+
+    game_repo.end_game(game_id).await
+}
+    }
+}
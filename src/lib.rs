@@ -9,6 +9,7 @@
 
 mod app;
 mod backend;
+pub mod shared;
 mod ui;
 
 use leptos::*;
@@ -25,8 +26,10 @@ async fn fetch(
 ) -> Result<axum::http::Response<axum::body::Body>> {
     use crate::app::App;
     use crate::backend::router::router_provider;
+    use crate::backend::utils::database_service::check_schema_version;
+    use axum::response::IntoResponse;
     use leptos::config::LeptosOptions;
-    use log::debug;
+    use log::{debug, error};
     use std::env;
     use std::sync::Arc;
     use tower_service::Service;
@@ -60,6 +63,13 @@ async fn fetch(
     //     worker::Error::RustError("DB binding not found".to_string())
     // })?;
 
+    // an outdated schema would only show up as missing columns in the middle of a request; the
+    // version is only read from the database once per worker instance
+    if let Err(err) = check_schema_version(&env).await {
+        error!("{}", err);
+        return Ok(err.into_response());
+    }
+
     debug!("Server is running on port http://localhost:3000/");
     Ok(router_provider::router(
         env,
@@ -72,6 +82,19 @@ async fn fetch(
     .await?)
 }
 
+#[event(scheduled)]
+#[cfg(feature = "ssr")]
+async fn scheduled(_event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    use crate::backend::utils::{config::Config, turn_service::advance_expired_turns};
+    use log::{debug, error};
+
+    // pass the turn on in all games where the current player missed the deadline
+    match advance_expired_turns(&env, &Config::load(&env), None).await {
+        Ok(advanced_games) => debug!("Advanced the turn of {} game(s)", advanced_games),
+        Err(err) => error!("Failed to advance expired turns: {}", err),
+    }
+}
+
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn hydrate() {
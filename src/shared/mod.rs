@@ -0,0 +1,20 @@
+// Types that are sent between the server and the client.
+//
+// They don't depend on the 'ssr' feature, so the client can deserialize the responses of the API
+// in the 'hydrate' build without referencing the repositories and handlers, which only exist on
+// the worker.
+
+pub use crate::backend::{
+    enums::{
+        card_types::CardType, claim_outcome::ClaimOutcome, game_phase::GamePhase,
+        game_state::GameState, player_color::PlayerColor,
+    },
+    types::{
+        card::Card,
+        chat::{Chat, ChatMessage, ReactionCount},
+        claim::Claim,
+        game::Game,
+        game_view::{ClaimView, GameView, PlayerView},
+        player::Player,
+    },
+};